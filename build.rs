@@ -0,0 +1,33 @@
+//! With the `bundled-lists` feature, copy the word lists into the build so `loader::load_bundled`
+//! can include them. The lists aren't checked in (see "Word lists" in the README), so if they're
+//! missing, the build goes ahead without them and says so, rather than failing.
+
+use std::path::Path;
+
+const LISTS: [&str; 2] = ["guessable.txt", "solutions.txt"];
+
+fn main() {
+    println!("cargo::rustc-check-cfg=cfg(bundled_lists)");
+    println!("cargo::rerun-if-env-changed=WORDLE_SOLVER_LISTS_DIR");
+    if std::env::var_os("CARGO_FEATURE_BUNDLED_LISTS").is_none() {
+        return;
+    }
+
+    let dir = std::env::var("WORDLE_SOLVER_LISTS_DIR").unwrap_or_else(|_| "lists".to_string());
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    for name in LISTS {
+        let path = Path::new(&dir).join(name);
+        println!("cargo::rerun-if-changed={}", path.display());
+        if let Err(e) = std::fs::copy(&path, Path::new(&out_dir).join(name)) {
+            println!(
+                "cargo::warning=bundled-lists: can't read {} ({}); building without bundled \
+                 lists. Put guessable.txt and solutions.txt in lists/, or in \
+                 $WORDLE_SOLVER_LISTS_DIR.",
+                path.display(),
+                e
+            );
+            return;
+        }
+    }
+    println!("cargo::rustc-cfg=bundled_lists");
+}
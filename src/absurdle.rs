@@ -0,0 +1,1168 @@
+//! Solvers for [Absurdle](https://qntm.org/files/absurdle/absurdle.html), the adversarial Wordle
+//! variant: instead of picking an answer up front, the host responds to each guess by keeping the
+//! largest group of possibilities it can. In challenge mode, the player is given a target word
+//! and has to force the host into it; `Solver` searches for a way to do that. `ShortestSearch`
+//! finds the fewest guesses that win, in either mode.
+
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::time::{Duration, Instant};
+
+use crate::constraints::Constraints;
+use crate::list_cache;
+use crate::score::{DetailScore, ScoringRules, NUM_POSSIBLE_SCORES};
+
+/// The most guesses `ShortestSearch::first_win` looks for a win within.
+pub const MAX_SHORTEST_GUESSES: usize = 10;
+
+/// How much work a search did.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SearchStats {
+    /// States whose next guesses were considered.
+    pub nodes: usize,
+
+    /// Guesses that turned out to be dead ends.
+    pub backtracks: usize,
+
+    /// The most guesses in any sequence tried.
+    pub deepest: usize,
+}
+
+/// Why `Solver::solve` didn't find a solution.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SolveError {
+    /// Every sequence of guesses the solver tried eliminates the target word at some point.
+    NoSolution,
+
+    /// No sequence within this many guesses, the solver's depth limit, forces the target word.
+    DepthLimit(usize),
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolveError::NoSolution => write!(f, "Total failure!"),
+            SolveError::DepthLimit(max) => write!(f, "No solution within {} guesses", max),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+/// Something that happened during `Solver::solve`, for showing its progress.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SearchEvent<'e> {
+    /// The search is trying this sequence of guesses.
+    Trying(&'e [(&'e str, DetailScore)]),
+
+    /// The sequence can be continued, so the search will try longer ones starting with it.
+    Continued,
+
+    /// The sequence is a dead end, so the search backtracks.
+    DeadEnd,
+
+    /// The target word is the only possibility left after the sequence, so guessing it wins.
+    Won,
+}
+
+/// Why `Solver::solve` found no solution, as far as the search can tell. See
+/// `Solver::infeasibility`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Infeasibility<'a> {
+    pub target: &'a str,
+    pub hard_mode: bool,
+
+    /// The most guesses in any sequence the search tried.
+    pub depth_reached: usize,
+
+    /// The longest sequence of moves after which no allowed guess keeps the target, if any. A
+    /// search that only hit its depth limit has none.
+    pub dead_end: Option<Vec<(&'a str, DetailScore)>>,
+
+    /// After `dead_end`, how many guesses are allowed (in hard mode, the ones that fit the
+    /// scores so far), and how many possibilities are left.
+    pub allowed_guesses: usize,
+    pub possibilities_left: usize,
+
+    /// Whether no first guess at all keeps the target, so that no sequence can force it. A
+    /// failure later on doesn't prove anything, since the search only tries a shortlist of
+    /// guesses at each step.
+    pub provably_unreachable: bool,
+}
+
+/// "1 guess", "2 guesses", etc.
+fn count_guesses(n: usize) -> String {
+    format!("{} guess{}", n, if n == 1 { "" } else { "es" })
+}
+
+impl Infeasibility<'_> {
+    /// The explanation as text, a line per fact.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if let Some(ref dead_end) = self.dead_end {
+            let guesses: Vec<&str> = dead_end.iter().map(|(guess, _)| *guess).collect();
+            lines.push(match guesses.len() {
+                0 => "Dead end from the start".to_string(),
+                n => format!(
+                    "Deepest dead end, after {}: {}",
+                    count_guesses(n),
+                    guesses.join(" ")
+                ),
+            });
+            if self.hard_mode && !dead_end.is_empty() {
+                lines.push(format!(
+                    "  Hard mode constraints: {}",
+                    Constraints::from_history(dead_end)
+                ));
+            }
+            lines.push(format!(
+                "  {} allowed guesses, {} possibilities left; Absurdle drops {} after every one",
+                self.allowed_guesses, self.possibilities_left, self.target
+            ));
+        }
+        lines.push(format!(
+            "Search exhausted at a depth of {}",
+            count_guesses(self.depth_reached)
+        ));
+        lines.push(if self.provably_unreachable {
+            format!("{} is unreachable: no first guess keeps it", self.target)
+        } else {
+            format!(
+                "{} isn't proven unreachable: only a shortlist of guesses was tried at each step",
+                self.target
+            )
+        });
+        lines
+    }
+}
+
+/// How the host picks the score for a guess, which decides the group of possibilities it keeps.
+/// Absurdle's is the default; other adversarial Wordle clones pick differently. Every policy has
+/// to respond the same way to the same guess and possibilities, so that searches can rely on it.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum HostPolicy {
+    /// Keep the largest group, breaking ties by the lowest `absurdle_entropy_lost`.
+    #[default]
+    Absurdle,
+
+    /// Keep one of the largest groups, picked at random. The choice is a hash of this seed, the
+    /// guess and the sizes of the groups, so it's the same every time the same situation comes
+    /// up, in any build (see `list_cache::hash`).
+    Random(u64),
+}
+
+impl argparse::FromCommandLine for HostPolicy {
+    fn from_argument(s: &str) -> Result<Self, String> {
+        match s.split_once(':') {
+            None if s == "absurdle" => Ok(Self::Absurdle),
+            None if s == "random" => Ok(Self::Random(0)),
+            Some(("random", seed)) => seed
+                .parse()
+                .map(Self::Random)
+                .map_err(|_| format!("invalid seed '{}'", seed)),
+            _ => Err("host policies are 'absurdle', 'random' and 'random:<seed>'".to_string()),
+        }
+    }
+}
+
+impl Display for HostPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HostPolicy::Absurdle => write!(f, "absurdle"),
+            HostPolicy::Random(seed) => write!(f, "random:{}", seed),
+        }
+    }
+}
+
+impl HostPolicy {
+    /// The score given to `guess` under `rules` when these are the possibilities left, and the
+    /// ones that score keeps. There must be at least one possibility.
+    pub fn host_score<'p>(
+        &self,
+        guess: &str,
+        possibilities: &[&'p str],
+        rules: ScoringRules,
+    ) -> (DetailScore, Vec<&'p str>) {
+        let (score, _) = self.host_choice(guess, possibilities, rules);
+        let retained = possibilities
+            .iter()
+            .copied()
+            .filter(|possibility| rules.is_consistent(guess, score, possibility))
+            .collect();
+        (score, retained)
+    }
+
+    /// Like `host_score`, but only counts the possibilities kept, which is quicker.
+    fn host_choice(
+        &self,
+        guess: &str,
+        possibilities: &[&str],
+        rules: ScoringRules,
+    ) -> (DetailScore, usize) {
+        let mut counts = [0; NUM_POSSIBLE_SCORES as usize];
+        for score in rules.compute_scores(guess, possibilities) {
+            counts[score.index() as usize] += 1;
+        }
+        let groups = counts
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(index, count)| (DetailScore::from_index(index as u8).unwrap(), *count));
+
+        match self {
+            HostPolicy::Absurdle => groups
+                .max_by(|(a, a_count), (b, b_count)| {
+                    a_count
+                        .cmp(b_count)
+                        .then(b.absurdle_entropy_lost().cmp(&a.absurdle_entropy_lost()))
+                })
+                .unwrap(),
+            HostPolicy::Random(seed) => {
+                let largest = *counts.iter().max().unwrap();
+                let mut data = seed.to_le_bytes().to_vec();
+                data.extend_from_slice(guess.as_bytes());
+                for count in counts {
+                    data.extend_from_slice(&(count as u32).to_le_bytes());
+                }
+                let ties = counts.iter().filter(|count| **count == largest).count();
+                // The high bits, because FNV's low bits hardly mix: the lowest is just a parity.
+                let pick = ((list_cache::hash(&data) >> 32) % ties as u64) as usize;
+                groups
+                    .filter(|(_, count)| *count == largest)
+                    .nth(pick)
+                    .unwrap()
+            }
+        }
+    }
+}
+
+/// The score Absurdle gives `guess` when these are the possibilities left, and the ones that
+/// score keeps. Absurdle keeps the largest group of possibilities that would all give the guess
+/// the same score, breaking ties by the lowest `absurdle_entropy_lost`. There must be at least one
+/// possibility. See `HostPolicy` for other hosts.
+pub fn host_score<'p>(guess: &str, possibilities: &[&'p str]) -> (DetailScore, Vec<&'p str>) {
+    HostPolicy::Absurdle.host_score(guess, possibilities, ScoringRules::Wordle)
+}
+
+/// A callback for `Solver::solve`'s progress. See `Solver::set_progress`.
+type Progress<'a> = Box<dyn FnMut(SearchEvent) + 'a>;
+
+/// A callback that saves `Solver::solve`'s stack. See `Solver::set_checkpoint`.
+type SaveStack<'a> = Box<dyn FnMut(&[Vec<&str>]) + 'a>;
+
+/// A callback that shows how `Solver::solve` is doing. See `Solver::set_status`.
+type ShowStatus<'a> = Box<dyn FnMut(SearchStats, &[(&str, DetailScore)]) + 'a>;
+
+/// A callback for `Solver::solve` to call every so often.
+struct Periodic<F> {
+    interval: Duration,
+    last_called: Instant,
+    callback: F,
+}
+
+impl<F> Periodic<F> {
+    fn new(interval: Duration, callback: F) -> Self {
+        Periodic {
+            interval,
+            last_called: Instant::now(),
+            callback,
+        }
+    }
+
+    /// Whether it's time to call the callback again. If so, the interval starts over.
+    fn due(&mut self) -> bool {
+        let due = self.last_called.elapsed() >= self.interval;
+        if due {
+            self.last_called = Instant::now();
+        }
+        due
+    }
+}
+
+/// What `Solver::solve` remembers a search state by: everything that decides which guesses can
+/// follow. Two states with the same key have the same future, so a dead end for one is a dead end
+/// for the other.
+#[derive(Eq, Hash, PartialEq)]
+struct StateKey<'a> {
+    /// The possibilities left, sorted.
+    possibilities: Vec<&'a str>,
+
+    /// With a depth limit, how many guesses were made.
+    depth: Option<usize>,
+
+    /// In hard mode, the guesses made and their scores, which constrain later guesses, sorted.
+    history: Vec<(&'a str, DetailScore)>,
+}
+
+/// A solver for challenge mode: finds a sequence of guesses that forces Absurdle to the target
+/// word, by a backtracking search over the guesses `next_guesses` suggests.
+pub struct Solver<'a> {
+    target_word: &'a str,
+    possibilities: Vec<&'a str>,
+    history: Vec<(&'a str, DetailScore)>,
+    guessable_list: &'a [String],
+    solutions_list: &'a [String],
+    hard_mode: bool,
+
+    /// Give up on sequences longer than this many guesses, counting the target word.
+    max_depth: Option<usize>,
+
+    host_policy: HostPolicy,
+    rules: ScoringRules,
+    progress: Option<Progress<'a>>,
+    stats: SearchStats,
+
+    /// For explaining a failure: the most guesses in any sequence the last `solve` tried, and
+    /// the longest sequence it found no allowed guess after.
+    depth_reached: usize,
+    deepest_dead_end: Option<Vec<(&'a str, DetailScore)>>,
+
+    checkpointer: Option<Periodic<SaveStack<'a>>>,
+    status: Option<Periodic<ShowStatus<'a>>>,
+
+    /// A stack saved by an earlier search, for the next `solve` to start from.
+    resume_stack: Option<Vec<Vec<&'a str>>>,
+}
+
+impl<'a> Solver<'a> {
+    pub fn new(
+        target_word: &'a str,
+        guessable_list: &'a [String],
+        solutions_list: &'a [String],
+        hard_mode: bool,
+    ) -> Self {
+        Solver {
+            target_word,
+            guessable_list,
+            solutions_list,
+            history: Vec::new(),
+            hard_mode,
+            max_depth: None,
+            host_policy: HostPolicy::default(),
+            rules: ScoringRules::Wordle,
+            progress: None,
+            stats: SearchStats::default(),
+            depth_reached: 0,
+            deepest_dead_end: None,
+            checkpointer: None,
+            status: None,
+            resume_stack: None,
+            possibilities: Vec::from_iter(solutions_list.iter().map(|s| s.as_str())),
+        }
+    }
+
+    /// Make `solve` give up on sequences of more than this many guesses, counting the target
+    /// word.
+    pub fn set_max_depth(&mut self, max_depth: Option<usize>) {
+        self.max_depth = max_depth;
+    }
+
+    /// Search against a host that picks scores this way, instead of Absurdle's.
+    pub fn set_host_policy(&mut self, host_policy: HostPolicy) {
+        self.host_policy = host_policy;
+    }
+
+    /// Use scoring rules other than Wordle's, for adversarial clones that score that way.
+    pub fn set_scoring_rules(&mut self, rules: ScoringRules) {
+        self.rules = rules;
+    }
+
+    /// Call this with each step of `solve`'s search, e.g. to show its progress.
+    pub fn set_progress(&mut self, progress: impl FnMut(SearchEvent) + 'a) {
+        self.progress = Some(Box::new(progress));
+    }
+
+    /// Call `save` with `solve`'s stack of guesses at most once per `interval`, so that a long
+    /// search can be picked up again with `resume` if it's interrupted. Each level of the stack
+    /// is the guesses left to try at that step, from worst to best; the last one is being tried.
+    pub fn set_checkpoint(&mut self, interval: Duration, save: impl FnMut(&[Vec<&str>]) + 'a) {
+        self.checkpointer = Some(Periodic::new(interval, Box::new(save)));
+    }
+
+    /// Call `show` at most once per `interval` during `solve`, with the search's stats so far and
+    /// the sequence of guesses it's trying, e.g. to show a status line.
+    pub fn set_status(
+        &mut self,
+        interval: Duration,
+        show: impl FnMut(SearchStats, &[(&str, DetailScore)]) + 'a,
+    ) {
+        self.status = Some(Periodic::new(interval, Box::new(show)));
+    }
+
+    /// Make the next `solve` start from a stack saved by `set_checkpoint`'s callback, instead of
+    /// from scratch. The dead ends it had found aren't saved, so it may search some again.
+    pub fn resume(&mut self, stack: &[Vec<String>]) -> Result<(), String> {
+        let words: HashMap<&str, &'a str> = self
+            .guessable_list
+            .iter()
+            .chain(self.solutions_list.iter())
+            .map(|word| (word.as_str(), word.as_str()))
+            .collect();
+        let stack = stack
+            .iter()
+            .map(|guesses| {
+                if guesses.is_empty() {
+                    return Err("a step has no guesses left".to_string());
+                }
+                guesses
+                    .iter()
+                    .map(|guess| {
+                        words
+                            .get(guess.as_str())
+                            .copied()
+                            .ok_or_else(|| format!("'{}' isn't in the word lists", guess))
+                    })
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+        self.resume_stack = Some(stack);
+        Ok(())
+    }
+
+    /// How much searching `solve` has done so far, over every call.
+    pub fn stats(&self) -> SearchStats {
+        self.stats
+    }
+
+    pub fn get_possibilities(&self) -> &[&'a str] {
+        &self.possibilities
+    }
+
+    pub fn history(&self) -> &[(&'a str, DetailScore)] {
+        &self.history
+    }
+
+    /// Why the last `solve` found no solution.
+    pub fn infeasibility(&self) -> Infeasibility<'a> {
+        let dead_end = self.deepest_dead_end.clone();
+        let history = dead_end.clone().unwrap_or_default();
+        let allowed_guesses = self
+            .guessable_list
+            .iter()
+            .chain(self.solutions_list.iter())
+            .filter(|guess| *guess != self.target_word)
+            .filter(|guess| !self.hard_mode || self.rules.is_hard_mode_legal(&history, guess))
+            .count();
+        let possibilities_left = self
+            .solutions_list
+            .iter()
+            .filter(|word| {
+                history
+                    .iter()
+                    .all(|(guess, score)| self.rules.is_consistent(guess, *score, word))
+            })
+            .count();
+        Infeasibility {
+            target: self.target_word,
+            hard_mode: self.hard_mode,
+            depth_reached: self.depth_reached,
+            provably_unreachable: history.is_empty() && dead_end.is_some(),
+            dead_end,
+            allowed_guesses,
+            possibilities_left,
+        }
+    }
+
+    fn report(&mut self, event: SearchEvent) {
+        if let Some(ref mut progress) = self.progress {
+            progress(event);
+        }
+    }
+
+    /// Guesses that could come next: the ones Absurdle would respond to by keeping the target
+    /// word, and of those, the ones that eliminate the most possibilities, with fallbacks that
+    /// eliminate fewer. They are sorted in order from worst to best. If the target word is the
+    /// only possibility left, it's the only guess.
+    pub fn next_guesses(&self) -> Vec<&'a str> {
+        if self.possibilities.len() == 1 {
+            return self.possibilities.clone();
+        }
+
+        // When you enter a guess in Absurdle challenge mode, it iterates through all possible
+        // scores, and returns the one that eliminates the fewest remaining possibilities. Ties
+        // are broken by Absurdle's "entropyLost" metric, calculated in score.rs. (Other hosts
+        // choose differently; see `HostPolicy`.)
+        //
+        // To pick a guess, therefore, we iterate through all possible guesses, and:
+        //   1. Compute the score that Absurdle would give for that guess
+        //   2. Compute how many possibilities are eliminated by that score
+        //   3. Make sure that score wouldn't eliminate the target word as a possibility
+        //
+        // Return the guess that had the highest number in step 2 and passed step 3.
+        //
+        // In other words: pick the guess for which the host's score is as informative as
+        // possible.
+
+        let mut guesses: Vec<&str> = Vec::new();
+        let mut eliminated_by_best_guess = 0;
+
+        for guess in self.guessable_list.iter().chain(self.solutions_list.iter()) {
+            // Don't guess the target word. (The winning move is covered by the len == 1 case).
+            if guess == self.target_word {
+                continue;
+            }
+
+            // Follow the hard mode rule: can't make a guess that is ruled out by previous scores.
+            if self.hard_mode && !self.rules.is_hard_mode_legal(&self.history, guess) {
+                continue;
+            }
+
+            let (score, kept) =
+                self.host_policy
+                    .host_choice(guess, &self.possibilities, self.rules);
+            let eliminated = self.possibilities.len() - kept;
+
+            // Skip guesses that eliminate fewer than one already found, ones that eliminate
+            // nothing (which would leave the search where it was, forever), and ones whose score
+            // eliminates the target word.
+            if eliminated < eliminated_by_best_guess.max(1)
+                || self.rules.compute_score(guess, self.target_word) != score
+            {
+                continue;
+            }
+
+            // In the normal version of the solver, we clear the guesses vec when a better guess
+            // comes along, because the guesses in the vec are known to be non-optimal. But here,
+            // it's possible that none of the optimal guesses will work out, and we'll need to fall
+            // back to worse ones. The vec will naturally be sorted in increasing order of
+            // possibilities eliminated, because any subsequently evaluated guesses that eliminate
+            // fewer than this one will be skipped, above.
+            eliminated_by_best_guess = eliminated;
+            guesses.push(guess);
+        }
+
+        guesses
+    }
+
+    /// Record that `guess` got `score`, and keep only the possibilities that fit it. Fails, and
+    /// leaves the solver as it was, if no possibility fits, e.g. because the score was mistyped.
+    pub fn respond_to_score(&mut self, guess: &'a str, score: DetailScore) -> Result<(), String> {
+        if !self
+            .possibilities
+            .iter()
+            .any(|poss| self.rules.is_consistent(guess, score, poss))
+        {
+            return Err(format!("No possibilities fit {} scored {}", guess, score));
+        }
+        self.narrow(guess, score);
+        Ok(())
+    }
+
+    /// Like `respond_to_score`, for a score the target word gave, which it always fits.
+    fn narrow(&mut self, guess: &'a str, score: DetailScore) {
+        self.possibilities
+            .retain(|poss| self.rules.is_consistent(guess, score, poss));
+        self.history.push((guess, score));
+    }
+
+    /// The key of the current search state. See `StateKey`.
+    fn state_key(&self) -> StateKey<'a> {
+        let mut possibilities = self.possibilities.clone();
+        possibilities.sort_unstable();
+        let mut history = Vec::new();
+        if self.hard_mode {
+            history = self.history.clone();
+            history.sort_unstable();
+        }
+        StateKey {
+            possibilities,
+            depth: self.max_depth.map(|_| self.history.len()),
+            history,
+        }
+    }
+
+    /// Search for a sequence of guesses that forces the target word, starting from scratch.
+    /// Returns the guesses and their scores, ending with the target word.
+    pub fn solve(&mut self) -> Result<Vec<(&'a str, DetailScore)>, SolveError> {
+        // The approach is to keep a stack of possible guesses at each step. We will repeatedly
+        // test a sequence of guesses consisting of the last one from each level of the stack. If
+        // this leads us to a dead end (no possible guesses left that don't eliminate the target
+        // word), we backtrack by popping a guess from the last level and trying again. We will
+        // maintain the invariant that every level of this stack is a nonempty vec.
+        let mut stack: Vec<Vec<&str>> = Vec::new();
+
+        // The state (see `state_key`) each level of the stack was generated in.
+        let mut stack_states: Vec<StateKey> = Vec::new();
+
+        // States known to be dead ends. Different orders of the same guesses lead to the same
+        // state, so this saves searching it again.
+        let mut dead_states: HashSet<StateKey> = HashSet::new();
+
+        let failure = match self.max_depth {
+            Some(max) => SolveError::DepthLimit(max),
+            None => SolveError::NoSolution,
+        };
+
+        self.history.clear();
+        self.possibilities = Vec::from_iter(self.solutions_list.iter().map(|s| s.as_str()));
+        self.depth_reached = 0;
+        self.deepest_dead_end = None;
+        if let Some(ref mut checkpointer) = self.checkpointer {
+            checkpointer.last_called = Instant::now();
+        }
+        if let Some(ref mut status) = self.status {
+            status.last_called = Instant::now();
+        }
+
+        // Pick up where an earlier search left off.
+        if let Some(resumed) = self.resume_stack.take() {
+            for guesses in resumed {
+                stack_states.push(self.state_key());
+                let guess = *guesses.last().unwrap();
+                self.narrow(guess, self.rules.compute_score(guess, self.target_word));
+                stack.push(guesses);
+            }
+            if let Some(ref mut progress) = self.progress {
+                progress(SearchEvent::Trying(&self.history));
+            }
+        }
+
+        loop {
+            let state = self.state_key();
+            let known_dead = dead_states.contains(&state);
+            self.depth_reached = self.depth_reached.max(self.history.len());
+            self.stats.deepest = self.stats.deepest.max(self.history.len());
+            let next_guesses = if known_dead {
+                Vec::new()
+            } else {
+                self.stats.nodes += 1;
+                self.next_guesses()
+            };
+            let won = next_guesses.len() == 1 && *next_guesses.first().unwrap() == self.target_word;
+
+            // With only the target word's guess left, anything but a win is a dead end.
+            let out_of_depth = self
+                .max_depth
+                .is_some_and(|max| self.history.len() + 1 >= max);
+
+            if next_guesses.is_empty() || (out_of_depth && !won) {
+                // No way to proceed. Backtrack.
+                self.report(SearchEvent::DeadEnd);
+                self.stats.backtracks += 1;
+                let deeper = |d: &Vec<_>| self.history.len() > d.len();
+                if next_guesses.is_empty()
+                    && !known_dead
+                    && self.deepest_dead_end.as_ref().is_none_or(deeper)
+                {
+                    self.deepest_dead_end = Some(self.history.clone());
+                }
+                dead_states.insert(state);
+
+                // The last guess, at least, led us to a loss. Drop it.
+                let Some(last) = stack.last_mut() else {
+                    return Err(failure);
+                };
+                last.pop();
+
+                // Restore the invariant that every level of the stack is a nonempty vector. If
+                // that pop made the last level empty, pop that whole level and drop the last guess
+                // of the previous level. Every guess from that level's state failed, so that
+                // state is a dead end too.
+                while stack.last().unwrap().is_empty() {
+                    stack.pop();
+                    dead_states.insert(stack_states.pop().unwrap());
+                    if stack.is_empty() {
+                        return Err(failure);
+                    }
+                    stack.last_mut().unwrap().pop();
+                }
+            } else if won {
+                self.report(SearchEvent::Won);
+                let mut moves = self.history.clone();
+                moves.push((
+                    self.target_word,
+                    self.rules.compute_score(self.target_word, self.target_word),
+                ));
+                return Ok(moves);
+            } else {
+                // Neither a win nor a loss. Add this set of guesses to the stack and keep going.
+                stack.push(next_guesses);
+                stack_states.push(state);
+                self.report(SearchEvent::Continued);
+            }
+
+            // Reconstruct the state for the current stack of guesses.
+            self.history.clear();
+            self.possibilities = Vec::from_iter(self.solutions_list.iter().map(|s| s.as_str()));
+
+            for guesses in stack.iter() {
+                let best_guess = *guesses.last().unwrap();
+                let score = self.rules.compute_score(best_guess, self.target_word);
+                self.narrow(best_guess, score);
+            }
+            if let Some(ref mut progress) = self.progress {
+                progress(SearchEvent::Trying(&self.history));
+            }
+            if let Some(ref mut checkpointer) = self.checkpointer {
+                if checkpointer.due() {
+                    (checkpointer.callback)(&stack);
+                }
+            }
+            if let Some(ref mut status) = self.status {
+                if status.due() {
+                    (status.callback)(self.stats, &self.history);
+                }
+            }
+        }
+    }
+}
+
+/// A search for the fewest guesses that win: in regular Absurdle, where there's no target word,
+/// or in challenge mode, forcing the target. A guess wins once it's the only possibility left.
+/// Absurdle's responses are deterministic, so a win is a single sequence of guesses rather than a
+/// tree.
+pub struct ShortestSearch<'a> {
+    words: Vec<&'a str>,
+    hard_mode: bool,
+
+    /// In challenge mode, the word that has to stay a possibility.
+    target: Option<&'a str>,
+
+    /// How many guesses to try at each step, if limited: the ones that leave the fewest
+    /// possibilities.
+    candidate_limit: Option<usize>,
+
+    host_policy: HostPolicy,
+    rules: ScoringRules,
+    stats: Cell<SearchStats>,
+}
+
+impl<'a> ShortestSearch<'a> {
+    pub fn new(
+        guessable_list: &'a [String],
+        solutions_list: &'a [String],
+        hard_mode: bool,
+        target: Option<&'a str>,
+        candidate_limit: Option<usize>,
+    ) -> Self {
+        ShortestSearch {
+            words: guessable_list
+                .iter()
+                .chain(solutions_list.iter())
+                .map(String::as_str)
+                .collect(),
+            hard_mode,
+            target,
+            candidate_limit,
+            host_policy: HostPolicy::default(),
+            rules: ScoringRules::Wordle,
+            stats: Cell::new(SearchStats::default()),
+        }
+    }
+
+    /// Search against a host that picks scores this way, instead of Absurdle's.
+    pub fn set_host_policy(&mut self, host_policy: HostPolicy) {
+        self.host_policy = host_policy;
+    }
+
+    pub fn host_policy(&self) -> HostPolicy {
+        self.host_policy
+    }
+
+    /// Use scoring rules other than Wordle's, for adversarial clones that score that way.
+    pub fn set_scoring_rules(&mut self, rules: ScoringRules) {
+        self.rules = rules;
+    }
+
+    pub fn scoring_rules(&self) -> ScoringRules {
+        self.rules
+    }
+
+    /// In challenge mode, the word that has to stay a possibility.
+    pub fn target(&self) -> Option<&'a str> {
+        self.target
+    }
+
+    pub fn hard_mode(&self) -> bool {
+        self.hard_mode
+    }
+
+    /// How much searching has been done so far.
+    pub fn stats(&self) -> SearchStats {
+        self.stats.get()
+    }
+
+    /// Whether every guess is tried at each step, so that failing to find a win proves there
+    /// isn't one.
+    pub fn is_exhaustive(&self) -> bool {
+        self.candidate_limit
+            .is_none_or(|limit| limit >= self.words.len())
+    }
+
+    /// Look for ways to win within `guesses` more guesses after the moves in `history`, adding
+    /// each one found to `wins` until there are `max_wins`.
+    pub fn search(
+        &self,
+        possibilities: &[&'a str],
+        history: &mut Vec<(&'a str, DetailScore)>,
+        guesses: usize,
+        wins: &mut Vec<Vec<(&'a str, DetailScore)>>,
+        max_wins: usize,
+    ) {
+        let mut stats = self.stats.get();
+        stats.nodes += 1;
+        stats.deepest = stats.deepest.max(history.len());
+        self.stats.set(stats);
+
+        if let [answer] = possibilities {
+            let mut win = history.clone();
+            win.push((answer, self.rules.compute_score(answer, answer)));
+            wins.push(win);
+            return;
+        }
+
+        // Each score splits the possibilities at most NUM_POSSIBLE_SCORES ways, and Absurdle
+        // keeps the largest group, so there have to be few enough to get down to one in time.
+        let needed = (guesses.max(1) - 1) as u32;
+        if guesses <= 1 || possibilities.len() > (NUM_POSSIBLE_SCORES as usize).pow(needed.min(4)) {
+            return;
+        }
+
+        let mut candidates: Vec<(usize, DetailScore, &'a str)> = self
+            .words
+            .iter()
+            .filter(|guess| Some(**guess) != self.target)
+            .filter(|guess| !self.hard_mode || self.rules.is_hard_mode_legal(history, guess))
+            .map(|guess| {
+                let (score, kept) = self
+                    .host_policy
+                    .host_choice(guess, possibilities, self.rules);
+                (kept, score, *guess)
+            })
+            .filter(|(kept, score, guess)| {
+                *kept < possibilities.len()
+                    && self
+                        .target
+                        .is_none_or(|target| self.rules.compute_score(guess, target) == *score)
+            })
+            .collect();
+        // Stable, so equally good guesses stay in list order.
+        candidates.sort_by_key(|(kept, _, _)| *kept);
+        if let Some(limit) = self.candidate_limit {
+            candidates.truncate(limit);
+        }
+
+        for (_, score, guess) in candidates {
+            if wins.len() >= max_wins {
+                return;
+            }
+            let retained: Vec<&str> = possibilities
+                .iter()
+                .copied()
+                .filter(|possibility| self.rules.is_consistent(guess, score, possibility))
+                .collect();
+            history.push((guess, score));
+            let wins_before = wins.len();
+            self.search(&retained, history, guesses - 1, wins, max_wins);
+            history.pop();
+            if wins.len() == wins_before {
+                let mut stats = self.stats.get();
+                stats.backtracks += 1;
+                self.stats.set(stats);
+            }
+        }
+    }
+
+    /// The shortest way to win after the moves in `history`, if there's one within
+    /// `MAX_SHORTEST_GUESSES` more guesses. Only the moves after `history` are returned.
+    pub fn first_win(
+        &self,
+        possibilities: &[&'a str],
+        history: &[(&'a str, DetailScore)],
+    ) -> Option<Vec<(&'a str, DetailScore)>> {
+        (1..=MAX_SHORTEST_GUESSES).find_map(|limit| {
+            let mut wins = Vec::new();
+            self.search(possibilities, &mut history.to_vec(), limit, &mut wins, 1);
+            wins.pop().map(|win| win[history.len()..].to_vec())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::score::compute_score;
+
+    fn words(list: &[&str]) -> Vec<String> {
+        list.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn test_host_choice() {
+        // "humph" shares no letters with "cigar" or "civic", the largest group.
+        let (score, kept) = HostPolicy::Absurdle.host_choice(
+            "humph",
+            &["cigar", "civic", "hyper"],
+            ScoringRules::Wordle,
+        );
+        assert_eq!(kept, 2);
+        assert_eq!(score, compute_score("humph", "cigar"));
+        assert_eq!(score, compute_score("humph", "civic"));
+    }
+
+    #[test]
+    fn test_host_score() {
+        let score = |s: &str| s.parse::<DetailScore>().unwrap();
+        let (first, retained) = host_score("humph", &["cigar", "civic", "hyper"]);
+        assert_eq!(first, score("aaaaa"));
+        assert_eq!(retained, ["cigar", "civic"]);
+
+        // A tie between "ccaaa" and "aaaac": fewer correct letters lose less entropy.
+        assert_eq!(
+            host_score("cigar", &["civic", "hyper"]),
+            (score("aaaac"), vec!["hyper"])
+        );
+
+        // A whole game. "sissy" splits these into "rebut humph awake focal evade", which share
+        // none of its letters, and three singletons; then "awake" leaves each of those five on
+        // its own, and the tie goes to the score with no correct or present letters.
+        let transcript = [
+            ("sissy", "aaaaa", 5),
+            ("awake", "aaaaa", 1),
+            ("humph", "ccccc", 1),
+        ];
+        let mut possibilities = vec![
+            "cigar", "rebut", "sissy", "humph", "awake", "blush", "focal", "evade",
+        ];
+        for (guess, expected, kept) in transcript {
+            let (host, retained) = host_score(guess, &possibilities);
+            assert_eq!(host, score(expected), "{}", guess);
+            assert_eq!(retained.len(), kept, "{}", guess);
+            possibilities = retained;
+        }
+        assert_eq!(possibilities, ["humph"]);
+    }
+
+    #[test]
+    fn test_host_policy() {
+        use argparse::FromCommandLine;
+        assert_eq!(
+            HostPolicy::from_argument("absurdle"),
+            Ok(HostPolicy::Absurdle)
+        );
+        assert_eq!(
+            HostPolicy::from_argument("random"),
+            Ok(HostPolicy::Random(0))
+        );
+        assert_eq!(
+            HostPolicy::from_argument("random:42"),
+            Ok(HostPolicy::Random(42))
+        );
+        assert!(HostPolicy::from_argument("random:x").is_err());
+        assert_eq!(HostPolicy::Random(42).to_string(), "random:42");
+
+        // Both groups are the largest, so a random host can keep either, but always keeps the
+        // same one for the same seed.
+        let possibilities = ["civic", "hyper"];
+        let kept: HashSet<Vec<&str>> = (0..20)
+            .map(|seed| {
+                let policy = HostPolicy::Random(seed);
+                let (score, retained) =
+                    policy.host_score("cigar", &possibilities, ScoringRules::Wordle);
+                assert_eq!(
+                    policy
+                        .host_score("cigar", &possibilities, ScoringRules::Wordle)
+                        .0,
+                    score
+                );
+                retained
+            })
+            .collect();
+        assert_eq!(kept.len(), 2);
+
+        // The picks are fixed, so checkpoints and reports from other builds still hold.
+        let picks: Vec<&str> = (0..5)
+            .map(|seed| {
+                let policy = HostPolicy::Random(seed);
+                policy
+                    .host_score("cigar", &possibilities, ScoringRules::Wordle)
+                    .1[0]
+            })
+            .collect();
+        assert_eq!(picks, ["civic", "hyper", "hyper", "civic", "civic"]);
+
+        // A random host never keeps a smaller group.
+        for seed in 0..20 {
+            let policy = HostPolicy::Random(seed);
+            let (_, retained) =
+                policy.host_score("humph", &["cigar", "civic", "hyper"], ScoringRules::Wordle);
+            assert_eq!(retained, ["cigar", "civic"]);
+        }
+    }
+
+    #[test]
+    fn test_solve() {
+        let solutions = words(&[
+            "cigar", "rebut", "sissy", "humph", "awake", "blush", "focal", "evade",
+        ]);
+        let guessable = words(&["ahead"]);
+        let mut solver = Solver::new("humph", &guessable, &solutions, false);
+        let moves = solver.solve().unwrap();
+        assert_eq!(moves.last().unwrap().0, "humph");
+        assert!(moves.last().unwrap().1.is_win());
+
+        // Each score is Absurdle's, and keeps the target.
+        let mut possibilities: Vec<&str> = solutions.iter().map(String::as_str).collect();
+        for (guess, score) in moves.iter() {
+            let (host, retained) = host_score(guess, &possibilities);
+            assert_eq!(host, *score);
+            assert_eq!(compute_score(guess, "humph"), *score);
+            possibilities = retained;
+        }
+        assert_eq!(possibilities, ["humph"]);
+        assert!(solver.stats().nodes >= moves.len());
+
+        // Against another host, the scores are that host's.
+        let policy = HostPolicy::Random(1);
+        solver.set_host_policy(policy);
+        let moves = solver.solve().unwrap();
+        let mut possibilities: Vec<&str> = solutions.iter().map(String::as_str).collect();
+        for (guess, score) in moves.iter() {
+            let (host, retained) = policy.host_score(guess, &possibilities, ScoringRules::Wordle);
+            assert_eq!(host, *score);
+            possibilities = retained;
+        }
+
+        // Under other scoring rules, the scores are those rules'.
+        let rules = ScoringRules::AllPresent;
+        solver.set_scoring_rules(rules);
+        let moves = solver.solve().unwrap();
+        let mut possibilities: Vec<&str> = solutions.iter().map(String::as_str).collect();
+        for (guess, score) in moves.iter() {
+            let (host, retained) = policy.host_score(guess, &possibilities, rules);
+            assert_eq!(host, *score);
+            assert_eq!(rules.compute_score(guess, "humph"), *score);
+            possibilities = retained;
+        }
+
+        // Guessing the target right away isn't a win.
+        solver.set_max_depth(Some(1));
+        assert_eq!(solver.solve(), Err(SolveError::DepthLimit(1)));
+    }
+
+    #[test]
+    fn test_useless_guesses() {
+        // "eerie" tells Absurdle nothing about these, so guessing it would get nowhere.
+        let solutions = words(&["cigar", "civic", "humph", "hyper", "focal"]);
+        let guessable = words(&["eerie"]);
+        let mut solver = Solver::new("cigar", &guessable, &solutions, false);
+        assert!(!solver.next_guesses().contains(&"eerie"));
+        assert_eq!(solver.solve(), Err(SolveError::NoSolution));
+    }
+
+    #[test]
+    fn test_infeasibility() {
+        // Every guess but "chest" keeps "scare" and "zesty" together.
+        let solutions = words(&["chest", "scare", "zesty"]);
+        let mut solver = Solver::new("chest", &[], &solutions, true);
+        assert_eq!(solver.solve(), Err(SolveError::NoSolution));
+        let infeasibility = solver.infeasibility();
+        assert_eq!(infeasibility.dead_end, Some(Vec::new()));
+        assert_eq!(infeasibility.allowed_guesses, 2);
+        assert_eq!(infeasibility.possibilities_left, 3);
+        assert!(infeasibility.provably_unreachable);
+
+        // "cigar" is kept after "humph", but nothing hard mode allows after that keeps it.
+        let solutions = words(&["cigar", "civic", "humph", "hyper", "focal"]);
+        let guessable = words(&["eerie"]);
+        let mut solver = Solver::new("cigar", &guessable, &solutions, true);
+        assert_eq!(solver.solve(), Err(SolveError::NoSolution));
+        let infeasibility = solver.infeasibility();
+        let dead_end = infeasibility.dead_end.as_ref().unwrap();
+        assert_eq!(dead_end[0].0, "humph");
+        assert_eq!(infeasibility.depth_reached, 1);
+        assert!(!infeasibility.provably_unreachable);
+        assert_eq!(
+            infeasibility.lines()[1],
+            "  Hard mode constraints: .....; no h, m, p, u"
+        );
+    }
+
+    #[test]
+    fn test_resume() {
+        let solutions = words(&[
+            "cigar", "rebut", "sissy", "humph", "awake", "blush", "focal", "evade",
+        ]);
+        let guessable = words(&["ahead"]);
+        let mut solver = Solver::new("blush", &guessable, &solutions, true);
+        let stacks = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let saved = stacks.clone();
+        solver.set_checkpoint(Duration::ZERO, move |stack| {
+            let stack = stack
+                .iter()
+                .map(|guesses| guesses.iter().map(|g| g.to_string()).collect())
+                .collect();
+            saved.borrow_mut().push(stack);
+        });
+        let moves = solver.solve().unwrap();
+
+        // Resuming from any point of the search finds the same solution.
+        let stacks: Vec<Vec<Vec<String>>> = stacks.borrow().clone();
+        assert!(!stacks.is_empty());
+        for stack in stacks {
+            let mut resumed = Solver::new("blush", &guessable, &solutions, true);
+            resumed.resume(&stack).unwrap();
+            assert_eq!(resumed.solve().unwrap(), moves);
+        }
+
+        let unknown = vec![vec!["zzzzz".to_string()]];
+        assert!(solver.resume(&unknown).is_err());
+        assert!(solver.resume(&[Vec::new()]).is_err());
+    }
+
+    #[test]
+    fn test_status() {
+        let shown = std::cell::RefCell::new(Vec::new());
+        let solutions = words(&[
+            "cigar", "rebut", "sissy", "humph", "awake", "blush", "focal", "evade",
+        ]);
+        let guessable = words(&["ahead"]);
+        let mut solver = Solver::new("humph", &guessable, &solutions, false);
+        solver.set_status(Duration::ZERO, |stats, moves| {
+            shown.borrow_mut().push((stats, moves.len()));
+        });
+        let moves = solver.solve().unwrap();
+
+        // Called after every step, with the stats so far.
+        let shown = shown.borrow();
+        let (stats, depth) = *shown.last().unwrap();
+        assert_eq!(depth, moves.len() - 1);
+        assert_eq!(stats.deepest, depth - 1);
+        assert!(shown.windows(2).all(|w| w[0].0.nodes <= w[1].0.nodes));
+    }
+
+    #[test]
+    fn test_respond_to_score() {
+        let solutions = words(&["cigar", "civic", "humph"]);
+        let mut solver = Solver::new("humph", &[], &solutions, false);
+        assert!(solver.next_guesses().contains(&"cigar"));
+
+        let impossible = "ggggb".parse().unwrap();
+        assert!(solver.respond_to_score("cigar", impossible).is_err());
+        assert_eq!(solver.get_possibilities().len(), 3);
+        assert!(solver.history().is_empty());
+
+        solver
+            .respond_to_score("cigar", compute_score("cigar", "humph"))
+            .unwrap();
+        assert_eq!(solver.get_possibilities(), ["humph"]);
+        assert_eq!(solver.history().len(), 1);
+        assert_eq!(solver.next_guesses(), ["humph"]);
+    }
+
+    #[test]
+    fn test_shortest_search() {
+        let solutions = words(&["cigar", "civic", "humph", "hyper"]);
+        let search = ShortestSearch::new(&[], &solutions, false, None, None);
+        assert!(search.is_exhaustive());
+        let possibilities: Vec<&str> = solutions.iter().map(String::as_str).collect();
+        let win = search.first_win(&possibilities, &[]).unwrap();
+        assert!(win.last().unwrap().1.is_win());
+
+        // Absurdle can always keep two of these apart, so one guess can't win.
+        let mut wins = Vec::new();
+        search.search(&possibilities, &mut Vec::new(), 1, &mut wins, 1);
+        assert!(wins.is_empty());
+    }
+}
@@ -1,198 +1,472 @@
-//! A solver for Absurdle's challenge mode.
-
-use argparse::{ArgumentParser, Store, StoreTrue};
+//! A solver for Absurdle, in regular mode or challenge mode.
+
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{stdin, stdout, BufWriter, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::channel;
+use std::time::{Duration, Instant};
+
+use argparse::{ArgumentParser, Parse, Store, StoreOption, StoreTrue};
+
+use wordle_solver::absurdle::{
+    HostPolicy, SearchEvent, SearchStats, ShortestSearch, Solver, MAX_SHORTEST_GUESSES,
+};
+use wordle_solver::default_thread_count;
+use wordle_solver::json;
+use wordle_solver::list_cache::hash_words;
+use wordle_solver::loader::{is_stdin_path, load_bundled, load_list_from_file};
+use wordle_solver::score::{read_score_interactively, DetailScore, ScoreFormat, ScoringRules};
+
+/// How often `--progress` updates the status line.
+const STATUS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often `--checkpoint` saves the challenge search's progress.
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The first line of a checkpoint file, identifying the search it belongs to.
+fn checkpoint_header(
+    target: &str,
+    hard_mode: bool,
+    host_policy: HostPolicy,
+    rules: ScoringRules,
+    max_depth: Option<usize>,
+    guessable: &[String],
+    solutions: &[String],
+) -> String {
+    let mode = if hard_mode {
+        "hard mode"
+    } else {
+        "normal mode"
+    };
+    let max_depth = max_depth.map_or("none".to_string(), |max| max.to_string());
+    format!(
+        "# absurdle-solver checkpoint: {}; {}; host {}; scoring {}; max depth {}; \
+         guessable {:016x}; solutions {:016x}",
+        target,
+        mode,
+        host_policy,
+        rules,
+        max_depth,
+        hash_words(guessable),
+        hash_words(solutions)
+    )
+}
 
-use wordle_solver::loader::load_list_from_file;
-use wordle_solver::score::{compute_score, DetailScore};
+/// Save the challenge search's stack: the header, then a line per step, listing the guesses left
+/// to try there, separated by spaces. Writes a temporary file and renames it over the old one, so
+/// that an interruption can't leave a partial checkpoint.
+fn write_checkpoint(path: &str, header: &str, stack: &[Vec<&str>]) -> std::io::Result<()> {
+    let temp_path = format!("{}.tmp", path);
+    let mut file = BufWriter::new(File::create(&temp_path)?);
+    writeln!(file, "{}", header)?;
+    for guesses in stack {
+        writeln!(file, "{}", guesses.join(" "))?;
+    }
+    file.flush()?;
+    drop(file);
+    std::fs::rename(&temp_path, path)
+}
 
-struct Solver<'a> {
-    target_word: &'a str,
-    possibilities: Vec<&'a str>,
-    history: Vec<(&'a str, DetailScore)>,
-    guessable_list: &'a [String],
-    solutions_list: &'a [String],
-    hard_mode: bool,
+/// The stack saved in a checkpoint file by a search with the same header. A missing file has an
+/// empty stack, so the search starts from scratch.
+fn read_checkpoint(path: &str, header: &str) -> std::io::Result<Vec<Vec<String>>> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut lines = contents.lines();
+    if lines.next() != Some(header) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "checkpoint is from a search with a different target, mode, host, scoring rules, depth \
+             or lists",
+        ));
+    }
+    Ok(lines
+        .map(|line| line.split_whitespace().map(str::to_string).collect())
+        .collect())
 }
 
-impl<'a> Solver<'a> {
-    fn new(
-        target_word: &'a str,
-        guessable_list: &'a [String],
-        solutions_list: &'a [String],
-        hard_mode: bool,
-    ) -> Self {
-        Solver {
-            target_word,
-            guessable_list,
-            solutions_list,
-            history: Vec::new(),
-            hard_mode,
-            possibilities: Vec::from_iter(solutions_list.iter().map(|s| s.as_str())),
-        }
-    }
-
-    /// Compute a vector of possible next guesses. They are sorted in order from worst to best.
-    pub fn next_guess(&self) -> Vec<&'a str> {
-        if self.possibilities.len() == 1 {
-            return self.possibilities.clone();
-        }
-
-        // When you enter a guess in Absurdle challenge mode, it iterates through all possible
-        // scores, and returns the one that eliminates the fewest remaining possibilities. Ties
-        // are broken by Absurdle's "entropyLost" metric, calculated in score.rs.
-        //
-        // To pick a guess, therefore, we iterate through all possible guesses, and:
-        //   1. Compute the score that Absurdle would give for that guess
-        //   2. Compute how many possibilities are eliminated by that score
-        //   3. Make sure that score wouldn't eliminate the target word as a possibility
-        //
-        // Return the guess that had the highest number in step 2 and passed step 3.
-        //
-        // In other words: pick the guess for which the least-informative score is as informative
-        // as possible.
-
-        let all_scores = DetailScore::all_possible();
-        let mut guesses: Vec<&str> = Vec::new();
-        let mut eliminated_by_best_guess = 0;
-
-        'next_guess: for guess in self.guessable_list.iter().chain(self.solutions_list.iter()) {
-            // Don't guess the target word. (The winning move is covered by the len == 1 case).
-            if guess == self.target_word {
-                continue;
-            }
+/// How much searching was done, for the end of a search with text output.
+fn print_stats(stats: SearchStats, elapsed: Duration) {
+    eprintln!(
+        "Search stats: nodes {}, backtracks {}, deepest {}, time {:.2}s",
+        stats.nodes,
+        stats.backtracks,
+        stats.deepest,
+        elapsed.as_secs_f64()
+    );
+}
 
-            // Follow the hard mode rule: can't make a guess that is ruled out by previous scores.
-            if self.hard_mode {
-                for (prev_guess, prev_score) in self.history.iter() {
-                    if compute_score(prev_guess, guess) != *prev_score {
-                        continue 'next_guess;
-                    }
-                }
-            }
+/// A status line for `--progress`, rewritten in place on stderr: how deep the search is and has
+/// been, how much it has done, and the sequence of guesses it's trying.
+fn show_status(stats: SearchStats, moves: &[(&str, DetailScore)], shown: &Cell<bool>) {
+    let guesses: Vec<&str> = moves.iter().map(|(guess, _)| *guess).collect();
+    let line = format!(
+        "depth {} (deepest {}), {} nodes, {} backtracks: {}",
+        moves.len(),
+        stats.deepest,
+        stats.nodes,
+        stats.backtracks,
+        guesses.join(" ")
+    );
+    eprint!("\r{:<100}", line);
+    shown.set(true);
+}
 
-            let mut min_eliminated_by_this_guess = usize::MAX;
-            let mut score_that_eliminates_min: Option<&DetailScore> = None;
+/// How to print the result of a search.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum OutputFormat {
+    /// The search as it goes, and the result, as text on stdout.
+    Text,
 
-            'next_score: for possible_score in all_scores.iter() {
-                let mut eliminated_by_this_score = 0;
+    /// The result as JSON on stdout (see `print_json`), and the search as it goes on stderr.
+    Json,
+}
 
-                for possibility in self.possibilities.iter() {
-                    if compute_score(guess, possibility) != *possible_score {
-                        eliminated_by_this_score += 1;
-                    }
+impl argparse::FromCommandLine for OutputFormat {
+    fn from_argument(s: &str) -> Result<Self, String> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err("output formats are 'text' and 'json'".to_string()),
+        }
+    }
+}
 
-                    // eliminated_by_this_score only goes up, so if it's already greater than the
-                    // minimum seen so far, no need to keep evaluating this score
-                    if eliminated_by_this_score > min_eliminated_by_this_guess {
-                        continue 'next_score;
-                    }
-                }
+/// A sequence of moves as JSON: `{"guesses": [...], "scores": [...]}`.
+fn json_moves(moves: &[(&str, DetailScore)]) -> [(&'static str, String); 2] {
+    let guesses = moves.iter().map(|(guess, _)| json::string(guess));
+    let scores = moves
+        .iter()
+        .map(|(_, score)| json::string(&score.to_string()));
+    [
+        ("guesses", json::array(guesses)),
+        ("scores", json::array(scores)),
+    ]
+}
 
-                if eliminated_by_this_score < min_eliminated_by_this_guess {
-                    score_that_eliminates_min = Some(possible_score);
-                    min_eliminated_by_this_guess = eliminated_by_this_score;
-                } else if eliminated_by_this_score == min_eliminated_by_this_guess {
-                    // If multiple scores result in the same number of eliminated possibilities,
-                    // Absurdle will return the one with the lowest "entropyLost".
-                    if score_that_eliminates_min.unwrap().absurdle_entropy_lost()
-                        > possible_score.absurdle_entropy_lost()
-                    {
-                        score_that_eliminates_min = Some(possible_score);
-                    }
-                }
+/// Print a search's result as JSON: the target word (null in regular mode), whether a solution
+/// was found, its guesses and the scores Absurdle shows for them, any other solutions found as
+/// `alternatives`, whether the solution is proven to be the shortest (if known), and stats.
+fn print_json(
+    target: Option<&str>,
+    hard_mode: bool,
+    wins: &[Vec<(&str, DetailScore)>],
+    proven_shortest: Option<bool>,
+    stats: SearchStats,
+    elapsed: Duration,
+) {
+    let mut fields = vec![
+        ("target", target.map_or("null".to_string(), json::string)),
+        ("hard_mode", hard_mode.to_string()),
+        ("solved", (!wins.is_empty()).to_string()),
+    ];
+    fields.extend(json_moves(wins.first().map_or(&[], Vec::as_slice)));
+    if wins.len() > 1 {
+        let alternatives = wins[1..].iter().map(|win| json::object(json_moves(win)));
+        fields.push(("alternatives", json::array(alternatives)));
+    }
+    if let Some(proven) = proven_shortest {
+        fields.push(("proven_shortest", proven.to_string()));
+    }
+    let stats = [
+        ("nodes", stats.nodes.to_string()),
+        ("backtracks", stats.backtracks.to_string()),
+        ("deepest", stats.deepest.to_string()),
+        ("seconds", json::number(elapsed.as_secs_f64())),
+    ];
+    fields.push(("stats", json::object(stats)));
+    println!("{}", json::object(fields));
+}
 
-                // min_eliminated_by_this_guess only goes down, so if it's already less than the
-                // maximum seen so far, no need to keep evaluating this guess.
-                if min_eliminated_by_this_guess < eliminated_by_best_guess {
-                    continue 'next_guess;
-                }
-            }
+/// Play against the real game: suggest guesses, read the scores Absurdle actually gives, and
+/// search again from there. The simulated host can disagree with the real one, e.g. if the word
+/// lists differ.
+fn play_interactively(
+    search: &ShortestSearch,
+    solutions_list: &[String],
+    score_format: ScoreFormat,
+) {
+    let mut input = stdin().lock();
+    let mut output = stdout();
+    let mut possibilities: Vec<&str> = solutions_list.iter().map(String::as_str).collect();
+    let mut history = Vec::new();
+
+    loop {
+        let Some(line) = search.first_win(&possibilities, &history) else {
+            println!("No way to win found from here");
+            std::process::exit(1);
+        };
+        let (guess, expected) = line[0];
+        println!("Guess: {} (wins in {} more guesses)", guess, line.len());
+
+        let score = read_score_interactively(&mut input, &mut output);
+        if score.is_win() {
+            println!("Win!");
+            return;
+        }
+        if score != expected {
+            println!(
+                "Expected {}; continuing from Absurdle's score instead",
+                expected.format(score_format)
+            );
+        }
+        history.push((guess, score));
+        let rules = search.scoring_rules();
+        possibilities.retain(|possibility| rules.is_consistent(guess, score, possibility));
+        if possibilities.is_empty() {
+            println!("No possibilities fit these scores; are the word lists the game's?");
+            std::process::exit(1);
+        }
+        if let Some(target) = search.target().filter(|t| !possibilities.contains(t)) {
+            println!("{} isn't possible anymore", target);
+            std::process::exit(1);
+        }
+    }
+}
 
-            // Now we know the score that Absurdle would give for this guess. Make sure it doesn't
-            // eliminate the target word.
-            if compute_score(guess, self.target_word) != *score_that_eliminates_min.unwrap() {
-                continue 'next_guess;
-            }
+/// Find the fewest guesses that win, by iterative deepening: search for a win within one guess,
+/// then two, and so on, so that the first one found is the shortest. With `all`, list every
+/// sequence of that many guesses that wins.
+fn solve_shortest(
+    search: &ShortestSearch,
+    solutions_list: &[String],
+    all: bool,
+    format: OutputFormat,
+    score_format: ScoreFormat,
+) {
+    let start = Instant::now();
+    let possibilities: Vec<&str> = solutions_list.iter().map(String::as_str).collect();
+    let how = if search.is_exhaustive() {
+        ""
+    } else {
+        " among the best guesses at each step"
+    };
+    let mut wins = Vec::new();
+    for limit in 1..=MAX_SHORTEST_GUESSES {
+        let max_wins = if all { usize::MAX } else { 1 };
+        search.search(&possibilities, &mut Vec::new(), limit, &mut wins, max_wins);
+        if !wins.is_empty() {
+            break;
+        }
+        let line = format!("No win within {} guesses{}", limit, how);
+        match format {
+            OutputFormat::Text => println!("{}", line),
+            OutputFormat::Json => eprintln!("{}", line),
+        }
+    }
 
-            if min_eliminated_by_this_guess > eliminated_by_best_guess {
-                // In the normal version of the solver, we clear the guesses vec here, because at
-                // this point, the guesses in the vec are known to be non-optimal. But here, it's
-                // possible that none of the optimal guesses will work out, and we'll need to fall
-                // back to worse ones. The vec will naturally be sorted in increasing order of
-                // possibilities eliminated, because any subsequently evaluated guesses that
-                // eliminate fewer than this one will be pruned out, above.
-                eliminated_by_best_guess = min_eliminated_by_this_guess;
+    if format == OutputFormat::Json {
+        let target = search.target();
+        let proven = search.is_exhaustive();
+        let elapsed = start.elapsed();
+        print_json(
+            target,
+            search.hard_mode(),
+            &wins,
+            Some(proven),
+            search.stats(),
+            elapsed,
+        );
+    } else if let Some(first) = wins.first() {
+        if all {
+            for win in wins.iter() {
+                let moves: Vec<String> = win
+                    .iter()
+                    .map(|(g, s)| format!("{} {}", g, s.format(score_format)))
+                    .collect();
+                println!("{}", moves.join("  "));
             }
-            if min_eliminated_by_this_guess == eliminated_by_best_guess {
-                guesses.push(guess);
+            println!(
+                "{} ways to win in {} guesses{}",
+                wins.len(),
+                first.len(),
+                how
+            );
+        } else {
+            for (guess, score) in first.iter() {
+                println!("{} {}", guess, score.format(score_format));
             }
         }
-
-        guesses
+        let proof = if search.is_exhaustive() {
+            "; no fewer guesses can win"
+        } else {
+            "; other guesses might win in fewer"
+        };
+        println!("Won in {} guesses{}", first.len(), proof);
+    }
+    if format == OutputFormat::Text {
+        print_stats(search.stats(), start.elapsed());
     }
+    if wins.is_empty() {
+        std::process::exit(1);
+    }
+}
+
+/// How many guesses a `--sweep` tries to force each target in, without `--max-depth`.
+const SWEEP_MAX_DEPTH: usize = 6;
 
-    pub fn respond_to_score(&mut self, guess: &'a str, score: &DetailScore) {
-        // Keep only possibilities that fit the score we got.
-        self.possibilities
-            .retain(|poss| compute_score(guess, *poss) == *score);
+/// One target's result in one mode of a `--sweep`.
+enum SweepResult<'a> {
+    /// The fewest guesses that force the target, ending with it.
+    Forced(Vec<(&'a str, DetailScore)>),
 
-        if self.possibilities.is_empty() {
-            // This should not happen absent human error in playing the game.
-            panic!("No possibilities left");
+    /// No way to force the target was found within the sweep's depth limit.
+    NotFound,
+
+    /// No first guess keeps the target, so no sequence can force it.
+    Unreachable,
+}
+
+impl Display for SweepResult<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SweepResult::Forced(moves) => {
+                let guesses: Vec<&str> = moves.iter().map(|(guess, _)| *guess).collect();
+                write!(f, "{} {}", moves.len(), guesses.join(" "))
+            }
+            SweepResult::NotFound => write!(f, "not found"),
+            SweepResult::Unreachable => write!(f, "unreachable"),
         }
     }
+}
 
-    pub fn solve(&mut self) {
-        // The approach is to keep a stack of possible guesses at each step. We will repeatedly
-        // test a sequence of guesses consisting of the last one from each level of the stack. If
-        // this leads us to a dead end (no possible guesses left that don't eliminate the target
-        // word), we backtrack by popping a guess from the last level and trying again. We will
-        // maintain the invariant that every level of this stack is a nonempty vec.
-        let mut stack: Vec<Vec<&str>> = Vec::new();
-
-        loop {
-            let next_guesses = self.next_guess();
+/// How a sweep searches for each target: the `--shortest` search's options.
+#[derive(Clone, Copy)]
+struct SweepOptions {
+    host_policy: HostPolicy,
+    rules: ScoringRules,
+    max_depth: usize,
+    candidate_limit: Option<usize>,
+}
 
-            if next_guesses.is_empty() {
-                // No way to proceed. Backtrack.
-                println!("✗");
+/// Find the fewest guesses that force one target, as `--shortest` does, up to the sweep's depth
+/// limit.
+fn sweep_target<'a>(
+    target: &'a str,
+    guessable: &'a [String],
+    solutions: &'a [String],
+    hard_mode: bool,
+    options: SweepOptions,
+) -> SweepResult<'a> {
+    let mut search = ShortestSearch::new(
+        guessable,
+        solutions,
+        hard_mode,
+        Some(target),
+        options.candidate_limit,
+    );
+    search.set_host_policy(options.host_policy);
+    search.set_scoring_rules(options.rules);
+    let possibilities: Vec<&str> = solutions.iter().map(String::as_str).collect();
+    for limit in 1..=options.max_depth {
+        let mut wins = Vec::new();
+        search.search(&possibilities, &mut Vec::new(), limit, &mut wins, 1);
+        if let Some(win) = wins.pop() {
+            return SweepResult::Forced(win);
+        }
+    }
 
-                // The last guess, at least, led us to a loss. Drop it.
-                stack.last_mut().unwrap().pop();
+    let keeps_target = |guess: &String| {
+        let (score, _) = options
+            .host_policy
+            .host_score(guess, &possibilities, options.rules);
+        guess != target && options.rules.compute_score(guess, target) == score
+    };
+    if guessable.iter().chain(solutions).any(keeps_target) {
+        SweepResult::NotFound
+    } else {
+        SweepResult::Unreachable
+    }
+}
 
-                // Restore the invariant that every level of the stack is a nonempty vector. If
-                // that pop made the last level empty, pop that whole level and drop the last guess
-                // of the previous level.
-                while stack.last().unwrap().is_empty() {
-                    stack.pop();
-                    if stack.is_empty() {
-                        println!("Total failure!");
-                        return;
-                    }
-                    stack.last_mut().unwrap().pop();
-                }
-            } else if next_guesses.len() == 1 && *next_guesses.first().unwrap() == self.target_word
-            {
-                println!("{} ✔", self.target_word);
-                return;
-            } else {
-                // Neither a win nor a loss. Add this set of guesses to the stack and keep going.
-                stack.push(next_guesses);
-                println!();
+/// Find the fewest guesses that force every target word, in normal and hard mode, split across
+/// threads. Prints a line per target, in list order, with the number of guesses and the guesses
+/// for each mode, then a summary of each mode.
+fn sweep(guessable: &[String], solutions: &[String], options: SweepOptions, thread_count: usize) {
+    let next_index = AtomicUsize::new(0);
+    let (sender, receiver) = channel();
+    let mut results: Vec<Option<[SweepResult; 2]>> = Vec::new();
+    results.resize_with(solutions.len(), || None);
+
+    std::thread::scope(|scope| {
+        for _ in 0..thread_count.max(1) {
+            let sender = sender.clone();
+            let next_index = &next_index;
+            scope.spawn(move || loop {
+                let index = next_index.fetch_add(1, Ordering::Relaxed);
+                let Some(target) = solutions.get(index) else {
+                    break;
+                };
+                let result = [false, true].map(|hard_mode| {
+                    sweep_target(target, guessable, solutions, hard_mode, options)
+                });
+                sender.send((index, result)).unwrap();
+            });
+        }
+        drop(sender);
+
+        let mut last_shown: Option<Instant> = None;
+        for (done, (index, result)) in receiver.iter().enumerate() {
+            results[index] = Some(result);
+            if last_shown.is_none_or(|last| last.elapsed() >= STATUS_INTERVAL) {
+                eprint!("\r{}/{} targets done", done + 1, solutions.len());
+                last_shown = Some(Instant::now());
             }
+        }
+        if last_shown.is_some() {
+            eprintln!();
+        }
+    });
+    let results: Vec<[SweepResult; 2]> = results.into_iter().map(Option::unwrap).collect();
 
-            // Reconstruct the state for the current stack of guesses.
-            self.history.clear();
-            self.possibilities = Vec::from_iter(self.solutions_list.iter().map(|s| s.as_str()));
+    for (target, [normal, hard]) in solutions.iter().zip(results.iter()) {
+        println!("{}\t{}\t{}", target, normal, hard);
+    }
 
-            for guesses in stack.iter() {
-                let best_guess = *guesses.last().unwrap();
-                print!("{} ", best_guess);
-                let score = compute_score(best_guess, self.target_word);
-                self.respond_to_score(best_guess, &score);
-                self.history.push((best_guess, score));
+    for (mode, name) in [(0, "NORMAL MODE"), (1, "HARD MODE")] {
+        let mut guess_counts: BTreeMap<usize, usize> = BTreeMap::new();
+        let mut unreachable = Vec::new();
+        let mut not_found = Vec::new();
+        for (target, result) in solutions.iter().zip(results.iter()) {
+            match result[mode] {
+                SweepResult::Forced(ref moves) => {
+                    *guess_counts.entry(moves.len()).or_default() += 1
+                }
+                SweepResult::NotFound => not_found.push(target.as_str()),
+                SweepResult::Unreachable => unreachable.push(target.as_str()),
             }
         }
+        let counts: Vec<String> = guess_counts
+            .iter()
+            .map(|(guesses, count)| format!("{}: {}", guesses, count))
+            .collect();
+        let counts = if counts.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", counts.join(", "))
+        };
+        println!(
+            "{}: {} targets; {} forced{}; {} not found within {} guesses; {} unreachable",
+            name,
+            solutions.len(),
+            guess_counts.values().sum::<usize>(),
+            counts,
+            not_found.len(),
+            options.max_depth,
+            unreachable.len()
+        );
+        if !not_found.is_empty() {
+            println!("  not found: {}", not_found.join(" "));
+        }
+        if !unreachable.is_empty() {
+            println!("  unreachable: {}", unreachable.join(" "));
+        }
     }
 }
 
@@ -201,41 +475,414 @@ fn main() {
     let mut solutions_path = "".to_string();
     let mut target_word = "".to_string();
     let mut hard_mode = false;
+    let mut regular = false;
+    let mut shortest = false;
+    let mut all = false;
+    let mut interactive = false;
+    let mut max_depth: Option<usize> = None;
+    let mut iterative_deepening = false;
+    let mut candidates: Option<usize> = None;
+    let mut sweep_all = false;
+    let mut thread_count = default_thread_count();
+    let mut format = OutputFormat::Text;
+    let mut checkpoint_path: Option<String> = None;
+    let mut resume = false;
+    let mut progress = false;
+    let mut host_policy = HostPolicy::Absurdle;
+    let mut scoring_rules = ScoringRules::Wordle;
+    let mut score_format = ScoreFormat::default_for_stdout();
 
     {
         let mut parser = ArgumentParser::new();
-        parser.set_description("Solve Absurdle challenge mode");
-        parser.refer(&mut guessable_path).required().add_argument(
+        parser.set_description(concat!(
+            "Solve Absurdle challenge mode, forcing the target word, or with --regular, regular ",
+            "Absurdle in as few guesses as possible"
+        ));
+        parser.refer(&mut guessable_path).add_argument(
             "guessable-path",
             Store,
-            "The path to the file of guessable strings",
+            concat!(
+                "The path to the file of guessable strings, or - for stdin. Omit both paths to ",
+                "use the bundled lists."
+            ),
         );
-        parser.refer(&mut solutions_path).required().add_argument(
+        parser.refer(&mut solutions_path).add_argument(
             "solutions-path",
             Store,
-            "The path to the file of possible solutions",
-        );
-        parser.refer(&mut target_word).required().add_argument(
-            "target-word",
-            Store,
-            "The target word",
+            "The path to the file of possible solutions, or - for stdin",
         );
+        parser
+            .refer(&mut target_word)
+            .add_argument("target-word", Store, "The target word");
         parser.refer(&mut hard_mode).add_option(
             &["--hard-mode"],
             StoreTrue,
             "Guesses must use all previously gained information",
         );
+        parser.refer(&mut host_policy).add_option(
+            &["--host"],
+            Parse,
+            concat!(
+                "How the simulated host picks scores: absurdle (default): the largest group, ",
+                "ties broken as Absurdle does; random or random:<seed>: one of the largest groups ",
+                "at random"
+            ),
+        );
+        parser.refer(&mut scoring_rules).add_option(
+            &["--scoring-rules"],
+            Parse,
+            "How guesses are scored: wordle (default) or all-present",
+        );
+        parser.refer(&mut regular).add_option(
+            &["--regular"],
+            StoreTrue,
+            "Play regular Absurdle, with no target word; omit the target word argument",
+        );
+        parser.refer(&mut sweep_all).add_option(
+            &["--sweep"],
+            StoreTrue,
+            concat!(
+                "Find the fewest guesses that force every target word, in normal and hard mode, ",
+                "as --shortest does; omit the target word argument"
+            ),
+        );
+        parser.refer(&mut thread_count).add_option(
+            &["--thread-count"],
+            Store,
+            "With --sweep, the number of threads to use (default: one per CPU)",
+        );
+        parser.refer(&mut shortest).add_option(
+            &["--shortest"],
+            StoreTrue,
+            concat!(
+                "In challenge mode, find the fewest guesses that force the target word, instead ",
+                "of the first solution found"
+            ),
+        );
+        parser.refer(&mut all).add_option(
+            &["--all"],
+            StoreTrue,
+            "With --regular or --shortest, list every sequence of the fewest guesses that wins",
+        );
+        parser.refer(&mut max_depth).add_option(
+            &["--max-depth"],
+            StoreOption,
+            concat!(
+                "In challenge mode, give up on sequences of more than this many guesses (with ",
+                "--sweep, default 6)"
+            ),
+        );
+        parser.refer(&mut iterative_deepening).add_option(
+            &["--iterative-deepening"],
+            StoreTrue,
+            concat!(
+                "In challenge mode, search with a limit of one guess, then two, and so on, up to ",
+                "--max-depth (default 10)"
+            ),
+        );
+        parser.refer(&mut progress).add_option(
+            &["--progress"],
+            StoreTrue,
+            concat!(
+                "In challenge mode, show a status line on stderr every second (depth, nodes, ",
+                "backtracks and the guesses being tried) instead of every step of the search"
+            ),
+        );
+        parser.refer(&mut checkpoint_path).add_option(
+            &["--checkpoint"],
+            StoreOption,
+            concat!(
+                "In challenge mode, save the search's progress to this file every 10 seconds, ",
+                "so that it can be resumed"
+            ),
+        );
+        parser.refer(&mut resume).add_option(
+            &["--resume"],
+            StoreTrue,
+            "With --checkpoint, continue the search saved in the checkpoint file",
+        );
+        parser.refer(&mut interactive).add_option(
+            &["--interactive"],
+            StoreTrue,
+            concat!(
+                "Play against the real game: suggest each guess, and read the score Absurdle ",
+                "gives for it, instead of simulating it"
+            ),
+        );
+        parser.refer(&mut candidates).add_option(
+            &["--candidates"],
+            StoreOption,
+            concat!(
+                "With --regular, --shortest, --sweep or --interactive, how many guesses to try at ",
+                "each step, best first (default 20 with --regular or --interactive, all with ",
+                "--shortest or --sweep); 0 for all"
+            ),
+        );
+        parser.refer(&mut format).add_option(
+            &["--output"],
+            Parse,
+            concat!(
+                "text (default), or json: print the solution, its scores and search statistics ",
+                "as JSON, and the search's progress on stderr"
+            ),
+        );
+        parser.refer(&mut score_format).add_option(
+            &["--score-format"],
+            Parse,
+            concat!(
+                "How to print scores in text output: apc, byg, emoji, or ansi (default: ansi on a ",
+                "terminal, otherwise apc)"
+            ),
+        );
         parser.parse_args_or_exit();
     }
 
-    let guessable = load_list_from_file(guessable_path.as_ref()).unwrap();
-    let solutions = load_list_from_file(solutions_path.as_ref()).unwrap();
+    if resume && checkpoint_path.is_none() {
+        eprintln!("--resume needs --checkpoint");
+        std::process::exit(2);
+    }
+    if checkpoint_path.is_some() && (regular || shortest || interactive || iterative_deepening) {
+        eprintln!(
+            "--checkpoint only works with the challenge search, without --regular, --shortest, \
+             --interactive or --iterative-deepening"
+        );
+        std::process::exit(2);
+    }
+
+    if interactive && format == OutputFormat::Json {
+        eprintln!("--output json doesn't work with --interactive");
+        std::process::exit(2);
+    }
+
+    if interactive
+        && (is_stdin_path(guessable_path.as_ref()) || is_stdin_path(solutions_path.as_ref()))
+    {
+        eprintln!(
+            "Can't read a word list from stdin in interactive mode, since scores are read from it"
+        );
+        std::process::exit(2);
+    }
+
+    if sweep_all
+        && (regular
+            || shortest
+            || interactive
+            || all
+            || progress
+            || iterative_deepening
+            || checkpoint_path.is_some())
+    {
+        eprintln!(
+            "--sweep doesn't work with --regular, --shortest, --interactive, --all, --progress, \
+             --iterative-deepening or --checkpoint"
+        );
+        std::process::exit(2);
+    }
+    if sweep_all && format == OutputFormat::Json {
+        eprintln!("--output json doesn't work with --sweep");
+        std::process::exit(2);
+    }
+
+    if regular || sweep_all {
+        if !target_word.is_empty() {
+            let flag = if regular { "--regular" } else { "--sweep" };
+            eprintln!("{} doesn't take a target word", flag);
+            std::process::exit(2);
+        }
+        let (guessable, solutions) = if guessable_path.is_empty() {
+            load_bundled().unwrap_or_else(|| {
+                eprintln!("No word lists given, and this build doesn't include bundled lists");
+                std::process::exit(2);
+            })
+        } else if solutions_path.is_empty() {
+            eprintln!("Expected two list paths, or none to use the bundled lists");
+            std::process::exit(2);
+        } else if is_stdin_path(guessable_path.as_ref()) && is_stdin_path(solutions_path.as_ref()) {
+            eprintln!("Only one word list can be read from stdin");
+            std::process::exit(2);
+        } else {
+            (
+                load_list_from_file(guessable_path.as_ref()).unwrap(),
+                load_list_from_file(solutions_path.as_ref()).unwrap(),
+            )
+        };
+        if sweep_all {
+            let options = SweepOptions {
+                host_policy,
+                rules: scoring_rules,
+                max_depth: max_depth.unwrap_or(SWEEP_MAX_DEPTH),
+                candidate_limit: candidates.filter(|n| *n > 0),
+            };
+            sweep(&guessable, &solutions, options, thread_count);
+            return;
+        }
+        let limit = Some(candidates.unwrap_or(20)).filter(|n| *n > 0);
+        let mut search = ShortestSearch::new(&guessable, &solutions, hard_mode, None, limit);
+        search.set_host_policy(host_policy);
+        search.set_scoring_rules(scoring_rules);
+        if interactive {
+            play_interactively(&search, &solutions, score_format);
+        } else {
+            solve_shortest(&search, &solutions, all, format, score_format);
+        }
+        return;
+    }
+
+    let (guessable, solutions) = if guessable_path.is_empty() {
+        eprintln!("Expected a target word, or two list paths and a target word");
+        std::process::exit(2);
+    } else if solutions_path.is_empty() {
+        // With the paths omitted, the only argument is the target word.
+        target_word = guessable_path;
+        load_bundled().unwrap_or_else(|| {
+            eprintln!("No word lists given, and this build doesn't include bundled lists");
+            std::process::exit(2);
+        })
+    } else if is_stdin_path(guessable_path.as_ref()) && is_stdin_path(solutions_path.as_ref()) {
+        eprintln!("Only one word list can be read from stdin");
+        std::process::exit(2);
+    } else if target_word.is_empty() {
+        eprintln!("Expected a target word, or two list paths and a target word");
+        std::process::exit(2);
+    } else {
+        (
+            load_list_from_file(guessable_path.as_ref()).unwrap(),
+            load_list_from_file(solutions_path.as_ref()).unwrap(),
+        )
+    };
 
     if !solutions.contains(&target_word) {
         println!("'{}' is not in the solution list", target_word);
         std::process::exit(1);
     }
 
-    let mut solver = Solver::new(target_word.as_str(), &guessable, &solutions, hard_mode);
-    solver.solve();
+    if interactive {
+        let limit = Some(candidates.unwrap_or(20)).filter(|n| *n > 0);
+        let target = Some(target_word.as_str());
+        let mut search = ShortestSearch::new(&guessable, &solutions, hard_mode, target, limit);
+        search.set_host_policy(host_policy);
+        search.set_scoring_rules(scoring_rules);
+        play_interactively(&search, &solutions, score_format);
+        return;
+    }
+
+    if shortest {
+        let limit = candidates.filter(|n| *n > 0);
+        let target = Some(target_word.as_str());
+        let mut search = ShortestSearch::new(&guessable, &solutions, hard_mode, target, limit);
+        search.set_host_policy(host_policy);
+        search.set_scoring_rules(scoring_rules);
+        solve_shortest(&search, &solutions, all, format, score_format);
+        return;
+    }
+
+    if all {
+        eprintln!("--all needs --regular or --shortest");
+        std::process::exit(2);
+    }
+
+    let start = Instant::now();
+    // Print the search as it goes: the sequence being tried, then whether it continues, is a
+    // dead end, or wins. With JSON output, stdout is for the result.
+    let show = move |text: &str| {
+        if format == OutputFormat::Json {
+            eprint!("{}", text);
+        } else {
+            print!("{}", text);
+        }
+    };
+    let target = target_word.as_str();
+    let status_shown = Cell::new(false);
+    let mut solver = Solver::new(target, &guessable, &solutions, hard_mode);
+    solver.set_host_policy(host_policy);
+    solver.set_scoring_rules(scoring_rules);
+    if progress {
+        solver.set_status(STATUS_INTERVAL, |stats, moves| {
+            show_status(stats, moves, &status_shown)
+        });
+    } else {
+        solver.set_progress(move |event| match event {
+            SearchEvent::Trying(moves) => {
+                for (guess, _) in moves {
+                    show(&format!("{} ", guess));
+                }
+            }
+            SearchEvent::Continued => show("\n"),
+            SearchEvent::DeadEnd => show("✗\n"),
+            SearchEvent::Won => show(&format!("{} ✔\n", target)),
+        });
+    }
+    if let Some(ref path) = checkpoint_path {
+        let exit_on_error = move |e: std::io::Error| -> ! {
+            eprintln!("{}: {}", path, e);
+            std::process::exit(1);
+        };
+        let header = checkpoint_header(
+            target,
+            hard_mode,
+            host_policy,
+            scoring_rules,
+            max_depth,
+            &guessable,
+            &solutions,
+        );
+        if resume {
+            let stack = read_checkpoint(path, &header).unwrap_or_else(|e| exit_on_error(e));
+            if let Err(e) = solver.resume(&stack) {
+                eprintln!("{}: {}", path, e);
+                std::process::exit(1);
+            }
+        } else if std::path::Path::new(path).exists() {
+            eprintln!(
+                "{}: already exists; add --resume to continue that search, or remove it",
+                path
+            );
+            std::process::exit(1);
+        }
+        solver.set_checkpoint(CHECKPOINT_INTERVAL, move |stack| {
+            write_checkpoint(path, &header, stack).unwrap_or_else(|e| exit_on_error(e));
+        });
+    }
+    let mut solve = |max_depth| {
+        solver.set_max_depth(max_depth);
+        let result = solver.solve();
+        if status_shown.replace(false) {
+            // Finish the status line.
+            eprintln!();
+        }
+        result.map_err(|e| show(&format!("{}\n", e))).ok()
+    };
+    let found = if iterative_deepening {
+        (1..=max_depth.unwrap_or(MAX_SHORTEST_GUESSES)).find_map(|limit| solve(Some(limit)))
+    } else {
+        solve(max_depth)
+    };
+    if found.is_none() {
+        for line in solver.infeasibility().lines() {
+            show(&format!("{}\n", line));
+        }
+    }
+
+    if let Some(moves) = found.as_ref().filter(|_| progress) {
+        // The search wasn't shown, so show the result.
+        let guesses: Vec<&str> = moves.iter().map(|(guess, _)| *guess).collect();
+        show(&format!("{}\n", guesses.join(" ")));
+    }
+    if format == OutputFormat::Json {
+        let wins: Vec<_> = found.iter().cloned().collect();
+        let target = Some(target_word.as_str());
+        print_json(
+            target,
+            hard_mode,
+            &wins,
+            None,
+            solver.stats(),
+            start.elapsed(),
+        );
+    } else {
+        print_stats(solver.stats(), start.elapsed());
+    }
+    if found.is_none() {
+        std::process::exit(1);
+    }
 }
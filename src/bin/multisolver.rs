@@ -1,120 +1,141 @@
 /// Solves multiple boards at once; e.g. https://quordle.com , https://duotrigordle.com
-use std::io::{stdin, stdout};
+use std::collections::BTreeMap;
+use std::io::{stdin, stdout, BufRead, Write};
 
-use argparse::{ArgumentParser, Parse, Store, StoreTrue};
+use argparse::{ArgumentParser, Parse, Store, StoreOption, StoreTrue};
 
-use wordle_solver::eval::{eval_guess, reduce_eval};
-use wordle_solver::loader::load_list_from_file;
+use wordle_solver::default_thread_count;
+use wordle_solver::loader::{is_stdin_path, load_bundled, load_list_from_file};
+use wordle_solver::multi::{
+    parse_labeled_score, parse_score_grid, GridError, MultiSolver, Objective,
+};
 use wordle_solver::read_guess_interactively;
-use wordle_solver::score::{read_score_interactively, DetailScore};
-use wordle_solver::solver::{Solver, Strategy};
+use wordle_solver::score::{read_score_interactively, DetailScore, ScoreFormat, ScoringRules};
+use wordle_solver::solver::Strategy;
 
-pub struct MultiSolver<'a> {
-    solvers: Vec<Solver<'a>>,
-    responded: Vec<bool>,
-    done: Vec<bool>,
-
-    guessable_list: &'a [String],
-    solution_list: &'a [String],
-    strategy: Strategy,
-}
-
-impl<'a> MultiSolver<'a> {
-    pub fn new(
-        count: usize,
-        guessable_list: &'a [String],
-        solution_list: &'a [String],
-        strategy: Strategy,
-    ) -> MultiSolver<'a> {
-        let mut solvers = Vec::new();
-        for _ in 0..count {
-            solvers.push(Solver::new(
-                guessable_list,
-                solution_list,
-                false,
-                true,
-                strategy,
-            ));
-        }
-        MultiSolver {
-            solvers,
-            responded: vec![false; count],
-            done: vec![false; count],
-            guessable_list,
-            solution_list,
-            strategy,
+/// Read pasted rows of emoji squares until a blank line, and record them as this round's scores.
+/// Asks again if they can't be used.
+fn read_pasted_scores<'a>(
+    input: &mut dyn BufRead,
+    output: &mut dyn Write,
+    solver: &mut MultiSolver<'a>,
+    guess: &'a str,
+    sequence: bool,
+) {
+    loop {
+        let needed: Vec<String> = solver
+            .active_boards()
+            .iter()
+            .map(|index| index.to_string())
+            .collect();
+        if sequence {
+            writeln!(
+                output,
+                "Paste the scores for board {}, a row per guess it hasn't been scored on, then a \
+                 blank line:",
+                needed.join(", ")
+            )
+        } else {
+            writeln!(
+                output,
+                "Paste the scores for boards {} (or for every board), then a blank line:",
+                needed.join(", ")
+            )
         }
-    }
+        .unwrap();
+        output.flush().unwrap();
 
-    pub fn index_needing_response(&self) -> Option<usize> {
-        (0..self.responded.len()).find(|idx| !self.responded[*idx] && !self.done[*idx])
-    }
+        let mut text = String::new();
+        loop {
+            let mut line = String::new();
+            if input.read_line(&mut line).unwrap() == 0 {
+                if text.trim().is_empty() {
+                    std::process::exit(1);
+                }
+                break;
+            }
+            if line.trim().is_empty() {
+                if text.trim().is_empty() {
+                    continue;
+                }
+                break;
+            }
+            text.push_str(&line);
+        }
 
-    pub fn all_done(&self) -> bool {
-        self.done.iter().all(|d| *d)
+        let result =
+            parse_score_grid(&text).and_then(|scores| solver.respond_to_scores(guess, &scores));
+        match result {
+            Ok(()) => return,
+            Err(e) => writeln!(output, "{}", e).unwrap(),
+        }
     }
+}
 
-    pub fn next_guess(&self) -> &'a str {
-        for (index, solver) in self.solvers.iter().enumerate() {
-            if !self.done[index] && solver.get_possibilities().len() == 1 {
-                return solver.get_possibilities()[0];
-            }
+/// Read scores labeled with their boards, like "3: acppc", in any order until a blank line, and
+/// record them. Entering a board's score again replaces it. Boards left out, and boards whose
+/// score doesn't match any of their possibilities, are asked for afterwards.
+fn read_labeled_scores<'a>(
+    input: &mut dyn BufRead,
+    output: &mut dyn Write,
+    solver: &mut MultiSolver<'a>,
+    guess: &'a str,
+) {
+    let mut scores: BTreeMap<usize, DetailScore> = BTreeMap::new();
+    loop {
+        let needed: Vec<String> = solver
+            .boards_needing_score(guess)
+            .iter()
+            .map(|index| index.to_string())
+            .collect();
+        if needed.is_empty() {
+            return;
         }
+        writeln!(
+            output,
+            "Enter the scores for boards {} as 'board: score', in any order, then a blank line:",
+            needed.join(", ")
+        )
+        .unwrap();
+        output.flush().unwrap();
 
-        let mut best_eval = (i32::MIN, i32::MIN);
-        let mut best_guesses: Vec<&str> = Vec::new();
-
-        for guess in self.solution_list.iter().chain(self.guessable_list.iter()) {
-            // Don't get evals from solvers that are already done.
-            let reduced = self
-                .solvers
-                .iter()
-                .filter(|solver| solver.get_possibilities().len() != 1)
-                .map(|solver| eval_guess(guess, solver.get_possibilities()))
-                .reduce(reduce_eval)
-                .unwrap();
-
-            let eval = if self.strategy == Strategy::GroupCount {
-                (reduced.count, reduced.size)
-            } else {
-                (reduced.size, reduced.count)
-            };
-
-            if eval > best_eval {
-                best_eval = eval;
-                best_guesses.clear();
+        loop {
+            let mut line = String::new();
+            if input.read_line(&mut line).unwrap() == 0 {
+                if scores.is_empty() {
+                    std::process::exit(1);
+                }
+                break;
+            }
+            if line.trim().is_empty() {
+                break;
             }
-            if eval == best_eval {
-                best_guesses.push(guess);
+            let result = parse_labeled_score(&line).and_then(|(board, score)| {
+                if solver.boards_needing_score(guess).contains(&board) {
+                    Ok((board, score))
+                } else {
+                    Err(GridError::NotNeeded(board))
+                }
+            });
+            match result {
+                Ok((board, score)) => {
+                    scores.insert(board, score);
+                }
+                Err(e) => writeln!(output, "{}", e).unwrap(),
             }
         }
 
-        best_guesses
-            .iter()
-            .max_by_key(|guess| {
-                self.solvers
-                    .iter()
-                    .enumerate()
-                    .filter(|(idx, solver)| {
-                        !self.done[*idx] && solver.get_possibilities().contains(guess)
-                    })
-                    .count()
-            })
-            .unwrap()
-    }
-
-    pub fn respond_to_score(&mut self, index: usize, guess: &'a str, score: DetailScore) {
-        assert!(!self.responded[index]);
-        self.solvers[index].respond_to_score(guess, score);
-        self.responded[index] = true;
-        if score.is_win() {
-            self.done[index] = true;
+        let labeled: Vec<(usize, DetailScore)> = scores.iter().map(|(b, s)| (*b, *s)).collect();
+        match solver.respond_to_labeled_scores(guess, &labeled) {
+            Ok(()) => return,
+            Err(e) => {
+                if let GridError::Inconsistent(board) = e {
+                    scores.remove(&board);
+                }
+                writeln!(output, "{}", e).unwrap();
+            }
         }
     }
-
-    pub fn next_round(&mut self) {
-        self.responded = vec![false; self.responded.len()];
-    }
 }
 
 fn main() {
@@ -123,7 +144,19 @@ fn main() {
 
     let mut count = 4;
     let mut enter_guesses = false;
+    let mut paste = false;
+    let mut labeled = false;
+    let mut sequence = false;
+    let mut thread_count = default_thread_count();
+    let mut objective = Objective::Combined;
+    let mut merge_duplicates = false;
+    let mut matrix = false;
+    let mut score_format = ScoreFormat::default_for_stdout();
+    let mut width = 80;
+    let mut hard_mode = false;
+    let mut max_guesses: Option<usize> = None;
     let mut strategy = Strategy::GroupSize;
+    let mut scoring_rules = ScoringRules::Wordle;
     let mut guessable_path = "".to_string();
     let mut solutions_path = "".to_string();
 
@@ -136,52 +169,224 @@ fn main() {
             Parse,
             "Which solving strategy to use: groupcount or groupsize (default)",
         );
+        parser.refer(&mut scoring_rules).add_option(
+            &["--scoring-rules"],
+            Parse,
+            "How guesses are scored: wordle (default) or all-present",
+        );
+        parser.refer(&mut hard_mode).add_option(
+            &["--hard-mode"],
+            StoreTrue,
+            "Only guess words that use every hint found on the unsolved boards",
+        );
+        parser.refer(&mut max_guesses).add_option(
+            &["--max-guesses"],
+            StoreOption,
+            concat!(
+                "How many guesses are allowed (default: 5 more than the number of boards, as in ",
+                "Quordle, Octordle and Duotrigordle); 0 for no limit"
+            ),
+        );
         parser.refer(&mut enter_guesses).add_option(
             &["--enter-guesses"],
             StoreTrue,
             "Manually enter guesses instead of automatically using generated ones",
         );
 
+        parser.refer(&mut sequence).add_option(
+            &["--sequence"],
+            StoreTrue,
+            concat!(
+                "Play a sequence game, where only the first unsolved board shows scores, and the ",
+                "next one shows every guess's score once it's solved"
+            ),
+        );
+        parser.refer(&mut objective).add_option(
+            &["--objective"],
+            Parse,
+            concat!(
+                "How to weigh the boards against each other: combined (default), worst (help the ",
+                "board with the most possibilities), total (fewest guesses over all boards) or ",
+                "finish (solve boards as soon as possible)"
+            ),
+        );
+        parser.refer(&mut merge_duplicates).add_option(
+            &["--merge-duplicates"],
+            StoreTrue,
+            "Count boards that have scored every guess the same once when choosing guesses",
+        );
+        parser.refer(&mut thread_count).add_option(
+            &["--thread-count"],
+            Store,
+            "Number of threads to evaluate guesses on (default: one per CPU)",
+        );
+        parser.refer(&mut paste).add_option(
+            &["--paste"],
+            StoreTrue,
+            "Enter every board's score at once, by pasting rows of emoji squares",
+        );
+        parser.refer(&mut labeled).add_option(
+            &["--labeled"],
+            StoreTrue,
+            concat!(
+                "Enter scores as 'board: score', in any order, and enter a board again to fix its ",
+                "score"
+            ),
+        );
+
+        parser.refer(&mut matrix).add_option(
+            &["--matrix"],
+            StoreTrue,
+            "After each round, also show every board's scores so far as a table",
+        );
+        parser.refer(&mut score_format).add_option(
+            &["--score-format"],
+            Parse,
+            concat!(
+                "How --matrix prints scores: apc, byg, emoji, or ansi (default: ansi on a ",
+                "terminal, otherwise apc)"
+            ),
+        );
+        parser.refer(&mut width).add_option(
+            &["--width"],
+            Store,
+            "How many columns wide --matrix can be (default 80); the earliest guesses are left out",
+        );
+
         parser.refer(&mut guessable_path).required().add_argument(
             "guessable-path",
             Store,
-            "The path to the file of guessable strings",
+            "The path to the file of guessable strings. Omit both paths to use the bundled lists.",
         );
-        parser.refer(&mut solutions_path).required().add_argument(
+        parser.refer(&mut solutions_path).add_argument(
             "solutions-path",
             Store,
             "The path to the file of possible solutions",
         );
-        parser.refer(&mut count).required().add_argument(
-            "count",
-            Store,
-            "How many boards to solve",
-        );
+        parser
+            .refer(&mut count)
+            .add_argument("count", Store, "How many boards to solve");
         parser.parse_args_or_exit();
     }
 
-    let guessable_list = load_list_from_file(guessable_path.as_ref()).unwrap();
-    let solution_list = load_list_from_file(solutions_path.as_ref()).unwrap();
+    if is_stdin_path(guessable_path.as_ref()) || is_stdin_path(solutions_path.as_ref()) {
+        eprintln!("Can't read a word list from stdin, since scores are read from it");
+        std::process::exit(2);
+    }
+
+    let (guessable_list, solution_list) = if solutions_path.is_empty() {
+        // With the paths omitted, the only argument is the count.
+        count = guessable_path.parse().unwrap_or_else(|_| {
+            eprintln!("Expected a board count, or two list paths and a count");
+            std::process::exit(2);
+        });
+        load_bundled().unwrap_or_else(|| {
+            eprintln!("No word lists given, and this build doesn't include bundled lists");
+            std::process::exit(2);
+        })
+    } else {
+        (
+            load_list_from_file(guessable_path.as_ref()).unwrap(),
+            load_list_from_file(solutions_path.as_ref()).unwrap(),
+        )
+    };
 
     let mut solver = MultiSolver::new(count, &guessable_list, &solution_list, strategy);
+    solver.set_scoring_rules(scoring_rules);
+    solver.set_hard_mode(hard_mode);
+    solver.set_sequence(sequence);
+    solver.set_thread_count(thread_count);
+    solver.set_objective(objective);
+    solver.set_merge_duplicates(merge_duplicates);
+    let max_guesses = max_guesses.unwrap_or(count + 5);
+    if max_guesses > 0 {
+        solver.set_max_guesses(max_guesses);
+    }
 
     loop {
         println!("==============================");
 
+        let unsolved: Vec<String> = solver
+            .unsolved_boards()
+            .iter()
+            .map(|index| index.to_string())
+            .collect();
+        if solver.is_lost() {
+            println!("Out of guesses! Unsolved boards: {}", unsolved.join(", "));
+            std::process::exit(1);
+        }
+        if let Some(remaining) = solver.guesses_remaining() {
+            println!(
+                "Guess {} of {}; unsolved boards: {}",
+                solver.guesses_used() + 1,
+                max_guesses,
+                unsolved.join(", ")
+            );
+            if remaining < solver.unsolved_boards().len() {
+                println!("Not enough guesses left to solve every board");
+            }
+        }
+
+        let recommended = solver.next_guess();
+        if hard_mode && solver.was_played(recommended) {
+            println!("No new guess is allowed in hard mode; the boards can't all be solved");
+            std::process::exit(1);
+        }
         let guess = if enter_guesses {
-            println!("Recommended: {}", solver.next_guess());
-            read_guess_interactively(&mut input, &mut output, &guessable_list, &solution_list)
+            println!("Recommended: {}", recommended);
+            loop {
+                let guess = read_guess_interactively(
+                    &mut input,
+                    &mut output,
+                    &guessable_list,
+                    &solution_list,
+                );
+                if solver.is_hard_mode_legal(guess) {
+                    break guess;
+                }
+                println!("Not allowed in hard mode");
+            }
         } else {
-            let g = solver.next_guess();
-            println!("Guess: {}", g);
-            g
+            println!("Guess: {}", recommended);
+            recommended
         };
         solver.next_round();
 
+        if paste {
+            read_pasted_scores(&mut input, &mut output, &mut solver, guess, sequence);
+        } else if labeled {
+            read_labeled_scores(&mut input, &mut output, &mut solver, guess);
+        }
         while let Some(index) = solver.index_needing_response() {
-            println!("Need score for index {}", index);
+            let board_guess = solver.pending_guess(index).unwrap_or(guess);
+            if board_guess == guess {
+                println!("Need score for index {}", index);
+            } else {
+                println!("Need score for index {}, guess {}", index, board_guess);
+            }
             let score = read_score_interactively(&mut input, &mut output);
-            solver.respond_to_score(index, guess, score);
+            solver.respond_to_score(index, board_guess, score);
+        }
+
+        if matrix {
+            for line in solver.progress_lines(score_format, width) {
+                println!("{}", line);
+            }
+        }
+        for line in solver.status_lines() {
+            println!("{}", line);
+        }
+        for group in solver.duplicate_boards() {
+            let boards: Vec<String> = group.iter().map(|index| index.to_string()).collect();
+            println!(
+                "Boards {} have scored every guess the same, and may have the same answer{}",
+                boards.join(", "),
+                if merge_duplicates {
+                    " (counted once)"
+                } else {
+                    ""
+                }
+            );
         }
 
         if solver.all_done() {
@@ -0,0 +1,256 @@
+use std::io::{stderr, stdout};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use argparse::{ArgumentParser, Store, StoreOption};
+
+use wordle_solver::default_thread_count;
+use wordle_solver::loader::load_list_from_file;
+use wordle_solver::prove::Prover;
+use wordle_solver::score::DetailScore;
+use wordle_solver::solver::{Outcome, Solver, Strategy};
+
+use crate::histogram::run_solver;
+
+/// An answer that a strategy needs many guesses for, and a better way to play it.
+struct Challenge<'a> {
+    answer: &'a str,
+    greedy: Vec<(&'a str, DetailScore)>,
+
+    /// Moves that solve the answer in fewer guesses. They follow the greedy line for a while,
+    /// then a tree that solves every possibility left at that point within fewer guesses, so
+    /// they could be played without knowing the answer.
+    better: Option<Vec<(&'a str, DetailScore)>>,
+}
+
+/// Play every answer with the strategy, split across threads, and return the games that took at
+/// least `min_guesses` guesses, in list order.
+fn hard_games<'a>(
+    guessable: &'a [String],
+    solutions: &'a [String],
+    strategy: Strategy,
+    min_guesses: usize,
+    thread_count: usize,
+) -> Vec<(&'a str, Vec<(&'a str, DetailScore)>)> {
+    let first_guess = Solver::new(guessable, solutions, false, false, strategy).next_guess();
+    let next_index = AtomicUsize::new(0);
+    let mut games: Vec<(usize, Vec<(&str, DetailScore)>)> = std::thread::scope(|scope| {
+        let threads: Vec<_> = (0..thread_count.max(1))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut games = Vec::new();
+                    loop {
+                        let index = next_index.fetch_add(1, Ordering::Relaxed);
+                        let Some(answer) = solutions.get(index) else {
+                            break;
+                        };
+                        let solver = Solver::new(guessable, solutions, false, false, strategy);
+                        let (outcome, moves) = run_solver(solver, first_guess, answer);
+                        if outcome >= Outcome::Won(min_guesses) {
+                            games.push((index, moves));
+                        }
+                    }
+                    games
+                })
+            })
+            .collect();
+        threads
+            .into_iter()
+            .flat_map(|thread| thread.join().unwrap())
+            .collect()
+    });
+    games.sort_by_key(|(index, _)| *index);
+    games
+        .into_iter()
+        .map(|(index, moves)| (solutions[index].as_str(), moves))
+        .collect()
+}
+
+/// Look for a better way to play a game: keep the greedy moves up to some point, then switch to
+/// a tree that solves every remaining possibility in time to beat the greedy line. Tries the
+/// latest switching point first, since that's the smallest change and the quickest search.
+fn find_better<'a>(
+    prover: &mut Prover<'a>,
+    guessable: &'a [String],
+    solutions: &'a [String],
+    answer: &'a str,
+    greedy: &[(&'a str, DetailScore)],
+) -> Option<Vec<(&'a str, DetailScore)>> {
+    // Only used to narrow down the possibilities, so the strategy doesn't matter.
+    let mut solver = Solver::new(guessable, solutions, false, false, Strategy::GroupSize);
+    let mut possibilities_after: Vec<Vec<&'a str>> = vec![solver.get_possibilities().to_vec()];
+    for (guess, score) in greedy.iter() {
+        solver.respond_to_score(guess, *score);
+        possibilities_after.push(solver.get_possibilities().to_vec());
+    }
+
+    let rules = solver.scoring_rules();
+    for kept in (0..greedy.len() - 1).rev() {
+        let budget = greedy.len() - 1 - kept;
+        if let Some(tree) = prover.solve(&possibilities_after[kept], budget) {
+            let mut moves = greedy[..kept].to_vec();
+            moves.extend(tree.play(answer, rules)?.into_iter().map(|(guess, score)| {
+                let guess = guessable
+                    .iter()
+                    .chain(solutions.iter())
+                    .find(|word| *word == guess)
+                    .unwrap();
+                (guess.as_str(), score)
+            }));
+            return Some(moves);
+        }
+    }
+    None
+}
+
+fn describe(moves: &[(&str, DetailScore)]) -> String {
+    let guesses: Vec<&str> = moves.iter().map(|(guess, _)| *guess).collect();
+    format!("{} ({} guesses)", guesses.join(" "), moves.len())
+}
+
+/// `wordle-solver challenges`: find answers that the greedy strategies need many guesses for,
+/// but that a deeper search solves in fewer.
+pub fn command(args: Vec<String>) {
+    let mut guessable_path = String::new();
+    let mut solutions_path = String::new();
+    let mut strategy: Option<Strategy> = None;
+    let mut min_guesses = 6;
+    let mut candidates = 20;
+    let mut thread_count = default_thread_count();
+
+    {
+        let mut parser = ArgumentParser::new();
+        parser.set_description(concat!(
+            "Find answers that the solver's strategies need many guesses for, but that a deeper ",
+            "search can solve in fewer, and show the better lines"
+        ));
+        parser.refer(&mut guessable_path).required().add_argument(
+            "guessable",
+            Store,
+            "The guessable list",
+        );
+        parser.refer(&mut solutions_path).required().add_argument(
+            "solutions",
+            Store,
+            "The solutions list",
+        );
+        parser.refer(&mut strategy).add_option(
+            &["--strategy"],
+            StoreOption,
+            "Only check this strategy: groupcount or groupsize (default: both)",
+        );
+        parser.refer(&mut min_guesses).add_option(
+            &["--min-guesses"],
+            Store,
+            "Check answers that a strategy needs at least this many guesses for (default 6)",
+        );
+        parser.refer(&mut candidates).add_option(
+            &["--candidates"],
+            Store,
+            "How many guesses the search tries at each step, best first (default 20); 0 for all",
+        );
+        parser.refer(&mut thread_count).add_option(
+            &["--thread-count"],
+            Store,
+            "Number of threads to use (default: one per CPU)",
+        );
+        if let Err(code) = parser.parse(args, &mut stdout(), &mut stderr()) {
+            std::process::exit(code);
+        }
+    }
+
+    let [guessable, solutions] = [guessable_path, solutions_path].map(|path| {
+        load_list_from_file(path.as_ref()).unwrap_or_else(|e| {
+            eprintln!("{}: {}", path, e);
+            std::process::exit(1);
+        })
+    });
+    let strategies = match strategy {
+        Some(strategy) => vec![strategy],
+        None => vec![Strategy::GroupCount, Strategy::GroupSize],
+    };
+
+    let mut prover = Prover::new(&guessable, &solutions);
+    prover.set_candidate_limit(Some(candidates).filter(|n| *n > 0));
+
+    for strategy in strategies {
+        let challenges: Vec<Challenge> = hard_games(
+            &guessable,
+            &solutions,
+            strategy,
+            min_guesses.max(2),
+            thread_count,
+        )
+        .into_iter()
+        .map(|(answer, greedy)| Challenge {
+            answer,
+            better: find_better(&mut prover, &guessable, &solutions, answer, &greedy),
+            greedy,
+        })
+        .collect();
+
+        let improved = challenges.iter().filter(|c| c.better.is_some()).count();
+        println!(
+            "{}: {} answers need {} or more guesses; {} can be solved in fewer",
+            strategy.to_string().to_uppercase(),
+            challenges.len(),
+            min_guesses,
+            improved
+        );
+        for challenge in challenges.iter() {
+            if let Some(ref better) = challenge.better {
+                println!("  {}: {}", challenge.answer, describe(&challenge.greedy));
+                println!("    better: {}", describe(better));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wordle_solver::score::compute_score;
+
+    fn words(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_hard_games() {
+        // With only these words to guess, the solver tries them one at a time, in list order.
+        let solutions = words(&["fight", "light", "might", "night", "sight", "tight"]);
+        let games = hard_games(&[], &solutions, Strategy::GroupSize, 5, 2);
+        let answers: Vec<&str> = games.iter().map(|(answer, _)| *answer).collect();
+        assert_eq!(answers, ["sight", "tight"]);
+        assert_eq!(games[1].1.len(), 6);
+    }
+
+    #[test]
+    fn test_find_better() {
+        let guessable = words(&["films"]);
+        let solutions = words(&["fight", "light", "might", "night", "sight", "tight"]);
+        let mut prover = Prover::new(&guessable, &solutions);
+        let line = |guesses: &[&'static str]| -> Vec<(&str, DetailScore)> {
+            guesses
+                .iter()
+                .map(|guess| (*guess, compute_score(guess, "tight")))
+                .collect()
+        };
+
+        // "films" sorts out all but "night" and "tight", so three guesses always do.
+        let greedy = line(&["fight", "light", "might", "night", "tight"]);
+        let better = find_better(&mut prover, &guessable, &solutions, "tight", &greedy).unwrap();
+        assert!(better.len() < greedy.len());
+        assert_eq!(better.last().unwrap().0, "tight");
+        assert!(better.last().unwrap().1.is_win());
+        for (guess, score) in better.iter() {
+            assert_eq!(compute_score(guess, "tight"), *score);
+        }
+
+        // Nothing wins in two guesses for sure.
+        let greedy = line(&["films", "tight"]);
+        assert_eq!(
+            find_better(&mut prover, &guessable, &solutions, "tight", &greedy),
+            None
+        );
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Display;
+
+use crate::score::{DetailScore, LetterScore};
+
+/// Constraints on the solution, in terms of individual letters, as learned from scores. A single
+/// guess and score produce constraints that match exactly the words that would give the guess that
+/// score, and constraints from several guesses can be merged.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Constraints {
+    /// The letter known to be in each position, if any.
+    pub fixed: [Option<char>; 5],
+
+    /// Letters known not to be in each position.
+    pub excluded: [BTreeSet<char>; 5],
+
+    /// The minimum number of times each letter appears in the solution.
+    pub min_counts: BTreeMap<char, usize>,
+
+    /// The maximum number of times each letter appears in the solution, for letters where that's
+    /// known. Letters known to be absent have a maximum of 0.
+    pub max_counts: BTreeMap<char, usize>,
+}
+
+impl Constraints {
+    /// The constraints implied by `guess` getting `score`.
+    pub fn from_score(guess: &str, score: DetailScore) -> Self {
+        let mut result = Constraints::default();
+        let mut absent = BTreeSet::new();
+
+        for (i, (c, letter)) in guess.chars().zip(score.letters()).enumerate() {
+            match letter {
+                LetterScore::Correct => {
+                    result.fixed[i] = Some(c);
+                    *result.min_counts.entry(c).or_default() += 1;
+                }
+                LetterScore::Present => {
+                    result.excluded[i].insert(c);
+                    *result.min_counts.entry(c).or_default() += 1;
+                }
+                LetterScore::Absent => {
+                    // If the letter were here, it would have been correct.
+                    result.excluded[i].insert(c);
+                    absent.insert(c);
+                }
+            }
+        }
+
+        // An absent letter means the solution has no more copies of it than were scored correct
+        // or present.
+        for c in absent {
+            let count = result.min_counts.get(&c).copied().unwrap_or(0);
+            result.max_counts.insert(c, count);
+        }
+
+        result
+    }
+
+    /// The constraints implied by all the guesses and scores in `history`.
+    pub fn from_history(history: &[(&str, DetailScore)]) -> Self {
+        let mut result = Constraints::default();
+        for (guess, score) in history.iter() {
+            result.merge(&Constraints::from_score(guess, *score));
+        }
+        result
+    }
+
+    /// Add the constraints from `other` to these. If they contradict each other, the result
+    /// matches no words.
+    pub fn merge(&mut self, other: &Constraints) {
+        for i in 0..5 {
+            match (self.fixed[i], other.fixed[i]) {
+                (None, theirs) => self.fixed[i] = theirs,
+                (Some(ours), Some(theirs)) if ours != theirs => {
+                    // Two different letters can't both be in this position. Excluding our letter
+                    // too makes this position impossible to satisfy.
+                    self.excluded[i].insert(ours);
+                }
+                _ => {}
+            }
+            self.excluded[i].extend(other.excluded[i].iter().copied());
+        }
+
+        for (c, count) in other.min_counts.iter() {
+            let min = self.min_counts.entry(*c).or_default();
+            *min = (*min).max(*count);
+        }
+        for (c, count) in other.max_counts.iter() {
+            let max = self.max_counts.entry(*c).or_insert(*count);
+            *max = (*max).min(*count);
+        }
+    }
+
+    /// Whether `word` satisfies all the constraints.
+    pub fn matches(&self, word: &str) -> bool {
+        let mut counts: BTreeMap<char, usize> = BTreeMap::new();
+
+        for (i, c) in word.chars().enumerate() {
+            if self.fixed[i].is_some_and(|fixed| fixed != c) || self.excluded[i].contains(&c) {
+                return false;
+            }
+            *counts.entry(c).or_default() += 1;
+        }
+
+        let count = |c: &char| counts.get(c).copied().unwrap_or(0);
+        self.min_counts.iter().all(|(c, min)| count(c) >= *min)
+            && self.max_counts.iter().all(|(c, max)| count(c) <= *max)
+    }
+}
+
+/// A summary like `..a.e; has a, e×2; a not at 1, 3; at most 2 e; no i, l, t`: the known
+/// letters by position, the letters known to be in the word (with how many times, if more than
+/// once), where they can't be, the most times they can appear, and the letters known to be absent.
+impl Display for Constraints {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let pattern: String = self.fixed.iter().map(|c| c.unwrap_or('.')).collect();
+        let mut parts = vec![pattern];
+
+        let has: Vec<String> = self
+            .min_counts
+            .iter()
+            .map(|(c, count)| match count {
+                1 => c.to_string(),
+                _ => format!("{}×{}", c, count),
+            })
+            .collect();
+        if !has.is_empty() {
+            parts.push(format!("has {}", has.join(", ")));
+        }
+
+        let absent = |c: &char| self.max_counts.get(c) == Some(&0);
+        let mut not_at: BTreeMap<char, Vec<String>> = BTreeMap::new();
+        for (i, excluded) in self.excluded.iter().enumerate() {
+            for c in excluded.iter().filter(|c| !absent(c)) {
+                not_at.entry(*c).or_default().push((i + 1).to_string());
+            }
+        }
+        for (c, positions) in not_at {
+            parts.push(format!("{} not at {}", c, positions.join(", ")));
+        }
+
+        for (c, max) in self.max_counts.iter().filter(|(_, max)| **max > 0) {
+            parts.push(format!("at most {} {}", max, c));
+        }
+
+        let absent: Vec<String> = self
+            .max_counts
+            .keys()
+            .filter(|c| absent(c))
+            .map(char::to_string)
+            .collect();
+        if !absent.is_empty() {
+            parts.push(format!("no {}", absent.join(", ")));
+        }
+
+        write!(f, "{}", parts.join("; "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::score::compute_score;
+
+    const WORDS: [&str; 12] = [
+        "arise", "verge", "speed", "eerie", "geese", "merge", "serve", "squid", "motto", "lofty",
+        "paper", "repeg",
+    ];
+
+    #[test]
+    fn test_from_score_matches_compute_score() {
+        for guess in WORDS {
+            for solution in WORDS {
+                let score = compute_score(guess, solution);
+                let constraints = Constraints::from_score(guess, score);
+                for word in WORDS {
+                    assert_eq!(
+                        constraints.matches(word),
+                        compute_score(guess, word) == score,
+                        "guess {} solution {} word {}",
+                        guess,
+                        solution,
+                        word
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_merge() {
+        let history = [
+            ("arise", compute_score("arise", "verge")),
+            ("speed", compute_score("speed", "verge")),
+        ];
+        let constraints = Constraints::from_history(&history);
+        for word in WORDS {
+            let expected = history
+                .iter()
+                .all(|(guess, score)| compute_score(guess, word) == *score);
+            assert_eq!(constraints.matches(word), expected, "{}", word);
+        }
+
+        let mut contradictory = Constraints::from_score("verge", "ccccc".parse().unwrap());
+        contradictory.merge(&Constraints::from_score("merge", "ccccc".parse().unwrap()));
+        assert!(WORDS.iter().all(|word| !contradictory.matches(word)));
+    }
+
+    #[test]
+    fn test_display() {
+        let constraints = Constraints::from_score("speed", compute_score("speed", "verge"));
+        assert_eq!(
+            constraints.to_string(),
+            ".....; has e×2; e not at 3, 4; no d, p, s"
+        );
+        let constraints = Constraints::from_score("eerie", compute_score("eerie", "verge"));
+        assert_eq!(
+            constraints.to_string(),
+            ".er.e; has e×2, r; e not at 1; at most 2 e; no i"
+        );
+        assert_eq!(Constraints::default().to_string(), ".....");
+    }
+}
@@ -1,4 +1,6 @@
-use crate::score::{compute_score, NUM_POSSIBLE_SCORES};
+use std::fmt::Display;
+
+use crate::score::{ScoringRules, NUM_POSSIBLE_SCORES};
 
 pub struct Eval {
     pub count: i32,
@@ -6,15 +8,14 @@ pub struct Eval {
 }
 
 /// Score the given guess against the possibility list. Higher score is better.
-pub fn eval_guess(guess: &str, possibilities: &[&str]) -> Eval {
+pub fn eval_guess(guess: &str, possibilities: &[&str], rules: ScoringRules) -> Eval {
     let mut groups = [0; NUM_POSSIBLE_SCORES as usize];
 
     // For each possible solution, compute what score this guess would get if that were the
     // actual solution. All strategies make use of this information.
     //
     // Count how many possible solutions would result in each possible score.
-    for possible_sol in possibilities.iter() {
-        let score = compute_score(guess, possible_sol);
+    for score in rules.compute_scores(guess, possibilities) {
         groups[score.as_num() as usize] += 1;
     }
 
@@ -36,3 +37,62 @@ pub fn reduce_eval(a: Eval, b: Eval) -> Eval {
         size: a.size.max(b.size),
     }
 }
+
+/// More detailed statistics about how a guess splits up the possibility list than `Eval`. These
+/// are for presenting to humans; they're too expensive to compute for every guess.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GuessStats {
+    /// How many distinct scores the guess could get.
+    pub group_count: usize,
+
+    /// The number of possibilities that would be left after the least informative score.
+    pub max_group: usize,
+
+    /// The expected information gained from the score, in bits.
+    pub entropy: f64,
+
+    /// The probability that the guess is the solution.
+    pub win_probability: f64,
+}
+
+impl Display for GuessStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} groups, largest {}, {:.2} bits, {:.0}% chance to win",
+            self.group_count,
+            self.max_group,
+            self.entropy,
+            self.win_probability * 100.0
+        )
+    }
+}
+
+/// Compute `GuessStats` for the given guess against the possibility list.
+pub fn guess_stats(guess: &str, possibilities: &[&str], rules: ScoringRules) -> GuessStats {
+    let mut groups = [0; NUM_POSSIBLE_SCORES as usize];
+    for score in rules.compute_scores(guess, possibilities) {
+        groups[score.as_num() as usize] += 1;
+    }
+
+    let total = possibilities.len() as f64;
+    let entropy = groups
+        .iter()
+        .filter(|g| **g != 0)
+        .map(|g| {
+            let p = *g as f64 / total;
+            -p * p.log2()
+        })
+        .sum();
+
+    GuessStats {
+        group_count: groups.iter().filter(|g| **g != 0).count(),
+        max_group: *groups.iter().max().unwrap(),
+        entropy,
+        win_probability: if possibilities.contains(&guess) {
+            1.0 / total
+        } else {
+            0.0
+        },
+    }
+}
@@ -0,0 +1,175 @@
+//! Rules for leaving words out of a list, e.g. for house-rule games and custom clones. Filters
+//! are applied by the loader (see `LoadOptions::filter`), after words are validated.
+
+use std::collections::BTreeSet;
+
+use regex::Regex;
+
+/// Which words to keep. Build one with the methods below, or from clauses like the command line's
+/// `--filter` (see `add_clause`). The default keeps every word.
+#[derive(Clone, Debug, Default)]
+pub struct WordFilter {
+    excluded_patterns: Vec<Regex>,
+    excluded_letters: BTreeSet<char>,
+    plurals: Option<bool>,
+    min_percentile: Option<f64>,
+}
+
+impl PartialEq for WordFilter {
+    fn eq(&self, other: &Self) -> bool {
+        self.excluded_patterns.len() == other.excluded_patterns.len()
+            && self
+                .excluded_patterns
+                .iter()
+                .zip(other.excluded_patterns.iter())
+                .all(|(a, b)| a.as_str() == b.as_str())
+            && self.excluded_letters == other.excluded_letters
+            && self.plurals == other.plurals
+            && self.min_percentile == other.min_percentile
+    }
+}
+
+/// Whether a word looks like a plural: it ends in "s", but not "ss".
+pub fn is_plural_like(word: &str) -> bool {
+    word.ends_with('s') && !word.ends_with("ss")
+}
+
+impl WordFilter {
+    /// Leave out words matching a regular expression (anywhere in the word, unless anchored).
+    pub fn exclude_pattern(&mut self, pattern: &str) -> Result<&mut Self, regex::Error> {
+        self.excluded_patterns.push(Regex::new(pattern)?);
+        Ok(self)
+    }
+
+    /// Leave out words containing any of these letters.
+    pub fn exclude_letters(&mut self, letters: &str) -> &mut Self {
+        self.excluded_letters.extend(letters.chars());
+        self
+    }
+
+    /// Leave out plural-like words (see `is_plural_like`).
+    pub fn exclude_plurals(&mut self) -> &mut Self {
+        self.plurals = Some(false);
+        self
+    }
+
+    /// Keep only plural-like words (see `is_plural_like`).
+    pub fn require_plurals(&mut self) -> &mut Self {
+        self.plurals = Some(true);
+        self
+    }
+
+    /// Leave out the least frequent `percentile` percent of the list, by the frequencies given
+    /// in the list (see `LoadOptions::load_weighted`). Words with equal frequencies are kept or
+    /// left out together.
+    pub fn min_percentile(&mut self, percentile: f64) -> &mut Self {
+        self.min_percentile = Some(percentile);
+        self
+    }
+
+    /// Add a rule written as text, as in the command line's `--filter`:
+    ///   - `exclude=<regex>`: `exclude_pattern`
+    ///   - `exclude-letters=<letters>`: `exclude_letters`
+    ///   - `no-plurals`: `exclude_plurals`
+    ///   - `only-plurals`: `require_plurals`
+    ///   - `min-percentile=<0-100>`: `min_percentile`
+    pub fn add_clause(&mut self, clause: &str) -> Result<(), String> {
+        let (name, value) = match clause.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (clause, None),
+        };
+        match (name, value) {
+            ("exclude", Some(pattern)) => {
+                self.exclude_pattern(pattern).map_err(|e| e.to_string())?;
+            }
+            ("exclude-letters", Some(letters)) => {
+                self.exclude_letters(letters);
+            }
+            ("no-plurals", None) => {
+                self.exclude_plurals();
+            }
+            ("only-plurals", None) => {
+                self.require_plurals();
+            }
+            ("min-percentile", Some(percentile)) => match percentile.parse::<f64>() {
+                Ok(p) if (0.0..=100.0).contains(&p) => {
+                    self.min_percentile(p);
+                }
+                _ => return Err(format!("Invalid percentile: {}", percentile)),
+            },
+            _ => return Err(format!("Invalid filter: {}", clause)),
+        }
+        Ok(())
+    }
+
+    /// Whether a word passes the rules that don't depend on the rest of the list.
+    pub fn keeps(&self, word: &str) -> bool {
+        !self.excluded_patterns.iter().any(|re| re.is_match(word))
+            && !word.chars().any(|c| self.excluded_letters.contains(&c))
+            && self
+                .plurals
+                .is_none_or(|plural| is_plural_like(word) == plural)
+    }
+
+    /// The words of a list, with their frequencies, that pass all the rules.
+    pub fn apply(&self, entries: Vec<(String, f64)>) -> Vec<(String, f64)> {
+        let threshold = self.min_percentile.and_then(|percentile| {
+            let mut weights: Vec<f64> = entries.iter().map(|(_, weight)| *weight).collect();
+            weights.sort_by(f64::total_cmp);
+            let index = (weights.len() as f64 * percentile / 100.0) as usize;
+            weights.get(index).copied()
+        });
+        entries
+            .into_iter()
+            .filter(|(word, weight)| {
+                self.keeps(word)
+                    && match threshold {
+                        Some(threshold) => *weight >= threshold,
+                        // With no threshold, either there's no percentile rule, or it's 100,
+                        // which no word reaches.
+                        None => self.min_percentile.is_none(),
+                    }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(entries: &[(String, f64)]) -> Vec<&str> {
+        entries.iter().map(|(word, _)| word.as_str()).collect()
+    }
+
+    #[test]
+    fn test_filter() {
+        let entries: Vec<(String, f64)> = [
+            ("cigar", 4.0),
+            ("books", 3.0),
+            ("grass", 2.0),
+            ("xylyl", 1.0),
+        ]
+        .iter()
+        .map(|(word, weight)| (word.to_string(), *weight))
+        .collect();
+
+        let mut filter = WordFilter::default();
+        assert_eq!(filter.apply(entries.clone()), entries);
+
+        filter.exclude_plurals().exclude_letters("x");
+        assert_eq!(words(&filter.apply(entries.clone())), ["cigar", "grass"]);
+
+        let mut filter = WordFilter::default();
+        filter.add_clause("exclude=^ci").unwrap();
+        filter.add_clause("min-percentile=50").unwrap();
+        assert_eq!(words(&filter.apply(entries.clone())), ["books"]);
+
+        filter.add_clause("min-percentile=100").unwrap();
+        assert!(filter.apply(entries.clone()).is_empty());
+
+        assert!(filter.add_clause("exclude=(").is_err());
+        assert!(filter.add_clause("min-percentile=101").is_err());
+        assert!(filter.add_clause("no-plurals=yes").is_err());
+    }
+}
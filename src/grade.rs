@@ -0,0 +1,115 @@
+use crate::eval::GuessStats;
+use crate::score::NUM_POSSIBLE_SCORES;
+use crate::solver::Solver;
+
+/// An assessment of one turn of a finished game.
+pub struct TurnGrade<'a> {
+    pub guess: &'a str,
+
+    /// What the solver would have guessed instead.
+    pub best_guess: &'a str,
+
+    /// Whether the guess was as good as the solver's guess, according to the solver's strategy.
+    pub is_best: bool,
+
+    pub stats: GuessStats,
+    pub best_stats: GuessStats,
+
+    /// How many possibilities there were before and after this turn's score.
+    pub possibilities_before: usize,
+    pub possibilities_after: usize,
+
+    /// How lucky the score was, from -1 (every other possible score would have left fewer
+    /// possibilities) to 1 (every other possible score would have left more). This is the fraction
+    /// of possible solutions that would have left more possibilities, minus the fraction that
+    /// would have left fewer.
+    pub luck: f64,
+}
+
+impl TurnGrade<'_> {
+    /// How much less information the guess was expected to give than the solver's guess, in bits.
+    /// This can be negative, since the strategies don't optimize for entropy.
+    pub fn entropy_lost(&self) -> f64 {
+        self.best_stats.entropy - self.stats.entropy
+    }
+}
+
+/// Grade each turn of a finished game: compare each guess to the one the solver would have made,
+/// and measure how lucky the resulting score was. `solver` should be freshly created, and
+/// configured the way the game was played (e.g. hard mode). Turns after the answer was guessed
+/// are ignored. The solver shouldn't be verbose.
+pub fn grade_game<'a>(
+    mut solver: Solver<'a>,
+    guesses: &[&'a str],
+    answer: &str,
+) -> Vec<TurnGrade<'a>> {
+    let mut result = Vec::new();
+    let rules = solver.scoring_rules();
+
+    for guess in guesses.iter() {
+        let best_guess = solver.next_guess();
+        let possibilities_before = solver.get_possibilities().len();
+
+        // How many possibilities each score would leave, to compare against the actual one.
+        let mut groups = [0; NUM_POSSIBLE_SCORES as usize];
+        for possibility in solver.get_possibilities().iter() {
+            groups[rules.compute_score(guess, possibility).as_num() as usize] += 1;
+        }
+        let score = rules.compute_score(guess, answer);
+        let actual = groups[score.as_num() as usize];
+        let (mut more, mut fewer) = (0, 0);
+        for possibility in solver.get_possibilities().iter() {
+            let remaining = groups[rules.compute_score(guess, possibility).as_num() as usize];
+            if remaining > actual {
+                more += 1;
+            } else if remaining < actual {
+                fewer += 1;
+            }
+        }
+
+        result.push(TurnGrade {
+            guess,
+            best_guess,
+            is_best: solver.strategy_eval(guess) >= solver.strategy_eval(best_guess),
+            stats: solver.stats(guess),
+            best_stats: solver.stats(best_guess),
+            possibilities_before,
+            possibilities_after: actual,
+            luck: (more - fewer) as f64 / possibilities_before as f64,
+        });
+
+        if score.is_win() {
+            break;
+        }
+        solver.respond_to_score(guess, score);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::Strategy;
+
+    #[test]
+    fn test_grade_game() {
+        let solutions: Vec<String> = ["cigar", "rebut", "sissy", "humph", "awake", "blush"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let solver = Solver::new(&[], &solutions, false, false, Strategy::GroupSize);
+
+        let grades = grade_game(solver, &["sissy", "humph", "blush", "awake"], "humph");
+        assert_eq!(grades.len(), 2);
+
+        // "sissy" leaves rebut, humph and awake, but cigar or blush would have left just one word.
+        assert_eq!(grades[0].possibilities_before, 6);
+        assert_eq!(grades[0].possibilities_after, 3);
+        assert!(grades[0].luck < 0.0);
+
+        assert_eq!(grades[1].possibilities_before, 3);
+        assert_eq!(grades[1].possibilities_after, 1);
+        assert!(grades[1].is_best);
+    }
+}
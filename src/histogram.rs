@@ -1,139 +1,1678 @@
-use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{BufWriter, IsTerminal, Write};
 use std::path::Path;
+use std::str::FromStr;
+use std::sync::atomic::{self, AtomicUsize};
 use std::sync::mpsc::{channel, Sender};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use wordle_solver::loader::load_list_from_file;
-use wordle_solver::score::compute_score;
-use wordle_solver::solver::{Solver, Strategy};
+use wordle_solver::json;
+use wordle_solver::list_cache::hash_words;
+use wordle_solver::score::{is_valid_word, DetailScore, ScoringRules};
+use wordle_solver::solver::{Outcome, Solver, Strategy};
 
-struct ThreadResult {
-    groupsize_counts: [usize; 10],
-    groupcount_counts: [usize; 10],
-    count_size_tie: [usize; 3],
+/// One way of running the solver, to compare against others in a run.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Configuration {
+    pub strategy: Strategy,
+
+    /// Play in hard mode, even if the run as a whole isn't.
+    pub hard_mode: bool,
+
+    /// Always start with this guess instead of the strategy's own choice.
+    pub opener: Option<String>,
+}
+
+impl Configuration {
+    pub fn new(strategy: Strategy) -> Self {
+        Configuration {
+            strategy,
+            hard_mode: false,
+            opener: None,
+        }
+    }
+}
+
+/// Written as the strategy, then optionally ":hard" and ":opener=<word>", e.g.
+/// "groupsize:hard:opener=crane".
+impl FromStr for Configuration {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(':');
+        let mut config = Configuration::new(parts.next().unwrap_or_default().parse()?);
+        for part in parts {
+            match part.split_once('=') {
+                None if part == "hard" => config.hard_mode = true,
+                Some(("opener", word)) if is_valid_word(word) => {
+                    config.opener = Some(word.to_string())
+                }
+                _ => return Err(format!("Invalid configuration option: {}", part)),
+            }
+        }
+        Ok(config)
+    }
+}
+
+impl Display for Configuration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.strategy)?;
+        if self.hard_mode {
+            write!(f, ":hard")?;
+        }
+        if let Some(ref opener) = self.opener {
+            write!(f, ":opener={}", opener)?;
+        }
+        Ok(())
+    }
+}
+
+/// The configurations `--solve-all` compares by default.
+pub fn default_configurations() -> Vec<Configuration> {
+    vec![
+        Configuration::new(Strategy::GroupCount),
+        Configuration::new(Strategy::GroupSize),
+    ]
+}
+
+/// How many answers were solved in each number of guesses, and how many weren't solved.
+#[derive(Default)]
+struct OutcomeCounts {
+    /// Indexed by number of guesses.
+    won: Vec<usize>,
+    lost: usize,
+}
+
+impl OutcomeCounts {
+    fn add(&mut self, outcome: Outcome) {
+        match outcome {
+            Outcome::Won(n) => {
+                if self.won.len() <= n {
+                    self.won.resize(n + 1, 0);
+                }
+                self.won[n] += 1;
+            }
+            Outcome::Lost => self.lost += 1,
+        }
+    }
+
+    fn won_count(&self) -> usize {
+        self.won.iter().sum()
+    }
+
+    /// Mean and standard deviation of the number of guesses in won games.
+    fn mean_and_std_dev(&self) -> Option<(f64, f64)> {
+        let count = self.won_count() as f64;
+        if count == 0.0 {
+            return None;
+        }
+        let weighted = |f: &dyn Fn(f64) -> f64| -> f64 {
+            self.won
+                .iter()
+                .enumerate()
+                .map(|(guesses, n)| *n as f64 * f(guesses as f64))
+                .sum()
+        };
+        let mean = weighted(&|g| g) / count;
+        let variance = weighted(&|g| (g - mean) * (g - mean)) / count;
+        Some((mean, variance.sqrt()))
+    }
+
+    /// The smallest number of guesses that at least `fraction` of the won games took no more
+    /// than.
+    fn percentile(&self, fraction: f64) -> Option<usize> {
+        let target = (self.won_count() as f64 * fraction).ceil().max(1.0) as usize;
+        let mut seen = 0;
+        for (guesses, n) in self.won.iter().enumerate() {
+            seen += n;
+            if seen >= target {
+                return Some(guesses);
+            }
+        }
+        None
+    }
+
+    /// The most guesses any won game took.
+    fn worst(&self) -> Option<usize> {
+        self.won.iter().rposition(|n| *n > 0)
+    }
+}
+
+/// Settings applied to every solver in a run.
+#[derive(Clone, Copy)]
+pub struct RunOptions {
+    pub hard_mode: bool,
+    pub deterministic: bool,
+    pub max_guesses: Option<usize>,
+    pub rules: ScoringRules,
+
+    /// Only play this many answers, chosen at random using the seed, instead of all of them. The
+    /// solvers still consider every word in the solution list.
+    pub sample: Option<(usize, u64)>,
+}
+
+/// SplitMix64: a simple random number generator, good enough for sampling answers, and stable
+/// across builds and platforms, unlike `std`'s hashers.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number less than `n`, which must be positive.
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+/// The positions, in order, of `count` of `len` items chosen at random. The same seed always
+/// chooses the same items.
+fn sample_indexes(len: usize, count: usize, seed: u64) -> Vec<usize> {
+    let mut rng = SplitMix64(seed);
+
+    // A partial Fisher-Yates shuffle.
+    let mut indexes: Vec<usize> = (0..len).collect();
+    let count = count.min(len);
+    for i in 0..count {
+        let j = i + rng.below(len - i);
+        indexes.swap(i, j);
+    }
+    indexes.truncate(count);
+    indexes.sort_unstable();
+    indexes
+}
+
+/// How to print the results of a run.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum OutputFormat {
+    /// A line per answer as it's solved, with each configuration's guess count and then the
+    /// answer, followed by a summary.
+    #[default]
+    Text,
+
+    /// A header, then a row per answer (the answer, then each configuration's guess count) in
+    /// solution list order. Lost games are left empty. The summary is printed to stderr, as text.
+    Csv,
+
+    /// An object with an "answers" array, in solution list order, and a "summary" object. Lost
+    /// games are null.
+    Json,
+}
+
+impl argparse::FromCommandLine for OutputFormat {
+    fn from_argument(s: &str) -> Result<Self, String> {
+        match s {
+            "text" => Ok(Self::Text),
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            _ => Err("output formats are 'text', 'csv', and 'json'".to_string()),
+        }
+    }
+}
+
+/// A characteristic of answers, to group results by.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Feature {
+    FirstLetter,
+
+    /// Whether any letter appears more than once.
+    RepeatedLetters,
+
+    /// How many of the letters are a, e, i, o or u.
+    Vowels,
+
+    /// Which quartile of the solution list's frequencies the answer is in.
+    Frequency,
+}
+
+impl FromStr for Feature {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "first-letter" => Ok(Self::FirstLetter),
+            "repeated-letters" => Ok(Self::RepeatedLetters),
+            "vowels" => Ok(Self::Vowels),
+            "frequency" => Ok(Self::Frequency),
+            _ => Err(
+                "features are 'first-letter', 'repeated-letters', 'vowels', and 'frequency'"
+                    .to_string(),
+            ),
+        }
+    }
+}
+
+impl Display for Feature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Feature::FirstLetter => write!(f, "first-letter"),
+            Feature::RepeatedLetters => write!(f, "repeated-letters"),
+            Feature::Vowels => write!(f, "vowels"),
+            Feature::Frequency => write!(f, "frequency"),
+        }
+    }
 }
 
-fn run_solver<'a>(mut solver: Solver<'a>, first_guess: &'a str, answer: &str) -> u8 {
-    let mut score = compute_score(first_guess, answer);
+/// The group each answer belongs in, by a feature. `frequencies` is only needed for
+/// `Feature::Frequency`.
+fn feature_groups(
+    feature: Feature,
+    answers: &[String],
+    frequencies: Option<&HashMap<String, f64>>,
+) -> Vec<String> {
+    let frequency = |word: &String| frequencies.and_then(|f| f.get(word)).copied();
+    let mut quartiles = HashMap::new();
+    if feature == Feature::Frequency {
+        let mut by_frequency: Vec<&String> = answers.iter().collect();
+        by_frequency.sort_by(|a, b| {
+            f64::total_cmp(&frequency(a).unwrap_or(0.0), &frequency(b).unwrap_or(0.0))
+        });
+        for (rank, word) in by_frequency.iter().enumerate() {
+            quartiles.insert(*word, rank * 4 / answers.len() + 1);
+        }
+    }
+
+    answers
+        .iter()
+        .map(|word| match feature {
+            Feature::FirstLetter => word.chars().next().unwrap_or_default().to_string(),
+            Feature::RepeatedLetters => {
+                let mut letters: Vec<char> = word.chars().collect();
+                letters.sort_unstable();
+                letters.dedup();
+                let repeated = letters.len() < word.chars().count();
+                (if repeated { "repeated" } else { "distinct" }).to_string()
+            }
+            Feature::Vowels => {
+                let vowels = word.chars().filter(|c| "aeiou".contains(*c)).count();
+                format!("{} vowels", vowels)
+            }
+            Feature::Frequency => format!("quartile {}", quartiles[word]),
+        })
+        .collect()
+}
+
+/// How to report the results of a run.
+#[derive(Clone, Debug, Default)]
+pub struct ReportOptions {
+    pub format: OutputFormat,
+
+    /// Write the guesses and scores of every game to this file, as a line of JSON per answer.
+    pub trace_path: Option<String>,
+
+    /// Also summarize results for each group of answers with the same value of this feature.
+    pub breakdown: Option<Feature>,
+
+    /// The solutions' frequencies, for breaking results down by `Feature::Frequency`. Higher
+    /// quartiles are more frequent.
+    pub frequencies: Option<HashMap<String, f64>>,
+
+    /// Save each answer's results to this file as soon as they're done, so that an interrupted
+    /// run can be resumed.
+    pub checkpoint_path: Option<String>,
+
+    /// Skip answers already saved in the checkpoint file, and use the saved results for them.
+    pub resume: bool,
+
+    /// Show how many answers are done, and an estimate of the time left, on stderr.
+    pub progress: bool,
+
+    /// With `OutputFormat::Text`, don't print each answer's result, only the summary.
+    pub quiet: bool,
+
+    /// For each configuration, also list the answers that took the most guesses, up to this
+    /// many (plus any lost ones), with the moves played.
+    pub worst: usize,
+
+    /// Count answers that take more than this many guesses, or are lost, as failures, and list
+    /// them in the summary.
+    pub failure_threshold: Option<usize>,
+
+    /// Time every turn of every game, and report the slowest answers, the mean time of each
+    /// turn, and how many guesses are made per second.
+    pub timing: bool,
+}
+
+/// A progress line on stderr, rewritten in place at most once a second.
+struct Progress {
+    total: usize,
+
+    /// Answers done before this run started, e.g. from a checkpoint. They don't count toward
+    /// the rate used to estimate the time left.
+    initial: usize,
+    start: Instant,
+    last_shown: Option<Instant>,
+}
+
+impl Progress {
+    fn new(total: usize, initial: usize) -> Progress {
+        Progress {
+            total,
+            initial,
+            start: Instant::now(),
+            last_shown: None,
+        }
+    }
+
+    fn update(&mut self, done: usize) {
+        let now = Instant::now();
+        if done < self.total
+            && self
+                .last_shown
+                .is_some_and(|last| now - last < Duration::from_secs(1))
+        {
+            return;
+        }
+        self.last_shown = Some(now);
+
+        let mut line = format!("{}/{} answers done", done, self.total);
+        if done > self.initial && done < self.total {
+            let per_answer = (now - self.start).as_secs_f64() / (done - self.initial) as f64;
+            let left = (per_answer * (self.total - done) as f64).ceil() as u64;
+            line.push_str(&format!(", ETA {}:{:02}", left / 60, left % 60));
+        }
+        eprint!("\r{:<40}", line);
+        if done == self.total {
+            eprintln!();
+        }
+    }
+
+    /// Erase the line, so that output to stdout on the same terminal doesn't run into it. The
+    /// next `update` shows it again.
+    fn clear(&mut self) {
+        if self.last_shown.take().is_some() {
+            eprint!("\r{:<40}\r", "");
+        }
+    }
+}
+
+/// Guesses and their scores, in order.
+type Moves = Vec<(String, DetailScore)>;
+
+/// Every configuration's result for one answer.
+struct AnswerResult {
+    /// Position of the answer in the solution list.
+    index: usize,
+
+    /// The outcome and moves of each configuration's game, in the order they were given.
+    games: Vec<(Outcome, Moves)>,
+
+    /// How long each turn of each configuration's game took, in the same order. Empty for
+    /// results read from a checkpoint.
+    times: Vec<Vec<Duration>>,
+}
+
+/// Play a game to the end, starting with `first_guess`. Returns the outcome and the moves played.
+pub fn run_solver<'a>(
+    solver: Solver<'a>,
+    first_guess: &'a str,
+    answer: &str,
+) -> (Outcome, Vec<(&'a str, DetailScore)>) {
+    let (outcome, moves, _) = run_solver_timed(solver, first_guess, answer);
+    (outcome, moves)
+}
+
+/// Like `run_solver`, but also returns how long each turn took: choosing the guess (except for
+/// `first_guess`, which is given) and narrowing down the possibilities with its score.
+pub fn run_solver_timed<'a>(
+    mut solver: Solver<'a>,
+    first_guess: &'a str,
+    answer: &str,
+) -> (Outcome, Vec<(&'a str, DetailScore)>, Vec<Duration>) {
+    let rules = solver.scoring_rules();
+    let mut turn_start = Instant::now();
+    let mut score = rules.compute_score(first_guess, answer);
     solver.respond_to_score(first_guess, score);
+    let mut times = vec![turn_start.elapsed()];
 
     let mut guess_count = 1;
 
     loop {
         if score.is_win() {
-            return guess_count;
+            return (Outcome::Won(guess_count), solver.history().to_vec(), times);
+        }
+        if solver.guesses_remaining() == Some(0) {
+            return (Outcome::Lost, solver.history().to_vec(), times);
         }
 
+        turn_start = Instant::now();
         let guess = solver.next_guess();
-        score = compute_score(guess, answer);
+        score = rules.compute_score(guess, answer);
         solver.respond_to_score(guess, score);
+        times.push(turn_start.elapsed());
         guess_count += 1;
     }
 }
 
 fn thread_func(
-    sender: Sender<ThreadResult>,
+    sender: Sender<AnswerResult>,
     guessable: Arc<Vec<String>>,
     solutions: Arc<Vec<String>>,
-    hard_mode: bool,
-    start_index: usize,
-    end_index: usize,
+    configs: Arc<Vec<Configuration>>,
+    options: RunOptions,
+    pending: Arc<Vec<usize>>,
+    next_pending: Arc<AtomicUsize>,
 ) {
-    let mut groupsize_counts = [0; 10];
-    let mut groupcount_counts = [0; 10];
-    let mut count_size_tie = [0; 3];
-
     let guessable = guessable.as_ref();
     let solutions = solutions.as_ref();
 
-    let size_first_guess =
-        Solver::new(guessable, solutions, false, false, Strategy::GroupSize).next_guess();
-    let count_first_guess =
-        Solver::new(guessable, solutions, false, false, Strategy::GroupCount).next_guess();
-
-    for answer in solutions[start_index..end_index].iter() {
-        let groupsize = Solver::new(guessable, solutions, hard_mode, false, Strategy::GroupSize);
-        let size_result = run_solver(groupsize, size_first_guess, answer);
-        groupsize_counts[size_result as usize] += 1;
-
-        let groupcount = Solver::new(guessable, solutions, hard_mode, false, Strategy::GroupCount);
-        let count_result = run_solver(groupcount, count_first_guess, answer);
-        groupcount_counts[count_result as usize] += 1;
-
-        println!("{} {} {}", count_result, size_result, answer);
-        match size_result.cmp(&count_result) {
-            Ordering::Less => count_size_tie[1] += 1,
-            Ordering::Equal => count_size_tie[2] += 1,
-            Ordering::Greater => count_size_tie[0] += 1,
+    let new_solver = |hard_mode: bool, strategy: Strategy| {
+        let mut solver = Solver::new(guessable, solutions, hard_mode, false, strategy);
+        solver.set_deterministic(options.deterministic);
+        solver.set_scoring_rules(options.rules);
+        if let Some(max) = options.max_guesses {
+            solver.set_max_guesses(max);
+        }
+        solver
+    };
+
+    let first_guesses: Vec<&str> = configs
+        .iter()
+        .map(|config| match config.opener {
+            Some(ref opener) => guessable
+                .iter()
+                .chain(solutions.iter())
+                .find(|word| *word == opener)
+                .expect("opener isn't in the word lists"),
+            None => new_solver(false, config.strategy).next_guess(),
+        })
+        .collect();
+
+    // Answers take varying amounts of time to solve, so rather than splitting the list up
+    // front, each thread takes the next unsolved answer whenever it finishes one.
+    loop {
+        let next = next_pending.fetch_add(1, atomic::Ordering::Relaxed);
+        let Some(&index) = pending.get(next) else {
+            break;
         };
+        let answer = &solutions[index];
+
+        let (games, times) = configs
+            .iter()
+            .zip(first_guesses.iter())
+            .map(|(config, first_guess)| {
+                let solver = new_solver(options.hard_mode || config.hard_mode, config.strategy);
+                let (outcome, moves, times) = run_solver_timed(solver, first_guess, answer);
+                let moves = moves.into_iter().map(|(g, s)| (g.to_string(), s)).collect();
+                ((outcome, moves), times)
+            })
+            .unzip();
+
+        sender
+            .send(AnswerResult {
+                index,
+                games,
+                times,
+            })
+            .unwrap();
     }
+}
 
-    sender
-        .send(ThreadResult {
-            groupsize_counts,
-            groupcount_counts,
-            count_size_tie,
+/// The first line of a checkpoint file, identifying the run it belongs to: the lists' contents,
+/// the configurations, and every option that can change a game's outcome.
+fn checkpoint_header(
+    names: &[String],
+    guessable_list: &[String],
+    solution_list: &[String],
+    options: &RunOptions,
+) -> String {
+    let answers = match options.sample {
+        Some((count, seed)) => {
+            let total = solution_list.len();
+            format!("{} of {} answers (seed {})", count, total, seed)
+        }
+        None => format!("{} answers", solution_list.len()),
+    };
+    let max_guesses = options
+        .max_guesses
+        .map_or("none".to_string(), |max| max.to_string());
+    format!(
+        "# wordle-solver checkpoint: {}; {}; hard mode {}; deterministic {}; max guesses {}; \
+         scoring {}; guessable {:016x}; solutions {:016x}",
+        answers,
+        names.join(" "),
+        options.hard_mode,
+        options.deterministic,
+        max_guesses,
+        options.rules,
+        hash_words(guessable_list),
+        hash_words(solution_list)
+    )
+}
+
+/// A line of a checkpoint file: the answer, then for each configuration, a tab, the outcome, a
+/// space, and the moves (as "guess=score", separated by commas).
+fn checkpoint_line(answer: &str, games: &[(Outcome, Moves)]) -> String {
+    let mut line = answer.to_string();
+    for (outcome, moves) in games {
+        let moves: Vec<String> = moves.iter().map(|(g, s)| format!("{}={}", g, s)).collect();
+        line.push_str(&format!("\t{} {}", outcome, moves.join(",")));
+    }
+    line
+}
+
+fn parse_checkpoint_line(line: &str) -> Option<(&str, Vec<(Outcome, Moves)>)> {
+    let mut fields = line.split('\t');
+    let answer = fields.next()?;
+    let games = fields
+        .map(|field| {
+            let (outcome, moves) = field.split_once(' ')?;
+            let outcome = match outcome {
+                "X" => Outcome::Lost,
+                n => Outcome::Won(n.parse().ok()?),
+            };
+            let moves = moves
+                .split(',')
+                .map(|m| {
+                    let (guess, score) = m.split_once('=')?;
+                    Some((guess.to_string(), score.parse().ok()?))
+                })
+                .collect::<Option<Moves>>()?;
+            Some((outcome, moves))
         })
-        .unwrap();
+        .collect::<Option<Vec<_>>>()?;
+    Some((answer, games))
 }
 
-/// Run the solver with each allowable solution, collecting a count of how many guesses were
-/// required to solve each one. Splits the work out into threads for speed.
+/// Results saved in a checkpoint file by a run with the same header. A missing file has no
+/// results. An incomplete last line, from an interrupted write, is ignored.
+fn read_checkpoint(
+    path: &str,
+    header: &str,
+    config_count: usize,
+    solution_list: &[String],
+) -> std::io::Result<Vec<AnswerResult>> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let invalid = |msg: String| std::io::Error::new(std::io::ErrorKind::InvalidData, msg);
+
+    let mut lines = contents.split_inclusive('\n').filter(|l| l.ends_with('\n'));
+    if lines.next().map(str::trim_end) != Some(header) {
+        return Err(invalid(
+            "checkpoint is from a run with different lists, configurations or options".to_string(),
+        ));
+    }
+
+    let indexes: HashMap<&str, usize> = solution_list
+        .iter()
+        .enumerate()
+        .map(|(i, word)| (word.as_str(), i))
+        .collect();
+    let mut results = Vec::new();
+    for (line_number, line) in lines.enumerate() {
+        let parsed = parse_checkpoint_line(line.trim_end())
+            .filter(|(_, games)| games.len() == config_count)
+            .and_then(|(answer, games)| Some((*indexes.get(answer)?, games)));
+        let Some((index, games)) = parsed else {
+            return Err(invalid(format!("Line {}: invalid result", line_number + 2)));
+        };
+        results.push(AnswerResult {
+            index,
+            games,
+            times: Vec::new(),
+        });
+    }
+    Ok(results)
+}
+
+/// Whether an outcome counts as a failure, given `ReportOptions::failure_threshold`.
+fn is_failure(outcome: Outcome, threshold: usize) -> bool {
+    match outcome {
+        Outcome::Won(n) => n > threshold,
+        Outcome::Lost => true,
+    }
+}
+
+/// Run the solver with each allowable solution and each configuration, collecting a count of how
+/// many guesses were required to solve each one. Splits the work out into threads for speed.
+/// Returns the number of failed games (see `ReportOptions::failure_threshold`).
 pub fn histogram(
     thread_count: usize,
-    guessable_path: &Path,
-    solution_path: &Path,
-    hard_mode: bool,
-) {
-    let guessable_list = Arc::new(load_list_from_file(guessable_path).unwrap());
-    let solution_list = Arc::new(load_list_from_file(solution_path).unwrap());
+    guessable_list: Vec<String>,
+    solution_list: Vec<String>,
+    configs: Vec<Configuration>,
+    options: RunOptions,
+    report: ReportOptions,
+) -> usize {
+    let format = report.format;
+    let names: Vec<String> = configs.iter().map(|c| c.to_string()).collect();
+    let guessable_list = Arc::new(guessable_list);
+    let solution_list = Arc::new(solution_list);
+    let configs = Arc::new(configs);
 
-    let mut start_index = 0;
-    let count_per_thread = solution_list.len() / thread_count;
+    let exit_on_error = |path: &str, e: std::io::Error| -> ! {
+        eprintln!("{}: {}", path, e);
+        std::process::exit(1);
+    };
+
+    // Results from an earlier, interrupted run; the checkpoint is rewritten with just those, to
+    // drop an incomplete last line. Without --resume, an existing checkpoint is left alone.
+    let header = checkpoint_header(&names, &guessable_list, &solution_list, &options);
+    let mut answers = Vec::new();
+    let mut checkpoint = report.checkpoint_path.as_ref().map(|path| {
+        if report.resume {
+            answers = read_checkpoint(path, &header, names.len(), &solution_list)
+                .unwrap_or_else(|e| exit_on_error(path, e));
+        } else if Path::new(path).exists() {
+            eprintln!(
+                "{}: already exists; add --resume to continue that run, or remove it",
+                path
+            );
+            std::process::exit(1);
+        }
+        let mut file =
+            BufWriter::new(File::create(path).unwrap_or_else(|e| exit_on_error(path, e)));
+        writeln!(file, "{}", header).unwrap_or_else(|e| exit_on_error(path, e));
+        for result in answers.iter() {
+            let line = checkpoint_line(&solution_list[result.index], &result.games);
+            writeln!(file, "{}", line).unwrap_or_else(|e| exit_on_error(path, e));
+        }
+        (path, file)
+    });
+
+    let selected: Vec<usize> = match options.sample {
+        Some((count, seed)) => sample_indexes(solution_list.len(), count, seed),
+        None => (0..solution_list.len()).collect(),
+    };
+    let mut done = vec![false; solution_list.len()];
+    for result in answers.iter() {
+        done[result.index] = true;
+    }
+    let pending: Vec<usize> = selected.iter().copied().filter(|i| !done[*i]).collect();
+    let pending = Arc::new(pending);
+    let next_pending = Arc::new(AtomicUsize::new(0));
     let (sender, receiver) = channel();
+    let start = Instant::now();
 
-    for i in 0..thread_count {
-        let end_index = if i == thread_count - 1 {
-            solution_list.len()
-        } else {
-            start_index + count_per_thread
-        };
+    for _ in 0..thread_count.max(1) {
         let this_sender = sender.clone();
         let this_guessable = Arc::clone(&guessable_list);
         let this_solutions = Arc::clone(&solution_list);
+        let this_configs = Arc::clone(&configs);
+        let this_pending = Arc::clone(&pending);
+        let this_next_pending = Arc::clone(&next_pending);
         std::thread::spawn(move || {
             thread_func(
                 this_sender,
                 this_guessable,
                 this_solutions,
-                hard_mode,
-                start_index,
-                end_index,
+                this_configs,
+                options,
+                this_pending,
+                this_next_pending,
             )
         });
-        start_index += count_per_thread;
     }
 
     std::mem::drop(sender);
 
-    let mut groupcount_totals = [0; 10];
-    let mut groupsize_totals = [0; 10];
-    let mut count_size_tie = [0; 3];
+    let mut progress = report
+        .progress
+        .then(|| Progress::new(selected.len(), answers.len()));
+    if let Some(ref mut progress) = progress {
+        progress.update(answers.len());
+    }
+
+    // Each answer's line is printed as soon as all the answers before it in the list are done,
+    // so output is in list order no matter how the threads' work interleaves.
+    let print_answers = format == OutputFormat::Text && !report.quiet;
+    let answer_line = |result: &AnswerResult| {
+        let outcomes: Vec<String> = result.games.iter().map(|(o, _)| o.to_string()).collect();
+        format!("{} {}", outcomes.join(" "), solution_list[result.index])
+    };
+    let mut waiting_lines = BTreeMap::new();
+    let mut next_line = 0;
+    if print_answers {
+        waiting_lines.extend(answers.iter().map(|r| (r.index, answer_line(r))));
+    }
 
+    // The progress line is on stderr, which is usually the same terminal.
+    let clear_progress = print_answers && std::io::stdout().is_terminal();
     for result in receiver.iter() {
-        for i in 0..10 {
-            groupcount_totals[i] += result.groupcount_counts[i];
-            groupsize_totals[i] += result.groupsize_counts[i];
+        let answer = &solution_list[result.index];
+        if print_answers {
+            waiting_lines.insert(result.index, answer_line(&result));
+            while let Some(line) = selected
+                .get(next_line)
+                .and_then(|index| waiting_lines.remove(index))
+            {
+                if let Some(ref mut progress) = progress {
+                    if clear_progress {
+                        progress.clear();
+                    }
+                }
+                println!("{}", line);
+                next_line += 1;
+            }
+        }
+        if let Some((path, ref mut file)) = checkpoint {
+            writeln!(file, "{}", checkpoint_line(answer, &result.games))
+                .and_then(|_| file.flush())
+                .unwrap_or_else(|e| exit_on_error(path, e));
+        }
+        answers.push(result);
+        if let Some(ref mut progress) = progress {
+            progress.update(answers.len());
+        }
+    }
+    // Only left over if every answer was already in the checkpoint.
+    for line in waiting_lines.into_values() {
+        println!("{}", line);
+    }
+    let elapsed = start.elapsed();
+    answers.sort_by_key(|result| result.index);
+
+    let mut totals: Vec<OutcomeCounts> = names.iter().map(|_| OutcomeCounts::default()).collect();
+    for result in answers.iter() {
+        for (total, (outcome, _)) in totals.iter_mut().zip(result.games.iter()) {
+            total.add(*outcome);
+        }
+    }
+
+    // With two configurations: how many answers each one solved in fewer guesses, and ties.
+    let record = names.len() == 2;
+    let mut wins_wins_tie = [0; 3];
+    if record {
+        for result in answers.iter() {
+            let index = match result.games[0].0.cmp(&result.games[1].0) {
+                std::cmp::Ordering::Less => 0,
+                std::cmp::Ordering::Greater => 1,
+                std::cmp::Ordering::Equal => 2,
+            };
+            wins_wins_tie[index] += 1;
+        }
+    }
+
+    if let Some(ref path) = report.trace_path {
+        write_trace(path, &solution_list, &names, &answers)
+            .unwrap_or_else(|e| exit_on_error(path, e));
+    }
+
+    let mut summary = Vec::new();
+    let label_width = names
+        .iter()
+        .map(|name| name.len() + 2)
+        .max()
+        .unwrap_or(0)
+        .max(12);
+    for (config, total) in configs.iter().zip(totals.iter()) {
+        // The strategy is capitalized, to set it apart from the array.
+        let name = config.to_string();
+        let label = name.replacen(
+            &config.strategy.to_string(),
+            &config.strategy.to_string().to_uppercase(),
+            1,
+        );
+        summary.push(format!(
+            "{:<width$}{:?}",
+            format!("{}:", label),
+            total.won,
+            width = label_width
+        ));
+    }
+    summary.extend(stats_table(&names, &totals));
+    if options.max_guesses.is_some() {
+        let lost: Vec<String> = totals.iter().map(|t| t.lost.to_string()).collect();
+        summary.push(format!(
+            "LOST ({}): {}",
+            names.join(" - "),
+            lost.join(" - ")
+        ));
+    }
+    if names.len() > 2 {
+        summary.extend(comparison_table(&names, &totals));
+    }
+    let breakdown = report.breakdown.map(|feature| {
+        let groups = feature_groups(feature, &solution_list, report.frequencies.as_ref());
+        let mut grouped: BTreeMap<String, Vec<OutcomeCounts>> = BTreeMap::new();
+        for result in answers.iter() {
+            let counts = grouped
+                .entry(groups[result.index].clone())
+                .or_insert_with(|| names.iter().map(|_| OutcomeCounts::default()).collect());
+            for (counts, (outcome, _)) in counts.iter_mut().zip(result.games.iter()) {
+                counts.add(*outcome);
+            }
+        }
+        (feature, grouped)
+    });
+    if let Some((feature, ref grouped)) = breakdown {
+        summary.extend(breakdown_table(feature, &names, grouped));
+    }
+    let worst: Vec<Vec<&AnswerResult>> = if report.worst > 0 {
+        (0..names.len())
+            .map(|config| worst_answers(&answers, config, report.worst))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    if !worst.is_empty() {
+        summary.extend(worst_table(&names, &worst, &solution_list));
+    }
+    // For each configuration, the answers it failed, in list order.
+    let failures: Option<Vec<Vec<&AnswerResult>>> = report.failure_threshold.map(|threshold| {
+        (0..names.len())
+            .map(|config| {
+                answers
+                    .iter()
+                    .filter(|r| is_failure(r.games[config].0, threshold))
+                    .collect()
+            })
+            .collect()
+    });
+    if let (Some(threshold), Some(ref failures)) = (report.failure_threshold, &failures) {
+        let counts: Vec<String> = failures.iter().map(|f| f.len().to_string()).collect();
+        summary.push(format!(
+            "FAILED (more than {} guesses; {}): {}",
+            threshold,
+            names.join(" - "),
+            counts.join(" - ")
+        ));
+        for (config, (name, results)) in names.iter().zip(failures.iter()).enumerate() {
+            if !results.is_empty() {
+                let words: Vec<String> = results
+                    .iter()
+                    .map(|r| format!("{} ({})", solution_list[r.index], r.games[config].0))
+                    .collect();
+                summary.push(format!("  {}: {}", name, words.join(", ")));
+            }
+        }
+    }
+    let paired = if record {
+        paired_stats(&answers, wins_wins_tie)
+    } else {
+        None
+    };
+    if record {
+        summary.push(format!(
+            "RECORD ({} wins - {} wins - tie): {:?}",
+            names[0], names[1], wins_wins_tie
+        ));
+    }
+    let timing = report.timing.then(|| timing_stats(&answers, names.len()));
+    if let Some(ref timing) = timing {
+        summary.extend(timing_table(&names, timing, elapsed, &solution_list));
+    }
+    if let Some(ref paired) = paired {
+        summary.push(format!(
+            "PAIRED ({} - {}, {} answers): mean difference {:.3} (95% CI {:.3} to {:.3}); \
+             sign test p = {:.4}",
+            names[0],
+            names[1],
+            paired.answers,
+            paired.mean_difference,
+            paired.interval.0,
+            paired.interval.1,
+            paired.sign_test_p
+        ));
+    }
+
+    match format {
+        OutputFormat::Text => {
+            for line in summary {
+                println!("{}", line);
+            }
+        }
+        OutputFormat::Csv => {
+            println!("answer,{}", names.join(","));
+            for result in answers.iter() {
+                let outcomes: Vec<String> =
+                    result.games.iter().map(|(o, _)| csv_outcome(*o)).collect();
+                println!("{},{}", solution_list[result.index], outcomes.join(","));
+            }
+            for line in summary {
+                eprintln!("{}", line);
+            }
+        }
+        OutputFormat::Json => {
+            println!("{{\"answers\": [");
+            for (i, result) in answers.iter().enumerate() {
+                let separator = if i + 1 < answers.len() { "," } else { "" };
+                let outcomes = names
+                    .iter()
+                    .zip(result.games.iter())
+                    .map(|(name, (o, _))| (name.as_str(), json_outcome(*o)));
+                let answer = ("answer", json::string(&solution_list[result.index]));
+                let line = json::object(std::iter::once(answer).chain(outcomes));
+                println!("  {}{}", line, separator);
+            }
+            println!("], \"summary\": {{");
+            let mut fields: Vec<String> = names
+                .iter()
+                .zip(totals.iter())
+                .map(|(name, total)| format!("  {}: {}", json::string(name), json_counts(total)))
+                .collect();
+            if let Some((feature, ref grouped)) = breakdown {
+                let groups: Vec<String> = grouped
+                    .iter()
+                    .map(|(group, counts)| {
+                        let configs = names
+                            .iter()
+                            .zip(counts.iter())
+                            .map(|(name, counts)| (name, json_counts(counts)));
+                        format!("    {}: {}", json::string(group), json::object(configs))
+                    })
+                    .collect();
+                fields.push(format!(
+                    "  \"breakdown\": {{\"feature\": {}, \"groups\": {{\n{}\n  }}}}",
+                    json::string(&feature.to_string()),
+                    groups.join(",\n")
+                ));
+            }
+            if !worst.is_empty() {
+                let configs: Vec<String> = names
+                    .iter()
+                    .zip(worst.iter())
+                    .enumerate()
+                    .map(|(config, (name, results))| {
+                        let results = results.iter().map(|result| {
+                            let (outcome, moves) = &result.games[config];
+                            json::object([
+                                ("answer", json::string(&solution_list[result.index])),
+                                ("game", json_game(*outcome, moves)),
+                            ])
+                        });
+                        format!("    {}: {}", json::string(name), json::array(results))
+                    })
+                    .collect();
+                fields.push(format!("  \"worst\": {{\n{}\n  }}", configs.join(",\n")));
+            }
+            if let Some(ref failures) = failures {
+                let configs = names.iter().zip(failures.iter()).map(|(name, results)| {
+                    let words = results
+                        .iter()
+                        .map(|r| json::string(&solution_list[r.index]));
+                    (name, json::array(words))
+                });
+                fields.push(format!("  \"failures\": {}", json::object(configs)));
+            }
+            if record {
+                let record = json::object([
+                    (format!("{}_wins", names[0]), wins_wins_tie[0].to_string()),
+                    (format!("{}_wins", names[1]), wins_wins_tie[1].to_string()),
+                    ("ties".to_string(), wins_wins_tie[2].to_string()),
+                ]);
+                fields.push(format!("  \"record\": {}", record));
+            }
+            if let Some(ref paired) = paired {
+                let interval = [paired.interval.0, paired.interval.1].map(json::number);
+                let paired = json::object([
+                    ("answers", paired.answers.to_string()),
+                    ("mean_difference", json::number(paired.mean_difference)),
+                    ("ci95", json::array(interval)),
+                    ("sign_test_p", json::number(paired.sign_test_p)),
+                ]);
+                fields.push(format!("  \"paired\": {}", paired));
+            }
+            if let Some(ref timing) = timing {
+                let configs: Vec<String> = names
+                    .iter()
+                    .zip(timing.iter())
+                    .map(|(name, stats)| {
+                        let ms = |time: &Duration| json::number(time.as_secs_f64() * 1000.0);
+                        let slowest = stats.slowest.iter().map(|(index, time)| {
+                            json::object([
+                                ("answer", json::string(&solution_list[*index])),
+                                ("ms", ms(time)),
+                            ])
+                        });
+                        let stats = json::object([
+                            ("guesses", stats.guesses.to_string()),
+                            ("seconds", json::number(stats.total.as_secs_f64())),
+                            ("turn_ms", json::array(stats.per_turn.iter().map(ms))),
+                            ("slowest", json::array(slowest)),
+                        ]);
+                        format!("    {}: {}", json::string(name), stats)
+                    })
+                    .collect();
+                fields.push(format!(
+                    "  \"timing\": {{\n    \"wall_seconds\": {},\n{}\n  }}",
+                    json::number(elapsed.as_secs_f64()),
+                    configs.join(",\n")
+                ));
+            }
+            println!("{}", fields.join(",\n"));
+            println!("}}}}");
+        }
+    }
+
+    failures.map_or(0, |failures| failures.iter().map(Vec::len).sum())
+}
+
+/// Write every game's moves, as a line of JSON per answer (see `ReportOptions::trace_path`).
+fn write_trace(
+    path: &str,
+    solution_list: &[String],
+    names: &[String],
+    answers: &[AnswerResult],
+) -> std::io::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+    for result in answers {
+        let games = names
+            .iter()
+            .zip(result.games.iter())
+            .map(|(name, (outcome, moves))| (name.as_str(), json_game(*outcome, moves)));
+        let answer = ("answer", json::string(&solution_list[result.index]));
+        writeln!(
+            file,
+            "{}",
+            json::object(std::iter::once(answer).chain(games))
+        )?;
+    }
+    file.flush()
+}
+
+fn json_game(outcome: Outcome, moves: &Moves) -> String {
+    let moves = moves.iter().map(|(guess, score)| {
+        json::object([
+            ("guess", json::string(guess)),
+            ("score", json::string(&score.to_string())),
+        ])
+    });
+    json::object([
+        ("guesses", json_outcome(outcome)),
+        ("moves", json::array(moves)),
+    ])
+}
+
+fn csv_outcome(outcome: Outcome) -> String {
+    match outcome {
+        Outcome::Won(n) => n.to_string(),
+        Outcome::Lost => String::new(),
+    }
+}
+
+fn json_outcome(outcome: Outcome) -> String {
+    match outcome {
+        Outcome::Won(n) => n.to_string(),
+        Outcome::Lost => "null".to_string(),
+    }
+}
+
+fn json_counts(counts: &OutcomeCounts) -> String {
+    let optional = |value: Option<String>| value.unwrap_or_else(|| "null".to_string());
+    let mean_and_std_dev = counts.mean_and_std_dev();
+    json::object([
+        ("won", json::array(counts.won.iter().map(usize::to_string))),
+        ("lost", counts.lost.to_string()),
+        (
+            "mean",
+            optional(mean_and_std_dev.map(|(mean, _)| json::number(mean))),
+        ),
+        (
+            "std_dev",
+            optional(mean_and_std_dev.map(|(_, sd)| json::number(sd))),
+        ),
+        (
+            "median",
+            optional(counts.percentile(0.5).map(|n| n.to_string())),
+        ),
+        (
+            "p95",
+            optional(counts.percentile(0.95).map(|n| n.to_string())),
+        ),
+        ("worst", optional(counts.worst().map(|n| n.to_string()))),
+    ])
+}
+
+/// Lines of a table of statistics about won games, with a row per configuration.
+fn stats_table(names: &[String], totals: &[OutcomeCounts]) -> Vec<String> {
+    let width = names
+        .iter()
+        .map(|name| name.len())
+        .max()
+        .unwrap_or(0)
+        .max(5);
+    let mut lines = vec![format!(
+        "{:<width$}  {:>6}  {:>6}  {:>6}  {:>4}  {:>5}",
+        "STATS",
+        "mean",
+        "sd",
+        "median",
+        "p95",
+        "worst",
+        width = width
+    )];
+    for (name, counts) in names.iter().zip(totals.iter()) {
+        let Some((mean, std_dev)) = counts.mean_and_std_dev() else {
+            lines.push(format!("{:<width$}  no games won", name, width = width));
+            continue;
+        };
+        lines.push(format!(
+            "{:<width$}  {:>6.3}  {:>6.3}  {:>6}  {:>4}  {:>5}",
+            name,
+            mean,
+            std_dev,
+            counts.percentile(0.5).unwrap(),
+            counts.percentile(0.95).unwrap(),
+            counts.worst().unwrap(),
+            width = width
+        ));
+    }
+    lines
+}
+
+/// Lines of a table with a row per group of answers, and the mean number of guesses of each
+/// configuration's won games in a column.
+fn breakdown_table(
+    feature: Feature,
+    names: &[String],
+    grouped: &BTreeMap<String, Vec<OutcomeCounts>>,
+) -> Vec<String> {
+    let label_width = grouped
+        .keys()
+        .map(|g| g.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(5);
+    let widths: Vec<usize> = names.iter().map(|name| name.len().max(6)).collect();
+    let mut header = format!("{:<label_width$}  {:>7}", "GROUP", "answers");
+    for (name, width) in names.iter().zip(widths.iter()) {
+        header.push_str(&format!("  {:>width$}", name, width = width));
+    }
+
+    let mut lines = vec![format!("BREAKDOWN BY {}", feature), header];
+    for (group, counts) in grouped.iter() {
+        let answers = counts[0].won_count() + counts[0].lost;
+        let mut line = format!("{:<label_width$}  {:>7}", group, answers);
+        for (counts, width) in counts.iter().zip(widths.iter()) {
+            let mut cell = match counts.mean_and_std_dev() {
+                Some((mean, _)) => format!("{:.3}", mean),
+                None => "-".to_string(),
+            };
+            if counts.lost > 0 {
+                cell.push_str(&format!(" ({} lost)", counts.lost));
+            }
+            line.push_str(&format!("  {:>width$}", cell, width = width));
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// The answers a configuration did worst on, worst first: every lost one, then the `count` that
+/// took the most guesses (in list order among equals).
+fn worst_answers(answers: &[AnswerResult], config: usize, count: usize) -> Vec<&AnswerResult> {
+    let mut sorted: Vec<&AnswerResult> = answers.iter().collect();
+    sorted.sort_by(|a, b| b.games[config].0.cmp(&a.games[config].0));
+    let lost = sorted
+        .iter()
+        .take_while(|r| r.games[config].0 == Outcome::Lost)
+        .count();
+    sorted.truncate(lost + count);
+    sorted
+}
+
+/// Lines listing each configuration's worst answers (see `worst_answers`), with the guesses played.
+fn worst_table(
+    names: &[String],
+    worst: &[Vec<&AnswerResult>],
+    solution_list: &[String],
+) -> Vec<String> {
+    let mut lines = Vec::new();
+    for (config, (name, results)) in names.iter().zip(worst.iter()).enumerate() {
+        lines.push(format!("WORST ({})", name));
+        for result in results {
+            let (outcome, moves) = &result.games[config];
+            let guesses: Vec<&str> = moves.iter().map(|(guess, _)| guess.as_str()).collect();
+            lines.push(format!(
+                "  {:>2} {}: {}",
+                outcome.to_string(),
+                solution_list[result.index],
+                guesses.join(" ")
+            ));
+        }
+    }
+    lines
+}
+
+/// How many of the slowest answers the timing report lists.
+const SLOWEST_ANSWERS: usize = 5;
+
+/// One configuration's timings over a run. See `ReportOptions::timing`.
+#[derive(Debug, PartialEq)]
+struct TimingStats {
+    /// Turns timed, i.e. guesses made.
+    guesses: usize,
+
+    /// Time spent on all of them, across threads.
+    total: Duration,
+
+    /// Mean time of the first turn, the second, and so on.
+    per_turn: Vec<Duration>,
+
+    /// The answers whose games took longest, slowest first, with how long they took.
+    slowest: Vec<(usize, Duration)>,
+}
+
+/// Sum up each configuration's turn times. Answers without times (from a checkpoint) are left
+/// out.
+fn timing_stats(answers: &[AnswerResult], config_count: usize) -> Vec<TimingStats> {
+    (0..config_count)
+        .map(|config| {
+            let mut per_turn: Vec<(Duration, u32)> = Vec::new();
+            let mut games: Vec<(usize, Duration)> = Vec::new();
+            for result in answers.iter() {
+                let Some(times) = result.times.get(config) else {
+                    continue;
+                };
+                for (turn, time) in times.iter().enumerate() {
+                    if per_turn.len() <= turn {
+                        per_turn.push((Duration::ZERO, 0));
+                    }
+                    per_turn[turn].0 += *time;
+                    per_turn[turn].1 += 1;
+                }
+                games.push((result.index, times.iter().sum()));
+            }
+
+            // Stable, so equal times stay in list order.
+            games.sort_by_key(|(_, time)| std::cmp::Reverse(*time));
+            TimingStats {
+                guesses: per_turn.iter().map(|(_, count)| *count as usize).sum(),
+                total: games.iter().map(|(_, time)| *time).sum(),
+                per_turn: per_turn.iter().map(|(sum, count)| *sum / *count).collect(),
+                slowest: games.into_iter().take(SLOWEST_ANSWERS).collect(),
+            }
+        })
+        .collect()
+}
+
+fn format_millis(time: Duration) -> String {
+    format!("{:.2} ms", time.as_secs_f64() * 1000.0)
+}
+
+/// Guesses per second, or "no timed guesses" when nothing was timed, e.g. when every result
+/// came from a checkpoint, rather than a division by zero.
+fn guess_rate(guesses: usize, time: Duration) -> String {
+    if guesses == 0 || time.is_zero() {
+        "no timed guesses".to_string()
+    } else {
+        format!("{:.1} guesses/second", guesses as f64 / time.as_secs_f64())
+    }
+}
+
+/// Lines reporting timings: guesses per second over the whole run, then each configuration's
+/// rate, mean time per turn and slowest answers.
+fn timing_table(
+    names: &[String],
+    timing: &[TimingStats],
+    elapsed: Duration,
+    solution_list: &[String],
+) -> Vec<String> {
+    let guesses: usize = timing.iter().map(|stats| stats.guesses).sum();
+    let mut lines = vec![format!(
+        "TIMING: {} guesses in {:.2} s, {}",
+        guesses,
+        elapsed.as_secs_f64(),
+        guess_rate(guesses, elapsed)
+    )];
+    for (name, stats) in names.iter().zip(timing.iter()) {
+        lines.push(format!(
+            "  {}: {} guesses in {:.2} s of thread time, {}",
+            name,
+            stats.guesses,
+            stats.total.as_secs_f64(),
+            guess_rate(stats.guesses, stats.total)
+        ));
+        let per_turn: Vec<String> = stats
+            .per_turn
+            .iter()
+            .enumerate()
+            .map(|(turn, time)| format!("{}: {}", turn + 1, format_millis(*time)))
+            .collect();
+        lines.push(format!("    mean per turn: {}", per_turn.join(", ")));
+        let slowest: Vec<String> = stats
+            .slowest
+            .iter()
+            .map(|(index, time)| format!("{} {}", solution_list[*index], format_millis(*time)))
+            .collect();
+        lines.push(format!("    slowest answers: {}", slowest.join(", ")));
+    }
+    lines
+}
+
+/// Two-sided sign test: the chance of a split of wins at least this uneven, if each
+/// configuration were equally likely to win each answer. Ties are left out.
+fn sign_test(a_wins: usize, b_wins: usize) -> f64 {
+    let n = a_wins + b_wins;
+    // Sum the binomial probabilities of the less likely tail, in log space so that they don't
+    // underflow before they're added up.
+    let mut log_p = -(n as f64) * std::f64::consts::LN_2;
+    let mut tail = 0.0;
+    for i in 0..=a_wins.min(b_wins) {
+        tail += log_p.exp();
+        log_p += ((n - i) as f64 / (i + 1) as f64).ln();
+    }
+    (2.0 * tail).min(1.0)
+}
+
+/// Number of resamples for `bootstrap_interval`.
+const BOOTSTRAP_RESAMPLES: usize = 10000;
+
+/// A 95% bootstrap confidence interval for the mean of `values`, which must not be empty. The
+/// seed is fixed, so results are reproducible.
+fn bootstrap_interval(values: &[f64]) -> (f64, f64) {
+    let mut rng = SplitMix64(0);
+    let mut means: Vec<f64> = (0..BOOTSTRAP_RESAMPLES)
+        .map(|_| {
+            let total: f64 = (0..values.len())
+                .map(|_| values[rng.below(values.len())])
+                .sum();
+            total / values.len() as f64
+        })
+        .collect();
+    means.sort_by(f64::total_cmp);
+    let at = |fraction: f64| means[((means.len() - 1) as f64 * fraction).round() as usize];
+    (at(0.025), at(0.975))
+}
+
+/// How two configurations compare on the answers both of them won.
+struct PairedStats {
+    answers: usize,
+
+    /// Mean of the first configuration's guesses minus the second's, and its 95% confidence
+    /// interval.
+    mean_difference: f64,
+    interval: (f64, f64),
+
+    /// Sign test p-value for the record of wins (see `sign_test`).
+    sign_test_p: f64,
+}
+
+fn paired_stats(answers: &[AnswerResult], wins_wins_tie: [usize; 3]) -> Option<PairedStats> {
+    let differences: Vec<f64> = answers
+        .iter()
+        .filter_map(|result| match (result.games[0].0, result.games[1].0) {
+            (Outcome::Won(a), Outcome::Won(b)) => Some(a as f64 - b as f64),
+            _ => None,
+        })
+        .collect();
+    if differences.is_empty() {
+        return None;
+    }
+    Some(PairedStats {
+        answers: differences.len(),
+        mean_difference: differences.iter().sum::<f64>() / differences.len() as f64,
+        interval: bootstrap_interval(&differences),
+        sign_test_p: sign_test(wins_wins_tie[0], wins_wins_tie[1]),
+    })
+}
+
+/// Lines of a table with a column per configuration and a row per number of guesses, plus losses.
+fn comparison_table(names: &[String], totals: &[OutcomeCounts]) -> Vec<String> {
+    let widths: Vec<usize> = names.iter().map(|name| name.len().max(5)).collect();
+    let row = |label: &str, cells: Vec<String>| {
+        let cells: Vec<String> = cells
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| format!("{:>width$}", cell, width = width))
+            .collect();
+        format!("{:<8}{}", label, cells.join("  "))
+    };
+
+    let mut lines = vec![row("GUESSES", names.to_vec())];
+    let rows = totals.iter().map(|t| t.won.len()).max().unwrap_or(0);
+    for guesses in 1..rows {
+        let cells = totals
+            .iter()
+            .map(|t| t.won.get(guesses).copied().unwrap_or(0).to_string())
+            .collect();
+        lines.push(row(&guesses.to_string(), cells));
+    }
+    lines.push(row(
+        "lost",
+        totals.iter().map(|t| t.lost.to_string()).collect(),
+    ));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feature_groups() {
+        let answers = ["cigar", "sissy", "humph"].map(String::from);
+        let groups = |feature| feature_groups(feature, &answers, None);
+        assert_eq!(groups(Feature::FirstLetter), ["c", "s", "h"]);
+        assert_eq!(
+            groups(Feature::RepeatedLetters),
+            ["distinct", "repeated", "repeated"]
+        );
+        assert_eq!(
+            groups(Feature::Vowels),
+            ["2 vowels", "1 vowels", "1 vowels"]
+        );
+
+        let frequencies = HashMap::from([
+            ("cigar".to_string(), 3.0),
+            ("sissy".to_string(), 1.0),
+            ("humph".to_string(), 2.0),
+        ]);
+        assert_eq!(
+            feature_groups(Feature::Frequency, &answers, Some(&frequencies)),
+            ["quartile 3", "quartile 1", "quartile 2"]
+        );
+    }
+
+    #[test]
+    fn test_checkpoint_line() {
+        let games = vec![
+            (
+                Outcome::Won(2),
+                vec![
+                    ("crate".to_string(), "aapaa".parse().unwrap()),
+                    ("cigar".to_string(), "ccccc".parse().unwrap()),
+                ],
+            ),
+            (
+                Outcome::Lost,
+                vec![("soare".to_string(), "aaapa".parse().unwrap())],
+            ),
+        ];
+        let line = checkpoint_line("cigar", &games);
+        assert_eq!(line, "cigar\t2 crate=aapaa,cigar=ccccc\tX soare=aaapa");
+
+        let (answer, parsed) = parse_checkpoint_line(&line).unwrap();
+        assert_eq!(answer, "cigar");
+        assert_eq!(parsed, games);
+        assert_eq!(parse_checkpoint_line("cigar\t2 crate"), None);
+    }
+
+    #[test]
+    fn test_checkpoint_header() {
+        let names = vec!["groupsize".to_string()];
+        let solutions: Vec<String> = ["cigar", "sissy", "humph"].map(String::from).to_vec();
+        let options = RunOptions {
+            hard_mode: false,
+            deterministic: false,
+            max_guesses: None,
+            rules: ScoringRules::Wordle,
+            sample: None,
+        };
+        let header = checkpoint_header(&names, &[], &solutions, &options);
+
+        // Same length, different words.
+        let other: Vec<String> = ["cigar", "sissy", "awake"].map(String::from).to_vec();
+        assert_ne!(header, checkpoint_header(&names, &[], &other, &options));
+        assert_ne!(
+            header,
+            checkpoint_header(&names, &other, &solutions, &options)
+        );
+
+        let changed = [
+            RunOptions {
+                hard_mode: true,
+                ..options
+            },
+            RunOptions {
+                deterministic: true,
+                ..options
+            },
+            RunOptions {
+                max_guesses: Some(6),
+                ..options
+            },
+            RunOptions {
+                rules: ScoringRules::AllPresent,
+                ..options
+            },
+        ];
+        for changed in changed {
+            assert_ne!(header, checkpoint_header(&names, &[], &solutions, &changed));
         }
-        for (i, count) in count_size_tie.iter_mut().enumerate() {
-            *count += result.count_size_tie[i];
+    }
+
+    #[test]
+    fn test_worst_answers() {
+        let results: Vec<AnswerResult> = [
+            Outcome::Won(3),
+            Outcome::Won(5),
+            Outcome::Lost,
+            Outcome::Won(5),
+            Outcome::Won(2),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(index, outcome)| AnswerResult {
+            index,
+            games: vec![(*outcome, Vec::new())],
+            times: Vec::new(),
+        })
+        .collect();
+        let indexes = |count| -> Vec<usize> {
+            worst_answers(&results, 0, count)
+                .iter()
+                .map(|r| r.index)
+                .collect()
+        };
+        assert_eq!(indexes(2), [2, 1, 3]);
+        assert_eq!(indexes(0), [2]);
+        assert_eq!(indexes(10), [2, 1, 3, 0, 4]);
+    }
+
+    #[test]
+    fn test_run_solver() {
+        let solutions = ["fight", "light", "might", "night", "sight", "tight"].map(String::from);
+        let mut solver = Solver::new(&[], &solutions, false, false, Strategy::GroupSize);
+        let (outcome, moves) = run_solver(solver.clone(), "fight", "tight");
+        assert_eq!(outcome, Outcome::Won(moves.len()));
+        assert_eq!(moves.last().unwrap().0, "tight");
+
+        // Each guess rules out one word at most, and ties go to the words in list order, so
+        // "tight" doesn't come up within three guesses.
+        solver.set_max_guesses(3);
+        let (outcome, moves, times) = run_solver_timed(solver, "fight", "tight");
+        assert_eq!(outcome, Outcome::Lost);
+        assert_eq!(moves.len(), 3);
+        assert_eq!(times.len(), 3);
+    }
+
+    #[test]
+    fn test_timing_stats() {
+        let ms = Duration::from_millis;
+        let result = |index, times: &[u64]| AnswerResult {
+            index,
+            games: vec![(Outcome::Won(times.len()), Vec::new())],
+            times: vec![times.iter().map(|t| ms(*t)).collect()],
+        };
+        let answers = [
+            result(0, &[1, 10]),
+            result(1, &[1, 20, 30]),
+            AnswerResult {
+                index: 2,
+                games: vec![(Outcome::Won(1), Vec::new())],
+                times: Vec::new(),
+            },
+            result(3, &[1, 4]),
+        ];
+        let stats = timing_stats(&answers, 1);
+        assert_eq!(
+            stats,
+            [TimingStats {
+                guesses: 7,
+                total: ms(67),
+                per_turn: vec![ms(1), ms(34) / 3, ms(30)],
+                slowest: vec![(1, ms(51)), (0, ms(11)), (3, ms(5))],
+            }]
+        );
+
+        assert_eq!(guess_rate(7, ms(500)), "14.0 guesses/second");
+        let untimed = timing_stats(&answers[2..3], 1);
+        let names = ["groupsize".to_string()];
+        let lines = timing_table(&names, &untimed, Duration::ZERO, &[]);
+        assert_eq!(lines[0], "TIMING: 0 guesses in 0.00 s, no timed guesses");
+        assert_eq!(
+            lines[1],
+            "  groupsize: 0 guesses in 0.00 s of thread time, no timed guesses"
+        );
+    }
+
+    #[test]
+    fn test_sample_indexes() {
+        let sample = sample_indexes(100, 10, 1);
+        assert_eq!(sample.len(), 10);
+        assert!(sample.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(sample.iter().all(|i| *i < 100));
+        assert_eq!(sample_indexes(100, 10, 1), sample);
+        assert_ne!(sample_indexes(100, 10, 2), sample);
+        assert_eq!(sample_indexes(3, 10, 1), [0, 1, 2]);
+    }
+
+    #[test]
+    fn test_paired_stats() {
+        assert_eq!(sign_test(0, 0), 1.0);
+        assert_eq!(sign_test(5, 5), 1.0);
+        assert_eq!(sign_test(10, 0), 2.0 / 1024.0);
+        assert!((sign_test(1, 9) - 22.0 / 1024.0).abs() < 1e-12);
+        assert!(sign_test(2000, 1000) < 1e-10);
+
+        let (low, high) = bootstrap_interval(&[1.0, -1.0, 0.0, 0.0, 1.0, 0.0]);
+        assert!(low < 1.0 / 6.0 && 1.0 / 6.0 < high);
+        assert_eq!(bootstrap_interval(&[2.0, 2.0]), (2.0, 2.0));
+    }
+
+    #[test]
+    fn test_is_failure() {
+        assert!(!is_failure(Outcome::Won(6), 6));
+        assert!(is_failure(Outcome::Won(7), 6));
+        assert!(is_failure(Outcome::Lost, 6));
+    }
+
+    #[test]
+    fn test_outcome_counts() {
+        let mut counts = OutcomeCounts::default();
+        assert_eq!(counts.mean_and_std_dev(), None);
+        for outcome in [2, 3, 3, 4].map(Outcome::Won) {
+            counts.add(outcome);
         }
+        counts.add(Outcome::Lost);
+        assert_eq!(counts.mean_and_std_dev(), Some((3.0, 0.5f64.sqrt())));
+        assert_eq!(counts.percentile(0.5), Some(3));
+        assert_eq!(counts.percentile(0.95), Some(4));
+        assert_eq!(counts.worst(), Some(4));
     }
 
-    println!("GROUPCOUNT: {:?}", groupcount_totals);
-    println!("GROUPSIZE:  {:?}", groupsize_totals);
-    println!(
-        "RECORD (count wins - size wins - tie): {:?}",
-        count_size_tie
-    );
+    #[test]
+    fn test_configuration() {
+        let config: Configuration = "groupcount:hard:opener=crane".parse().unwrap();
+        assert_eq!(config.strategy, Strategy::GroupCount);
+        assert!(config.hard_mode);
+        assert_eq!(config.opener.as_deref(), Some("crane"));
+        assert_eq!(config.to_string(), "groupcount:hard:opener=crane");
+
+        assert!("fastest".parse::<Configuration>().is_err());
+        assert!("groupsize:opener=CRANE".parse::<Configuration>().is_err());
+    }
 }
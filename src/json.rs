@@ -0,0 +1,71 @@
+//! Just enough JSON writing for the binaries' JSON output, without depending on the `json`
+//! feature. Values are built up as strings that are already valid JSON: `string` and `number`
+//! encode single values, and `array` and `object` combine encoded ones.
+
+/// A JSON string, quoted, with quotes, backslashes and control characters escaped.
+pub fn string(s: &str) -> String {
+    let mut result = String::with_capacity(s.len() + 2);
+    result.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if c.is_control() => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+/// A JSON number, or null for NaN and infinities, which JSON can't represent.
+pub fn number(n: f64) -> String {
+    if n.is_finite() {
+        n.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+/// A JSON array of encoded values.
+pub fn array(items: impl IntoIterator<Item = String>) -> String {
+    let items: Vec<String> = items.into_iter().collect();
+    format!("[{}]", items.join(", "))
+}
+
+/// A JSON object of keys and encoded values, in order. Keys are escaped.
+pub fn object<K: AsRef<str>>(fields: impl IntoIterator<Item = (K, String)>) -> String {
+    let fields: Vec<String> = fields
+        .into_iter()
+        .map(|(key, value)| format!("{}: {}", string(key.as_ref()), value))
+        .collect();
+    format!("{{{}}}", fields.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json() {
+        assert_eq!(string("cigar"), "\"cigar\"");
+        assert_eq!(string("a\"b\\c\nd\u{1}"), "\"a\\\"b\\\\c\\nd\\u0001\"");
+        assert_eq!(number(1.5), "1.5");
+        assert_eq!(number(f64::NAN), "null");
+        assert_eq!(number(f64::INFINITY), "null");
+        assert_eq!(array(Vec::new()), "[]");
+
+        let value = object([
+            ("name", string("x:y")),
+            ("list", array(["1".to_string(), "2".to_string()])),
+        ]);
+        assert_eq!(value, "{\"name\": \"x:y\", \"list\": [1, 2]}");
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&value).unwrap()["list"][1],
+            2
+        );
+    }
+}
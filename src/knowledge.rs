@@ -0,0 +1,109 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::constraints::Constraints;
+use crate::score::DetailScore;
+
+/// What's known about a single letter, as a keyboard would display it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LetterState {
+    /// The letter hasn't been guessed yet.
+    Unknown,
+    /// The letter isn't in the solution.
+    Absent,
+    /// The letter is in the solution, but its position isn't known.
+    Present,
+    /// The letter is known to be in at least one position in the solution.
+    Correct,
+}
+
+/// Everything learned about individual letters from a sequence of guesses and their scores.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LetterKnowledge {
+    /// The letter known to be in each position, if any.
+    pub confirmed: [Option<char>; 5],
+
+    /// Letters known to be in the solution, mapped to the positions where they're known not to be.
+    /// Letters with confirmed positions are included too, if they've been seen elsewhere.
+    pub present: BTreeMap<char, BTreeSet<usize>>,
+
+    /// Letters known not to be in the solution at all.
+    pub eliminated: BTreeSet<char>,
+}
+
+impl LetterKnowledge {
+    /// Fold the guesses made so far and their scores into per-letter knowledge.
+    pub fn from_history(history: &[(&str, DetailScore)]) -> Self {
+        Self::from_constraints(&Constraints::from_history(history))
+    }
+
+    /// The per-letter view of `constraints`: what a keyboard shows, without the letter counts.
+    pub fn from_constraints(constraints: &Constraints) -> Self {
+        let mut result = LetterKnowledge {
+            confirmed: constraints.fixed,
+            ..Default::default()
+        };
+
+        // A letter that's in the solution isn't in the positions where it was scored present, or
+        // absent because the solution has no other copy of it.
+        for c in constraints.min_counts.keys() {
+            let excluded: BTreeSet<usize> = (0..5)
+                .filter(|i| constraints.excluded[*i].contains(c))
+                .collect();
+            if !excluded.is_empty() {
+                result.present.insert(*c, excluded);
+            }
+        }
+
+        result.eliminated = constraints
+            .max_counts
+            .iter()
+            .filter(|(c, max)| **max == 0 && !constraints.min_counts.contains_key(c))
+            .map(|(c, _)| *c)
+            .collect();
+
+        result
+    }
+
+    /// The state of a letter for rendering a keyboard.
+    pub fn keyboard_state(&self, letter: char) -> LetterState {
+        if self.confirmed.contains(&Some(letter)) {
+            LetterState::Correct
+        } else if self.present.contains_key(&letter) {
+            LetterState::Present
+        } else if self.eliminated.contains(&letter) {
+            LetterState::Absent
+        } else {
+            LetterState::Unknown
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::score::compute_score;
+
+    #[test]
+    fn test_from_history() {
+        let history = [
+            ("arise", compute_score("arise", "verge")),
+            ("speed", compute_score("speed", "verge")),
+        ];
+        let knowledge = LetterKnowledge::from_history(&history);
+
+        assert_eq!(knowledge.confirmed, [None, None, None, None, Some('e')]);
+        assert_eq!(
+            knowledge.present,
+            BTreeMap::from([('e', BTreeSet::from([2, 3])), ('r', BTreeSet::from([1]))])
+        );
+        assert_eq!(
+            knowledge.eliminated,
+            BTreeSet::from(['a', 'd', 'i', 'p', 's'])
+        );
+
+        assert_eq!(knowledge.keyboard_state('e'), LetterState::Correct);
+        assert_eq!(knowledge.keyboard_state('r'), LetterState::Present);
+        assert_eq!(knowledge.keyboard_state('s'), LetterState::Absent);
+        assert_eq!(knowledge.keyboard_state('v'), LetterState::Unknown);
+    }
+}
@@ -0,0 +1,136 @@
+//! Letter statistics for a set of possible solutions: how letters are distributed in each
+//! position, and how much learning whether a word contains each letter would narrow things down.
+//! Meant for people choosing their own guesses.
+
+use std::collections::BTreeMap;
+
+/// How many letters `LetterReport::lines` lists for each position.
+const LETTERS_PER_POSITION: usize = 8;
+
+/// Entropy in bits of a distribution given as counts out of `total`.
+fn entropy(counts: impl Iterator<Item = usize>, total: usize) -> f64 {
+    counts
+        .filter(|count| *count > 0)
+        .map(|count| {
+            let p = count as f64 / total as f64;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+pub struct LetterReport {
+    pub total: usize,
+
+    /// For each position, how many possibilities have each letter there, most common first.
+    pub positions: Vec<Vec<(char, usize)>>,
+
+    /// For each letter, how many possibilities contain it, and the information in bits that
+    /// learning whether the answer contains it would give. Most informative first.
+    pub letters: Vec<(char, usize, f64)>,
+}
+
+impl LetterReport {
+    pub fn new(possibilities: &[&str]) -> LetterReport {
+        let total = possibilities.len();
+        let word_length = possibilities
+            .iter()
+            .map(|word| word.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut positions = vec![BTreeMap::new(); word_length];
+        let mut containing: BTreeMap<char, usize> = BTreeMap::new();
+        for word in possibilities {
+            let mut seen: Vec<char> = Vec::new();
+            for (position, letter) in word.chars().enumerate() {
+                *positions[position].entry(letter).or_insert(0) += 1;
+                if !seen.contains(&letter) {
+                    seen.push(letter);
+                    *containing.entry(letter).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let positions = positions
+            .into_iter()
+            .map(|counts| {
+                let mut counts: Vec<(char, usize)> = counts.into_iter().collect();
+                counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+                counts
+            })
+            .collect();
+        let mut letters: Vec<(char, usize, f64)> = containing
+            .into_iter()
+            .map(|(letter, count)| {
+                let bits = entropy([count, total - count].into_iter(), total);
+                (letter, count, bits)
+            })
+            .collect();
+        letters.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+        LetterReport {
+            total,
+            positions,
+            letters,
+        }
+    }
+
+    /// The report as text: a line per position with its most common letters and the entropy of
+    /// its distribution, then a table of letters.
+    pub fn lines(&self) -> Vec<String> {
+        let percent = |count: usize| 100.0 * count as f64 / self.total as f64;
+        let mut lines = vec![format!("{} possibilities", self.total)];
+
+        for (i, counts) in self.positions.iter().enumerate() {
+            let mut cells: Vec<String> = counts
+                .iter()
+                .take(LETTERS_PER_POSITION)
+                .map(|(letter, count)| format!("{} {:.0}%", letter, percent(*count)))
+                .collect();
+            if counts.len() > LETTERS_PER_POSITION {
+                cells.push(format!("+{} more", counts.len() - LETTERS_PER_POSITION));
+            }
+            let bits = entropy(counts.iter().map(|(_, count)| *count), self.total);
+            lines.push(format!(
+                "Position {} ({:.2} bits): {}",
+                i + 1,
+                bits,
+                cells.join(", ")
+            ));
+        }
+
+        lines.push("LETTER  IN WORDS  BITS".to_string());
+        for (letter, count, bits) in self.letters.iter() {
+            lines.push(format!(
+                "{:<6}  {:>7.0}%  {:.3}",
+                letter,
+                percent(*count),
+                bits
+            ));
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_letter_report() {
+        let report = LetterReport::new(&["cigar", "civic", "humph", "hyper"]);
+        assert_eq!(report.total, 4);
+        assert_eq!(report.positions[0], [('c', 2), ('h', 2)]);
+        assert_eq!(report.positions[1][0], ('i', 2));
+
+        // "c" is in half the words, so it's worth a whole bit; "h" too. "civic" has two "c"s
+        // but only counts once.
+        assert_eq!(report.letters[0].2, 1.0);
+        let c = report.letters.iter().find(|(l, _, _)| *l == 'c').unwrap();
+        assert_eq!(c.1, 2);
+        let y = report.letters.iter().find(|(l, _, _)| *l == 'y').unwrap();
+        assert!(y.2 < 1.0);
+
+        assert_eq!(report.lines()[0], "4 possibilities");
+    }
+}
@@ -1,7 +1,25 @@
+pub mod absurdle;
+pub mod constraints;
 pub mod eval;
+pub mod filter;
+pub mod grade;
+pub mod json;
+pub mod knowledge;
+pub mod letters;
+pub mod list_cache;
+pub mod lists;
 pub mod loader;
+pub mod multi;
+pub mod prove;
 pub mod score;
+pub mod session;
 pub mod solver;
+pub mod tree;
+
+/// The default for the binaries' `--thread-count` options: one thread per CPU.
+pub fn default_thread_count() -> usize {
+    std::thread::available_parallelism().map_or(1, usize::from)
+}
 
 pub fn read_guess_interactively<'a>(
     input: &mut dyn std::io::BufRead,
@@ -19,7 +37,7 @@ pub fn read_guess_interactively<'a>(
         input.read_line(&mut buf).unwrap();
         buf.truncate(buf.len() - 1);
 
-        if buf.len() != 5 || !buf.as_bytes().iter().all(u8::is_ascii_lowercase) {
+        if !score::is_valid_word(&buf) {
             println!("Guess must be 5 lowercase letters");
             continue;
         }
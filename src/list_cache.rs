@@ -0,0 +1,109 @@
+//! A compact binary format for validated word lists, so they can be loaded without parsing and
+//! validating every line again. See `LoadOptions::cache`.
+//!
+//! Layout (integers are little-endian):
+//!   - `MAGIC` (8 bytes)
+//!   - hash of the source the list was loaded from (u64)
+//!   - hash of the entries that follow (u64)
+//!   - number of entries (u32)
+//!   - entries: the word's UTF-8 bytes, zero-padded to `WORD_WIDTH`, then its frequency (f64)
+
+use std::path::{Path, PathBuf};
+
+const MAGIC: &[u8; 8] = b"WSLCACH1";
+const HEADER_LEN: usize = 28;
+
+/// Five letters of up to 4 bytes each.
+const WORD_WIDTH: usize = 20;
+const ENTRY_LEN: usize = WORD_WIDTH + 8;
+
+/// 64-bit FNV-1a. Unlike `std`'s hashers, its output is stable across builds, which matters for
+/// a file format.
+pub fn hash(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// `hash` of a list's words, each followed by a newline, to tell whether two lists are the same,
+/// e.g. in a checkpoint file.
+pub fn hash_words(words: &[String]) -> u64 {
+    let mut data = Vec::with_capacity(words.len() * 6);
+    for word in words {
+        data.extend_from_slice(word.as_bytes());
+        data.push(b'\n');
+    }
+    hash(&data)
+}
+
+/// Where the cache for a list file is kept: next to it, with ".cache" appended to the name.
+pub fn cache_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".cache");
+    PathBuf::from(name)
+}
+
+/// Encode a list. `source_hash` identifies what the list was loaded from, so that a stale cache
+/// can be detected. Words must be valid (see `is_valid_word`).
+pub fn encode(source_hash: u64, entries: &[(String, f64)]) -> Vec<u8> {
+    let mut body = Vec::with_capacity(entries.len() * ENTRY_LEN);
+    for (word, weight) in entries {
+        let mut padded = [0u8; WORD_WIDTH];
+        padded[..word.len()].copy_from_slice(word.as_bytes());
+        body.extend_from_slice(&padded);
+        body.extend_from_slice(&weight.to_le_bytes());
+    }
+
+    let mut result = Vec::with_capacity(HEADER_LEN + body.len());
+    result.extend_from_slice(MAGIC);
+    result.extend_from_slice(&source_hash.to_le_bytes());
+    result.extend_from_slice(&hash(&body).to_le_bytes());
+    result.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+    result.extend_from_slice(&body);
+    result
+}
+
+/// Decode a list, or return `None` if the data is corrupt or wasn't made from the source with
+/// this hash.
+pub fn decode(data: &[u8], source_hash: u64) -> Option<Vec<(String, f64)>> {
+    let (header, body) = data.split_at_checked(HEADER_LEN)?;
+    let read_u64 = |at: usize| u64::from_le_bytes(header[at..at + 8].try_into().unwrap());
+    let count = u32::from_le_bytes(header[24..28].try_into().unwrap()) as usize;
+    if &header[..8] != MAGIC
+        || read_u64(8) != source_hash
+        || read_u64(16) != hash(body)
+        || body.len() != count.checked_mul(ENTRY_LEN)?
+    {
+        return None;
+    }
+
+    body.chunks_exact(ENTRY_LEN)
+        .map(|entry| {
+            let (word, weight) = entry.split_at(WORD_WIDTH);
+            let length = word.iter().position(|b| *b == 0).unwrap_or(WORD_WIDTH);
+            let word = std::str::from_utf8(&word[..length]).ok()?;
+            Some((
+                word.to_string(),
+                f64::from_le_bytes(weight.try_into().unwrap()),
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let entries = vec![("cigar".to_string(), 1.0), ("ñandú".to_string(), 2.5)];
+        let data = encode(42, &entries);
+        assert_eq!(decode(&data, 42), Some(entries));
+        assert_eq!(decode(&data, 43), None);
+
+        let mut corrupt = data.clone();
+        *corrupt.last_mut().unwrap() ^= 1;
+        assert_eq!(decode(&corrupt, 42), None);
+        assert_eq!(decode(&data[..data.len() - 1], 42), None);
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::HashSet;
+use std::io::{stderr, stdout};
+
+use argparse::{ArgumentParser, Parse, Store, StoreTrue};
+
+use wordle_solver::loader::load_list_from_file;
+use wordle_solver::solver::{Outcome, Solver, Strategy};
+
+use crate::histogram::run_solver;
+
+/// Words in `new` but not `old`, and words in `old` but not `new`, each in list order.
+fn diff<'a>(old: &'a [String], new: &'a [String]) -> (Vec<&'a str>, Vec<&'a str>) {
+    let old_set: HashSet<&str> = old.iter().map(String::as_str).collect();
+    let new_set: HashSet<&str> = new.iter().map(String::as_str).collect();
+    let added = new
+        .iter()
+        .map(String::as_str)
+        .filter(|w| !old_set.contains(w));
+    let removed = old
+        .iter()
+        .map(String::as_str)
+        .filter(|w| !new_set.contains(w));
+    (added.collect(), removed.collect())
+}
+
+fn print_diff(name: &str, old: &[String], new: &[String]) {
+    let (added, removed) = diff(old, new);
+    println!(
+        "{}: {} -> {} words (+{} -{})",
+        name,
+        old.len(),
+        new.len(),
+        added.len(),
+        removed.len()
+    );
+    if !added.is_empty() {
+        println!("  + {}", added.join(" "));
+    }
+    if !removed.is_empty() {
+        println!("  - {}", removed.join(" "));
+    }
+}
+
+/// Average guesses to solve each answer, or `None` if there are no answers. Losses can't happen
+/// without a guess limit.
+fn average_guesses<'a>(new_solver: impl Fn() -> Solver<'a>, answers: &[&str]) -> Option<f64> {
+    if answers.is_empty() {
+        return None;
+    }
+    let first_guess = new_solver().next_guess();
+    let total: usize = answers
+        .iter()
+        .map(
+            |answer| match run_solver(new_solver(), first_guess, answer).0 {
+                Outcome::Won(n) => n,
+                Outcome::Lost => unreachable!(),
+            },
+        )
+        .sum();
+    Some(total as f64 / answers.len() as f64)
+}
+
+/// Up to `count` answers that are in both solution lists, evenly spaced through the new list, so
+/// that both lists are measured on the same answers.
+fn sample_answers<'a>(old: &[String], new: &'a [String], count: usize) -> Vec<&'a str> {
+    let old_set: HashSet<&str> = old.iter().map(String::as_str).collect();
+    let shared: Vec<&str> = new
+        .iter()
+        .map(String::as_str)
+        .filter(|w| old_set.contains(w))
+        .collect();
+    if count == 0 || shared.len() <= count {
+        return shared;
+    }
+    (0..count)
+        .map(|i| shared[i * shared.len() / count])
+        .collect()
+}
+
+/// `wordle-solver diff-lists`: compare two versions of the word lists, and how the solver does
+/// with each.
+pub fn command(args: Vec<String>) {
+    let mut paths = [(); 4].map(|_| String::new());
+    let mut sample = 100;
+    let mut strategy = Strategy::GroupSize;
+    let mut hard_mode = false;
+
+    {
+        let mut parser = ArgumentParser::new();
+        parser.set_description(concat!(
+            "Show words added to and removed from the lists, and how that changes the solver's ",
+            "opener and average guesses"
+        ));
+        let [old_guessable, old_solutions, new_guessable, new_solutions] = &mut paths;
+        parser.refer(old_guessable).required().add_argument(
+            "old-guessable",
+            Store,
+            "The old guessable list",
+        );
+        parser.refer(old_solutions).required().add_argument(
+            "old-solutions",
+            Store,
+            "The old solutions list",
+        );
+        parser.refer(new_guessable).required().add_argument(
+            "new-guessable",
+            Store,
+            "The new guessable list",
+        );
+        parser.refer(new_solutions).required().add_argument(
+            "new-solutions",
+            Store,
+            "The new solutions list",
+        );
+        parser.refer(&mut sample).add_option(
+            &["--sample"],
+            Parse,
+            "How many answers (in both solution lists) to average guesses over; 0 for all",
+        );
+        parser.refer(&mut strategy).add_option(
+            &["--strategy"],
+            Parse,
+            "Which solving strategy to use: groupcount or groupsize (default)",
+        );
+        parser.refer(&mut hard_mode).add_option(
+            &["--hard-mode"],
+            StoreTrue,
+            "Only guess words that are possible solutions",
+        );
+        if let Err(code) = parser.parse(args, &mut stdout(), &mut stderr()) {
+            std::process::exit(code);
+        }
+    }
+
+    let [old_guessable, old_solutions, new_guessable, new_solutions] = paths.map(|path| {
+        load_list_from_file(path.as_ref()).unwrap_or_else(|e| {
+            eprintln!("{}: {}", path, e);
+            std::process::exit(1);
+        })
+    });
+
+    print_diff("Solutions", &old_solutions, &new_solutions);
+    print_diff("Guessable", &old_guessable, &new_guessable);
+
+    let old_solver = || Solver::new(&old_guessable, &old_solutions, hard_mode, false, strategy);
+    let new_solver = || Solver::new(&new_guessable, &new_solutions, hard_mode, false, strategy);
+    println!(
+        "Opener: {} -> {}",
+        old_solver().next_guess(),
+        new_solver().next_guess()
+    );
+
+    let answers = sample_answers(&old_solutions, &new_solutions, sample);
+    if let (Some(old_average), Some(new_average)) = (
+        average_guesses(old_solver, &answers),
+        average_guesses(new_solver, &answers),
+    ) {
+        println!(
+            "Average guesses over {} shared answers: {:.3} -> {:.3}",
+            answers.len(),
+            old_average,
+            new_average
+        );
+    } else {
+        println!("No answers are in both solution lists");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let old = ["cigar", "rebut", "sissy"].map(String::from);
+        let new = ["rebut", "humph", "cigar"].map(String::from);
+        assert_eq!(diff(&old, &new), (vec!["humph"], vec!["sissy"]));
+
+        let sample = sample_answers(&old, &new, 1);
+        assert_eq!(sample, ["rebut"]);
+        assert_eq!(sample_answers(&old, &new, 0), ["rebut", "cigar"]);
+    }
+}
@@ -0,0 +1,257 @@
+//! A local cache of word lists, downloaded from well-known sources (see `BUILTIN_SOURCES`) or
+//! ones the user registers, so that lists can be referred to by name instead of by path.
+//!
+//! The cache directory holds a `sources.txt` registry, where each line is a name followed by the
+//! URLs of its guessable and solutions lists, e.g. `mylists https://... https://...`. Blank lines
+//! and lines starting with `#` are ignored. Fetched lists are stored as `<name>/guessable.txt`
+//! and `<name>/solutions.txt`. Lists are downloaded with the `curl` command, which has to be
+//! installed.
+
+use std::env;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::loader::LoadOptions;
+
+/// Where a named pair of lists can be downloaded from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Source {
+    pub name: String,
+    pub guessable_url: String,
+    pub solutions_url: String,
+}
+
+/// Sources that are available without registering them, as (name, guessable list URL, solutions
+/// list URL). A registered source with the same name replaces one of these.
+pub const BUILTIN_SOURCES: [(&str, &str, &str); 2] = [
+    // The original game's lists, before the New York Times bought it. The guessable list
+    // doesn't repeat the solutions.
+    (
+        "wordle",
+        "https://gist.githubusercontent.com/cfreshman/cdcdf777450c5b5301e439061d29694c/raw/wordle-allowed-guesses.txt",
+        "https://gist.githubusercontent.com/cfreshman/a03ef2cba789d8cf00c08f767e0fad7b/raw/wordle-answers-alphabetical.txt",
+    ),
+    // The New York Times' current list of allowed guesses, with the original solutions.
+    (
+        "nyt",
+        "https://raw.githubusercontent.com/tabatkins/wordle-list/main/words",
+        "https://gist.githubusercontent.com/cfreshman/a03ef2cba789d8cf00c08f767e0fad7b/raw/wordle-answers-alphabetical.txt",
+    ),
+];
+
+/// The cache directory: `$WORDLE_SOLVER_CACHE` if set, otherwise `wordle-solver` inside
+/// `$XDG_CACHE_HOME` or `~/.cache`.
+pub fn cache_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("WORDLE_SOLVER_CACHE") {
+        return Some(PathBuf::from(dir));
+    }
+    let base = match env::var_os("XDG_CACHE_HOME") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env::var_os("HOME")?).join(".cache"),
+    };
+    Some(base.join("wordle-solver"))
+}
+
+/// The path of the sources registry in a cache directory.
+pub fn sources_path(dir: &Path) -> PathBuf {
+    dir.join("sources.txt")
+}
+
+/// Names become directory names, so only allow letters, digits, `-`, `_` and `.`, and no leading
+/// `.`.
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Parse the contents of a sources registry.
+pub fn parse_sources(text: &str) -> Result<Vec<Source>, Error> {
+    let mut sources = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let problem = match fields[..] {
+            [name, _, _] if !is_valid_name(name) => format!("Invalid list name: {}", name),
+            [name, guessable_url, solutions_url] => {
+                sources.push(Source {
+                    name: name.to_string(),
+                    guessable_url: guessable_url.to_string(),
+                    solutions_url: solutions_url.to_string(),
+                });
+                continue;
+            }
+            _ => "Expected a name, a guessable list URL and a solutions list URL".to_string(),
+        };
+        let msg = format!("Line {}: {}", index + 1, problem);
+        return Err(Error::new(ErrorKind::InvalidData, msg));
+    }
+    Ok(sources)
+}
+
+/// The built-in sources, then `registered`, which replace built-in ones with the same name.
+pub fn with_builtins(registered: Vec<Source>) -> Vec<Source> {
+    let mut sources: Vec<Source> = BUILTIN_SOURCES
+        .iter()
+        .filter(|(name, _, _)| !registered.iter().any(|source| source.name == *name))
+        .map(|(name, guessable_url, solutions_url)| Source {
+            name: name.to_string(),
+            guessable_url: guessable_url.to_string(),
+            solutions_url: solutions_url.to_string(),
+        })
+        .collect();
+    sources.extend(registered);
+    sources
+}
+
+/// The sources available with a cache directory: the built-in ones, and the ones registered in
+/// it (see `with_builtins`). A missing registry has only the built-in sources.
+pub fn read_sources(dir: &Path) -> Result<Vec<Source>, Error> {
+    let registered = match fs::read_to_string(sources_path(dir)) {
+        Ok(text) => parse_sources(&text)?,
+        Err(e) if e.kind() == ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(e),
+    };
+    Ok(with_builtins(registered))
+}
+
+/// The paths of the guessable and solutions lists cached under a name.
+pub fn list_paths(dir: &Path, name: &str) -> (PathBuf, PathBuf) {
+    let list_dir = dir.join(name);
+    (
+        list_dir.join("guessable.txt"),
+        list_dir.join("solutions.txt"),
+    )
+}
+
+/// The names of lists that have been fetched into a cache directory, in sorted order.
+pub fn cached_names(dir: &Path) -> Result<Vec<String>, Error> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let mut names = Vec::new();
+    for entry in entries {
+        let name = entry?.file_name().to_string_lossy().into_owned();
+        let (guessable, solutions) = list_paths(dir, &name);
+        if is_valid_name(&name) && guessable.is_file() && solutions.is_file() {
+            names.push(name);
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// The cached lists for a name, or an error explaining how to fetch them.
+pub fn find_cached(dir: &Path, name: &str) -> Result<(PathBuf, PathBuf), Error> {
+    let (guessable, solutions) = list_paths(dir, name);
+    if is_valid_name(name) && guessable.is_file() && solutions.is_file() {
+        return Ok((guessable, solutions));
+    }
+    let msg = format!(
+        "No cached lists named {}; run `wordle-solver lists fetch {}` first",
+        name, name
+    );
+    Err(Error::new(ErrorKind::NotFound, msg))
+}
+
+/// Download a source's lists with `curl` into the cache directory. The lists are loaded leniently
+/// and saved with one normalized word per line, so they load quickly and strictly afterward.
+/// Existing lists with the same name are only replaced once both downloads succeed. Fails with
+/// `ErrorKind::NotFound` if `curl` isn't installed.
+pub fn fetch(dir: &Path, source: &Source) -> Result<(), Error> {
+    let (guessable, solutions) = list_paths(dir, &source.name);
+    fs::create_dir_all(dir.join(&source.name))?;
+
+    let mut downloaded = Vec::new();
+    for (url, path) in [
+        (&source.guessable_url, &guessable),
+        (&source.solutions_url, &solutions),
+    ] {
+        let partial = path.with_extension("part");
+        let status = Command::new("curl")
+            .args([
+                "--fail",
+                "--silent",
+                "--show-error",
+                "--location",
+                "--output",
+            ])
+            .arg(&partial)
+            .arg(url)
+            .status()
+            .map_err(|e| match e.kind() {
+                ErrorKind::NotFound => Error::new(
+                    ErrorKind::NotFound,
+                    "Downloading lists needs curl, which isn't installed (or isn't on the PATH)",
+                ),
+                _ => e,
+            })?;
+        if !status.success() {
+            let _ = fs::remove_file(&partial);
+            let msg = format!("Downloading {} failed ({})", url, status);
+            return Err(Error::other(msg));
+        }
+
+        let words = LoadOptions::lenient().load_file(&partial);
+        let _ = fs::remove_file(&partial);
+        let words = words.map_err(|e| Error::new(e.kind(), format!("{}: {}", url, e)))?;
+        downloaded.push((path, words));
+    }
+
+    for (path, words) in downloaded {
+        let mut contents = words.join("\n");
+        contents.push('\n');
+        fs::write(path, contents)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sources() {
+        let text = "# comment\n\nmine https://a/g.txt https://a/s.txt\n";
+        assert_eq!(
+            parse_sources(text).unwrap(),
+            [Source {
+                name: "mine".to_string(),
+                guessable_url: "https://a/g.txt".to_string(),
+                solutions_url: "https://a/s.txt".to_string(),
+            }]
+        );
+
+        let error = parse_sources("mine https://a/g.txt").unwrap_err();
+        assert!(error.to_string().starts_with("Line 1: Expected"));
+        assert!(parse_sources("../up https://a/g.txt https://a/s.txt").is_err());
+    }
+
+    #[test]
+    fn test_with_builtins() {
+        let names = |sources: &[Source]| -> Vec<String> {
+            sources.iter().map(|source| source.name.clone()).collect()
+        };
+        assert_eq!(names(&with_builtins(Vec::new())), ["wordle", "nyt"]);
+
+        let mine = Source {
+            name: "wordle".to_string(),
+            guessable_url: "https://a/g.txt".to_string(),
+            solutions_url: "https://a/s.txt".to_string(),
+        };
+        let sources = with_builtins(vec![mine.clone()]);
+        assert_eq!(names(&sources), ["nyt", "wordle"]);
+        assert_eq!(sources[1], mine);
+    }
+}
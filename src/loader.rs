@@ -1,23 +1,1006 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader, Error, ErrorKind};
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::fs::{self, File};
+use std::io::{stdin, BufRead, BufReader, Error, ErrorKind, Read};
 use std::path::Path;
+use std::str::FromStr;
 
-/// Read a word list from a file (one word per line).
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::filter::WordFilter;
+use crate::list_cache;
+use crate::score::is_valid_word;
+
+/// How to clean up lines of a word list before validating them. The default is strict: only
+/// trailing whitespace (including Windows line endings) is removed, and anything else that isn't
+/// a valid word is an error.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LoadOptions {
+    /// Convert words to lowercase.
+    pub lowercase: bool,
+
+    /// Remove leading whitespace too.
+    pub trim: bool,
+
+    /// Skip empty lines.
+    pub skip_blank: bool,
+
+    /// Ignore everything from "#" to the end of the line, and skip lines that only have a comment.
+    pub skip_comments: bool,
+
+    /// Convert words to Unicode normalization form C, so that accented letters written as a base
+    /// letter plus a combining accent count as one letter, and match the precomposed form.
+    pub nfc: bool,
+
+    /// Remove accents, e.g. "é" becomes "e". This also applies to letters that some languages
+    /// consider distinct, like "ñ", so only use it for games that ignore accents.
+    pub fold_accents: bool,
+
+    /// Skip lines that aren't valid words instead of failing. They're listed in the `LoadReport`.
+    pub skip_invalid: bool,
+
+    /// Keep only the first copy of words that appear more than once.
+    pub dedup: bool,
+
+    /// When loading a file, save the validated words in a binary cache next to it (see
+    /// `list_cache`), and on later loads, use the cache instead if the file and these options
+    /// haven't changed. Lists with problems in their `LoadReport` aren't cached.
+    pub cache: bool,
+
+    /// Leave out words that don't pass this filter, after validating them.
+    pub filter: WordFilter,
+}
+
+/// Problems found while loading a list. Line numbers start at 1.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LoadReport {
+    /// Lines that were skipped because of `LoadOptions::skip_invalid`, and what was wrong.
+    pub invalid: Vec<(usize, String)>,
+
+    /// Lines with a word that already appeared on an earlier line, and the word. These are only
+    /// removed with `LoadOptions::dedup`.
+    pub duplicates: Vec<(usize, String)>,
+}
+
+impl LoadReport {
+    /// Whether no problems were found.
+    pub fn is_clean(&self) -> bool {
+        self.invalid.is_empty() && self.duplicates.is_empty()
+    }
+}
+
+impl Display for LoadReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} invalid lines, {} duplicates",
+            self.invalid.len(),
+            self.duplicates.len()
+        )?;
+        for (line, problem) in self.invalid.iter() {
+            writeln!(f, "  line {}: {}", line, problem)?;
+        }
+        for (line, word) in self.duplicates.iter() {
+            writeln!(f, "  line {}: duplicate of {}", line, word)?;
+        }
+        Ok(())
+    }
+}
+
+/// A word list with metadata, from the JSON format (see `LoadOptions::load_json`).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WordList {
+    /// The language of the words, e.g. "en".
+    pub language: Option<String>,
+
+    /// Letters per word. Only 5 is supported.
+    pub word_length: Option<usize>,
+
+    /// Where the words came from.
+    pub source: Option<String>,
+
+    /// The license the list is distributed under.
+    pub license: Option<String>,
+
+    /// Words that can be guessed but aren't possible solutions, with frequencies.
+    pub guessable: Vec<(String, f64)>,
+
+    /// Possible solutions, with frequencies.
+    pub solutions: Vec<(String, f64)>,
+}
+
+/// A calendar date, written as YYYY-MM-DD in past-answer lists.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl FromStr for Date {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid date: {} (must be YYYY-MM-DD)", s);
+        let mut parts = s.split('-');
+        let (Some(year), Some(month), Some(day), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+        if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+            return Err(invalid());
+        }
+
+        let year: u16 = year.parse().map_err(|_| invalid())?;
+        let month: u8 = month.parse().map_err(|_| invalid())?;
+        let day: u8 = day.parse().map_err(|_| invalid())?;
+        let leap =
+            year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+        let days_in_month = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if leap => 29,
+            2 => 28,
+            _ => return Err(invalid()),
+        };
+        if day < 1 || day > days_in_month {
+            return Err(invalid());
+        }
+        Ok(Date { year, month, day })
+    }
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// A word that was the answer on a given date.
+pub type PastAnswer = (Date, String);
+
+/// A normalized line of a list, with its line number.
+type Line = (usize, String);
+
+/// Guessable words and possible solutions from a single list, with their frequencies.
+pub type CombinedList = (Vec<(String, f64)>, Vec<(String, f64)>);
+
+impl LoadOptions {
+    /// Options that accept the messiest lists: all normalization enabled, except accent folding,
+    /// which changes words' meaning.
+    pub fn lenient() -> Self {
+        LoadOptions {
+            lowercase: true,
+            trim: true,
+            skip_blank: true,
+            skip_comments: true,
+            nfc: true,
+            ..LoadOptions::default()
+        }
+    }
+
+    /// Read a word list from a file (one word per line). A path of "-" reads from stdin instead.
+    /// Files compressed with gzip or zstd are decompressed; see `load`. Frequencies (see
+    /// `load_weighted`) are allowed, but ignored.
+    pub fn load_file(&self, path: &Path) -> Result<Vec<String>, Error> {
+        self.load_weighted_file(path).map(without_weights)
+    }
+
+    /// Like `load_file`, but also returns each word's frequency; see `load_weighted`.
+    pub fn load_weighted_file(&self, path: &Path) -> Result<Vec<(String, f64)>, Error> {
+        self.load_weighted_file_with_report(path)
+            .map(|(entries, _)| entries)
+    }
+
+    /// Like `load_weighted_file`, but also report skipped lines and duplicates.
+    pub fn load_weighted_file_with_report(
+        &self,
+        path: &Path,
+    ) -> Result<(Vec<(String, f64)>, LoadReport), Error> {
+        if self.cache && !is_stdin_path(path) {
+            return self.load_cached_file(path);
+        }
+        self.parse_weighted_words(self.read_lines_from_file(path)?)
+    }
+
+    fn load_cached_file(&self, path: &Path) -> Result<(Vec<(String, f64)>, LoadReport), Error> {
+        let source = fs::read(path)?;
+        let options = LoadOptions {
+            cache: false,
+            ..self.clone()
+        };
+        let mut hashed = format!("{:?}", options).into_bytes();
+        hashed.extend_from_slice(&source);
+        let source_hash = list_cache::hash(&hashed);
+
+        let cache_path = list_cache::cache_path(path);
+        if let Ok(data) = fs::read(&cache_path) {
+            if let Some(entries) = list_cache::decode(&data, source_hash) {
+                return Ok((entries, LoadReport::default()));
+            }
+        }
+
+        let (entries, report) = self.parse_weighted_words(self.read_lines(&source[..])?)?;
+        if report.is_clean() {
+            // The cache only saves time, so failing to write it (e.g. because the directory is
+            // read-only) isn't an error.
+            let _ = fs::write(&cache_path, list_cache::encode(source_hash, &entries));
+        }
+        Ok((entries, report))
+    }
+
+    /// Read a word list from any reader. If the data starts with a gzip or zstd header, decompress
+    /// it first. Decompression needs the `gzip` or `zstd` feature respectively; without it,
+    /// compressed data is an error.
+    pub fn load<R: BufRead>(&self, reader: R) -> Result<Vec<String>, Error> {
+        self.load_weighted(reader).map(without_weights)
+    }
+
+    /// Like `load`, but each line can have a frequency after the word, separated by a tab (e.g.
+    /// "cigar\t1234"), which is returned along with the word. Words without one get 1. These are
+    /// suitable as priors for `Solver::set_priors`.
+    pub fn load_weighted<R: BufRead>(&self, reader: R) -> Result<Vec<(String, f64)>, Error> {
+        self.parse_weighted_words(self.read_lines(reader)?)
+            .map(|(entries, _)| entries)
+    }
+
+    /// Load a single list that marks which of its words are possible solutions, and return
+    /// `(guessable, solutions)` with frequencies as in `load_weighted`. A word is a possible
+    /// solution if it ends with "*" (e.g. "cigar*" or "cigar*\t1234"), or if it comes after a
+    /// "[solutions]" line (up to a "[guessable]" line). If no words are marked, all of them are
+    /// possible solutions, as with `Solver::with_single_list`.
+    pub fn load_combined<R: BufRead>(&self, reader: R) -> Result<CombinedList, Error> {
+        self.split_combined(self.read_lines(reader)?)
+            .map(|(list, _)| list)
+    }
+
+    /// Like `load_combined`, but from a file, as with `load_file`.
+    pub fn load_combined_file(&self, path: &Path) -> Result<CombinedList, Error> {
+        self.load_combined_file_with_report(path)
+            .map(|(list, _)| list)
+    }
+
+    /// Like `load_combined_file`, but also report skipped lines and duplicates.
+    pub fn load_combined_file_with_report(
+        &self,
+        path: &Path,
+    ) -> Result<(CombinedList, LoadReport), Error> {
+        self.split_combined(self.read_lines_from_file(path)?)
+    }
+
+    /// Load a word list in JSON format, which carries metadata along with the words:
+    ///
+    /// ```json
+    /// {
+    ///   "language": "en",
+    ///   "word_length": 5,
+    ///   "source": "https://example.com/words",
+    ///   "license": "CC0-1.0",
+    ///   "words": ["aahed", {"word": "cigar", "solution": true, "frequency": 1234}]
+    /// }
+    /// ```
+    ///
+    /// All fields besides "words" are optional, as are "solution" and "frequency". As with
+    /// `load_combined`, if no words are marked as solutions, all of them are. Words are normalized
+    /// according to these options; in the `LoadReport`, line numbers are positions in "words".
+    /// Needs the `json` feature; without it, this always fails.
+    pub fn load_json<R: Read>(&self, reader: R) -> Result<(WordList, LoadReport), Error> {
+        #[cfg(feature = "json")]
+        {
+            json::load(self, reader)
+        }
+
+        #[cfg(not(feature = "json"))]
+        {
+            let _ = reader;
+            let msg = "JSON word lists need the json feature; rebuild with it to read them";
+            Err(Error::new(ErrorKind::InvalidData, msg))
+        }
+    }
+
+    /// Like `load_json`, but from a file, as with `load_file`. JSON lists aren't decompressed.
+    pub fn load_json_file(&self, path: &Path) -> Result<(WordList, LoadReport), Error> {
+        if is_stdin_path(path) {
+            return self.load_json(stdin().lock());
+        }
+        self.load_json(BufReader::new(File::open(path)?))
+    }
+
+    /// Read a CSV of past answers. Each line is a date (YYYY-MM-DD) and the word that was the
+    /// answer that day, separated by a comma, e.g. `2021-06-19,cigar`; any further columns are
+    /// ignored, as is a header line whose first column is "date". Lines are normalized as in
+    /// `load`, and spaces around columns are removed.
+    pub fn load_past_answers<R: BufRead>(&self, reader: R) -> Result<Vec<PastAnswer>, Error> {
+        self.parse_past_answers(self.read_lines(reader)?)
+    }
+
+    /// Like `load_past_answers`, but from a file, as with `load_file`.
+    pub fn load_past_answers_file(&self, path: &Path) -> Result<Vec<PastAnswer>, Error> {
+        self.parse_past_answers(self.read_lines_from_file(path)?)
+    }
+
+    fn parse_past_answers(&self, lines: Vec<Line>) -> Result<Vec<PastAnswer>, Error> {
+        let mut checker = Checker::new(self);
+        let mut result = Vec::new();
+        for (index, (line_number, line)) in lines.into_iter().enumerate() {
+            let mut columns = line.split(',').map(str::trim);
+            let date = columns.next().unwrap_or_default();
+            if index == 0 && date.eq_ignore_ascii_case("date") {
+                continue;
+            }
+
+            let date = match date.parse::<Date>() {
+                Ok(date) => date,
+                Err(problem) => {
+                    checker.reject(line_number, problem)?;
+                    continue;
+                }
+            };
+            let Some(word) = columns.next() else {
+                checker.reject(line_number, format!("No word after {}", date))?;
+                continue;
+            };
+            if let Some((word, _)) = checker.check(line_number, word)? {
+                result.push((date, word));
+            }
+        }
+        Ok(result)
+    }
+
+    /// Normalized lines of a file, or of stdin for "-"; see `read_lines`.
+    fn read_lines_from_file(&self, path: &Path) -> Result<Vec<Line>, Error> {
+        if is_stdin_path(path) {
+            return self.read_lines(stdin().lock());
+        }
+        let reader = File::open(path)?;
+        self.read_lines(BufReader::new(reader))
+    }
+
+    /// Decompress the data if needed, and return its normalized lines, not yet validated.
+    fn read_lines<R: BufRead>(&self, mut reader: R) -> Result<Vec<Line>, Error> {
+        let header = reader.fill_buf()?;
+        if header.starts_with(GZIP_MAGIC) {
+            self.read_gzip_lines(reader)
+        } else if header.starts_with(ZSTD_MAGIC) {
+            self.read_zstd_lines(reader)
+        } else {
+            self.read_uncompressed_lines(reader)
+        }
+    }
+
+    #[cfg(feature = "gzip")]
+    fn read_gzip_lines<R: BufRead>(&self, reader: R) -> Result<Vec<Line>, Error> {
+        self.read_uncompressed_lines(BufReader::new(flate2::bufread::MultiGzDecoder::new(reader)))
+    }
+
+    #[cfg(not(feature = "gzip"))]
+    fn read_gzip_lines<R: BufRead>(&self, _reader: R) -> Result<Vec<Line>, Error> {
+        let msg = "List is gzip compressed; rebuild with the gzip feature to read it";
+        Err(Error::new(ErrorKind::InvalidData, msg))
+    }
+
+    #[cfg(feature = "zstd")]
+    fn read_zstd_lines<R: BufRead>(&self, reader: R) -> Result<Vec<Line>, Error> {
+        self.read_uncompressed_lines(BufReader::new(zstd::stream::read::Decoder::with_buffer(
+            reader,
+        )?))
+    }
+
+    #[cfg(not(feature = "zstd"))]
+    fn read_zstd_lines<R: BufRead>(&self, _reader: R) -> Result<Vec<Line>, Error> {
+        let msg = "List is zstd compressed; rebuild with the zstd feature to read it";
+        Err(Error::new(ErrorKind::InvalidData, msg))
+    }
+
+    fn read_uncompressed_lines<R: BufRead>(&self, mut reader: R) -> Result<Vec<Line>, Error> {
+        let mut result = Vec::new();
+        let mut buffer = String::new();
+        let mut line_number = 0;
+        while reader.read_line(&mut buffer)? > 0 {
+            line_number += 1;
+            if let Some(line) = self.normalize(&buffer) {
+                result.push((line_number, line));
+            }
+            buffer.clear();
+        }
+
+        Ok(result)
+    }
+
+    /// Clean up a line of a list, or return `None` if it should be skipped.
+    fn normalize(&self, line: &str) -> Option<String> {
+        let mut line = line;
+        let mut had_comment = false;
+        if self.skip_comments {
+            if let Some(index) = line.find('#') {
+                line = &line[..index];
+                had_comment = true;
+            }
+        }
+
+        line = line.trim_end();
+        if self.trim {
+            line = line.trim_start();
+        }
+
+        if line.is_empty() && (self.skip_blank || had_comment) {
+            return None;
+        }
+
+        let mut word = if self.lowercase {
+            line.to_lowercase()
+        } else {
+            line.to_string()
+        };
+        if self.fold_accents {
+            word = word
+                .nfd()
+                .filter(|c| !is_combining_mark(*c))
+                .nfc()
+                .collect();
+        } else if self.nfc {
+            word = word.nfc().collect();
+        }
+        Some(word)
+    }
+
+    fn parse_weighted_words(
+        &self,
+        lines: Vec<Line>,
+    ) -> Result<(Vec<(String, f64)>, LoadReport), Error> {
+        let mut checker = Checker::new(self);
+        let mut result = Vec::new();
+        for (line_number, line) in lines {
+            if let Some(entry) = checker.check(line_number, &line)? {
+                result.push(entry);
+            }
+        }
+        Ok((self.filter.apply(result), checker.report))
+    }
+
+    fn split_combined(&self, lines: Vec<Line>) -> Result<(CombinedList, LoadReport), Error> {
+        let mut checker = Checker::new(self);
+        let mut guessable = Vec::new();
+        let mut solutions = Vec::new();
+        let mut in_solutions = false;
+        let mut any_marked = false;
+
+        for (line_number, line) in lines {
+            if line.eq_ignore_ascii_case("[solutions]") {
+                in_solutions = true;
+                any_marked = true;
+                continue;
+            }
+            if line.eq_ignore_ascii_case("[guessable]") {
+                in_solutions = false;
+                continue;
+            }
+
+            let (word, frequency) = line.split_at(line.find('\t').unwrap_or(line.len()));
+            let (word, starred) = match word.strip_suffix('*') {
+                Some(word) => (word, true),
+                None => (word, false),
+            };
+            any_marked |= starred;
+
+            let unmarked = format!("{}{}", word, frequency);
+            if let Some(entry) = checker.check(line_number, &unmarked)? {
+                if starred || in_solutions {
+                    solutions.push(entry);
+                } else {
+                    guessable.push(entry);
+                }
+            }
+        }
+
+        if !any_marked {
+            return Ok(((Vec::new(), self.filter.apply(guessable)), checker.report));
+        }
+        let guessable = self.filter.apply(guessable);
+        Ok(((guessable, self.filter.apply(solutions)), checker.report))
+    }
+}
+
+/// Validates lines one at a time, keeping track of duplicates and skipped lines.
+struct Checker<'o> {
+    options: &'o LoadOptions,
+    seen: HashSet<String>,
+    report: LoadReport,
+}
+
+impl<'o> Checker<'o> {
+    fn new(options: &'o LoadOptions) -> Self {
+        Checker {
+            options,
+            seen: HashSet::new(),
+            report: LoadReport::default(),
+        }
+    }
+
+    /// The word and frequency on this line, or `None` if the line should be skipped.
+    fn check(&mut self, line_number: usize, line: &str) -> Result<Option<(String, f64)>, Error> {
+        match parse_weighted_word(line) {
+            Ok((word, weight)) => self.accept(line_number, word, weight),
+            Err(problem) => self.reject(line_number, problem),
+        }
+    }
+
+    /// Skip an invalid line, or fail if invalid lines aren't allowed.
+    fn reject(
+        &mut self,
+        line_number: usize,
+        problem: String,
+    ) -> Result<Option<(String, f64)>, Error> {
+        if self.options.skip_invalid {
+            self.report.invalid.push((line_number, problem));
+            return Ok(None);
+        }
+        let msg = format!("Line {}: {}", line_number, problem);
+        Err(Error::new(ErrorKind::InvalidData, msg))
+    }
+
+    /// Check a valid word for duplicates.
+    fn accept(
+        &mut self,
+        line_number: usize,
+        word: String,
+        weight: f64,
+    ) -> Result<Option<(String, f64)>, Error> {
+        if !self.seen.insert(word.clone()) {
+            self.report.duplicates.push((line_number, word.clone()));
+            if self.options.dedup {
+                return Ok(None);
+            }
+        }
+        Ok(Some((word, weight)))
+    }
+}
+
+#[cfg(feature = "json")]
+mod json {
+    use std::io::{Error, ErrorKind, Read};
+
+    use serde::Deserialize;
+
+    use super::{Checker, LoadOptions, LoadReport, WordList};
+    use crate::score::is_valid_word;
+
+    #[derive(Deserialize)]
+    struct JsonWordList {
+        language: Option<String>,
+        word_length: Option<usize>,
+        source: Option<String>,
+        license: Option<String>,
+        words: Vec<JsonWord>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum JsonWord {
+        Plain(String),
+        Detailed {
+            word: String,
+            solution: Option<bool>,
+            frequency: Option<f64>,
+        },
+    }
+
+    pub fn load<R: Read>(
+        options: &LoadOptions,
+        reader: R,
+    ) -> Result<(WordList, LoadReport), Error> {
+        let list: JsonWordList = serde_json::from_reader(reader)?;
+        if let Some(length) = list.word_length {
+            if length != 5 {
+                let msg = format!("{}-letter words aren't supported", length);
+                return Err(Error::new(ErrorKind::InvalidData, msg));
+            }
+        }
+
+        let mut checker = Checker::new(options);
+        let mut guessable = Vec::new();
+        let mut solutions = Vec::new();
+        let any_marked = list.words.iter().any(|w| {
+            matches!(
+                w,
+                JsonWord::Detailed {
+                    solution: Some(true),
+                    ..
+                }
+            )
+        });
+
+        for (index, entry) in list.words.into_iter().enumerate() {
+            let (word, solution, frequency) = match entry {
+                JsonWord::Plain(word) => (word, None, None),
+                JsonWord::Detailed {
+                    word,
+                    solution,
+                    frequency,
+                } => (word, solution, frequency),
+            };
+            let Some(word) = options.normalize(&word) else {
+                continue;
+            };
+
+            let weight = frequency.unwrap_or(1.0);
+            let checked = if !is_valid_word(&word) {
+                let problem = format!("Invalid word: {} (must be 5 lowercase letters)", word);
+                checker.reject(index + 1, problem)?
+            } else if !(weight >= 0.0 && weight.is_finite()) {
+                let problem = format!("Invalid frequency for {}: {}", word, weight);
+                checker.reject(index + 1, problem)?
+            } else {
+                checker.accept(index + 1, word, weight)?
+            };
+
+            if let Some(entry) = checked {
+                if !any_marked || solution == Some(true) {
+                    solutions.push(entry);
+                } else {
+                    guessable.push(entry);
+                }
+            }
+        }
+
+        let word_list = WordList {
+            language: list.language,
+            word_length: list.word_length,
+            source: list.source,
+            license: list.license,
+            guessable: options.filter.apply(guessable),
+            solutions: options.filter.apply(solutions),
+        };
+        Ok((word_list, checker.report))
+    }
+}
+
+/// Split a line into a word and its frequency, and validate both. Errors describe the problem.
+fn parse_weighted_word(line: &str) -> Result<(String, f64), String> {
+    let (word, weight) = match line.split_once('\t') {
+        Some((word, frequency)) => match frequency.trim().parse::<f64>() {
+            Ok(weight) if weight >= 0.0 && weight.is_finite() => (word, weight),
+            _ => return Err(format!("Invalid frequency for {}: {}", word, frequency)),
+        },
+        None => (line, 1.0),
+    };
+
+    if !is_valid_word(word) {
+        return Err(format!(
+            "Invalid word: {} (must be 5 lowercase letters)",
+            word
+        ));
+    }
+    Ok((word.to_string(), weight))
+}
+
+fn without_weights(entries: Vec<(String, f64)>) -> Vec<String> {
+    entries.into_iter().map(|(word, _)| word).collect()
+}
+
+/// Read a word list from a file (one word per line), with the default (strict) `LoadOptions`.
 pub fn load_list_from_file(path: &Path) -> Result<Vec<String>, Error> {
-    let reader = File::open(path)?;
-    let mut bufreader = BufReader::new(reader);
+    LoadOptions::default().load_file(path)
+}
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// Like `load_list`, but if the data starts with a gzip or zstd header, decompress it first.
+pub fn load_compressed_list<R: BufRead>(reader: R) -> Result<Vec<String>, Error> {
+    LoadOptions::default().load(reader)
+}
+
+/// Whether `load_list_from_file` would read this path from stdin.
+pub fn is_stdin_path(path: &Path) -> bool {
+    path == Path::new("-")
+}
+
+/// Read an uncompressed word list (one word per line) from any reader, with the default
+/// `LoadOptions`.
+pub fn load_list<R: BufRead>(reader: R) -> Result<Vec<String>, Error> {
+    let options = LoadOptions::default();
+    let lines = options.read_uncompressed_lines(reader)?;
+    let (entries, _) = options.parse_weighted_words(lines)?;
+    Ok(without_weights(entries))
+}
+
+/// The word lists built into the binary with the `bundled-lists` feature, as
+/// `(guessable, solutions)`, or `None` if this build doesn't include them. build.rs copies the
+/// lists from `lists/guessable.txt` and `lists/solutions.txt` (or `$WORDLE_SOLVER_LISTS_DIR`) at
+/// build time. They aren't checked in (see the README), so without them the feature builds with
+/// a warning, and there are no bundled lists.
+pub fn load_bundled() -> Option<(Vec<String>, Vec<String>)> {
+    #[cfg(bundled_lists)]
+    {
+        let guessable = include_str!(concat!(env!("OUT_DIR"), "/guessable.txt"));
+        let solutions = include_str!(concat!(env!("OUT_DIR"), "/solutions.txt"));
+        Some((
+            load_list(guessable.as_bytes()).expect("invalid bundled guessable list"),
+            load_list(solutions.as_bytes()).expect("invalid bundled solution list"),
+        ))
+    }
+
+    #[cfg(not(bundled_lists))]
+    None
+}
+
+/// Remove words that were answers before `cutoff` from a solution list. With no cutoff, remove
+/// every past answer.
+pub fn exclude_past_answers(
+    solutions: &mut Vec<String>,
+    past: &[PastAnswer],
+    cutoff: Option<Date>,
+) {
+    let used: HashSet<&str> = past
+        .iter()
+        .filter(|(date, _)| cutoff.is_none_or(|cutoff| *date < cutoff))
+        .map(|(_, word)| word.as_str())
+        .collect();
+    solutions.retain(|word| !used.contains(word.as_str()));
+}
 
-    let mut result = Vec::new();
-    let mut buffer = String::new();
-    while bufreader.read_line(&mut buffer)? > 0 {
-        let trimmed = buffer.trim_end();
-        if !trimmed.as_bytes().iter().all(u8::is_ascii_lowercase) || trimmed.len() != 5 {
-            let msg = format!("Invalid word: {} (must be 5 lowercase letters)", trimmed);
-            return Err(Error::new(ErrorKind::InvalidData, msg));
+/// Split a single list containing both possible solutions and other guessable words, using a
+/// heuristic given each word and its frequency (e.g. a frequency threshold) to decide which words
+/// are likely solutions. Returns `(guessable, solutions)`, as `LoadOptions::load_combined` does.
+pub fn split_merged_list<F>(words: Vec<(String, f64)>, is_solution: F) -> CombinedList
+where
+    F: Fn(&str, f64) -> bool,
+{
+    let (solutions, guessable) = words
+        .into_iter()
+        .partition(|(word, frequency)| is_solution(word, *frequency));
+    (guessable, solutions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_merged_list() {
+        let words = [("cigar", 1234.0), ("aahed", 2.0), ("rebut", 90.0)]
+            .map(|(word, frequency)| (word.to_string(), frequency))
+            .to_vec();
+        let (guessable, solutions) = split_merged_list(words, |_, frequency| frequency >= 50.0);
+        assert_eq!(guessable, [("aahed".to_string(), 2.0)]);
+        assert_eq!(
+            solutions,
+            [("cigar".to_string(), 1234.0), ("rebut".to_string(), 90.0)]
+        );
+    }
+
+    #[test]
+    fn test_load_compressed_list() {
+        let plain = "cigar\nrebut\n";
+        assert_eq!(
+            load_compressed_list(plain.as_bytes()).unwrap(),
+            ["cigar", "rebut"]
+        );
+
+        #[cfg(feature = "gzip")]
+        {
+            use std::io::Write;
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(plain.as_bytes()).unwrap();
+            let compressed = encoder.finish().unwrap();
+            assert_eq!(
+                load_compressed_list(compressed.as_slice()).unwrap(),
+                ["cigar", "rebut"]
+            );
         }
-        result.push(String::from(trimmed));
-        buffer.clear();
+
+        #[cfg(feature = "zstd")]
+        {
+            let compressed = zstd::encode_all(plain.as_bytes(), 0).unwrap();
+            assert_eq!(
+                load_compressed_list(compressed.as_slice()).unwrap(),
+                ["cigar", "rebut"]
+            );
+        }
+
+        #[cfg(not(feature = "gzip"))]
+        assert!(load_compressed_list(&[0x1f, 0x8b, 0x08][..]).is_err());
+    }
+
+    #[test]
+    fn test_load_options() {
+        let messy = "# Header\r\nCIGAR\r\n\n  rebut # note\n";
+        assert!(load_list(messy.as_bytes()).is_err());
+        assert_eq!(
+            LoadOptions::lenient().load(messy.as_bytes()).unwrap(),
+            ["cigar", "rebut"]
+        );
+
+        let options = LoadOptions {
+            skip_comments: true,
+            ..LoadOptions::default()
+        };
+        assert_eq!(
+            options.load("# Header\ncigar\r\n".as_bytes()).unwrap(),
+            ["cigar"]
+        );
+        assert!(options.load("cigar\n\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_load_weighted() {
+        let list = "cigar\t12.5\nrebut\n";
+        assert_eq!(
+            LoadOptions::default()
+                .load_weighted(list.as_bytes())
+                .unwrap(),
+            [("cigar".to_string(), 12.5), ("rebut".to_string(), 1.0)]
+        );
+        assert_eq!(load_list(list.as_bytes()).unwrap(), ["cigar", "rebut"]);
+        assert!(load_list("cigar\tlots\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_load_combined() {
+        let words = without_weights;
+        let options = LoadOptions::default();
+
+        let (guessable, solutions) = options
+            .load_combined("aahed\ncigar*\t3\nrebut*\nzymic\n".as_bytes())
+            .unwrap();
+        assert_eq!(words(guessable), ["aahed", "zymic"]);
+        assert_eq!(solutions[0], ("cigar".to_string(), 3.0));
+        assert_eq!(words(solutions), ["cigar", "rebut"]);
+
+        let list = "[solutions]\ncigar\nrebut\n[guessable]\naahed\n";
+        let (guessable, solutions) = options.load_combined(list.as_bytes()).unwrap();
+        assert_eq!(words(guessable), ["aahed"]);
+        assert_eq!(words(solutions), ["cigar", "rebut"]);
+
+        let (guessable, solutions) = options.load_combined("cigar\naahed\n".as_bytes()).unwrap();
+        assert!(guessable.is_empty());
+        assert_eq!(words(solutions), ["cigar", "aahed"]);
+    }
+
+    #[test]
+    fn test_load_report() {
+        let list = "cigar\nCIGAR\nrebut\ncigar\nrebut\n";
+        let error = load_list(list.as_bytes()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Line 2: Invalid word: CIGAR (must be 5 lowercase letters)"
+        );
+
+        let options = LoadOptions {
+            skip_invalid: true,
+            ..LoadOptions::default()
+        };
+        let lines = options.read_lines(list.as_bytes()).unwrap();
+        let (entries, report) = options.parse_weighted_words(lines).unwrap();
+        assert_eq!(without_weights(entries).len(), 4);
+        assert_eq!(report.invalid.len(), 1);
+        assert_eq!(report.invalid[0].0, 2);
+        assert_eq!(
+            report.duplicates,
+            [(4, "cigar".to_string()), (5, "rebut".to_string())]
+        );
+
+        let options = LoadOptions {
+            dedup: true,
+            ..options
+        };
+        assert_eq!(options.load(list.as_bytes()).unwrap(), ["cigar", "rebut"]);
+    }
+
+    #[test]
+    fn test_cache() {
+        let dir = std::env::temp_dir().join(format!("wordle-solver-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("list.txt");
+        fs::write(&path, "cigar\nrebut\t2\n").unwrap();
+
+        let options = LoadOptions {
+            cache: true,
+            ..LoadOptions::default()
+        };
+        let expected = [("cigar".to_string(), 1.0), ("rebut".to_string(), 2.0)];
+        assert_eq!(options.load_weighted_file(&path).unwrap(), expected);
+        assert!(list_cache::cache_path(&path).is_file());
+        assert_eq!(options.load_weighted_file(&path).unwrap(), expected);
+
+        // A changed file makes the cache stale.
+        fs::write(&path, "sissy\n").unwrap();
+        assert_eq!(options.load_file(&path).unwrap(), ["sissy"]);
+        fs::remove_dir_all(&dir).unwrap();
     }
 
-    Ok(result)
+    #[test]
+    fn test_date() {
+        let date: Date = "2024-02-29".parse().unwrap();
+        assert_eq!(date.to_string(), "2024-02-29");
+        assert!(date < "2024-03-01".parse().unwrap());
+        assert!("2023-02-29".parse::<Date>().is_err());
+        assert!("2024-13-01".parse::<Date>().is_err());
+        assert!("2024-1-01".parse::<Date>().is_err());
+        assert!("2024-01-01-01".parse::<Date>().is_err());
+    }
+
+    #[test]
+    fn test_past_answers() {
+        let csv = "date,word,number\n2021-06-19,cigar,0\n2021-06-20, rebut ,1\n";
+        let past = LoadOptions::default()
+            .load_past_answers(csv.as_bytes())
+            .unwrap();
+        assert_eq!(
+            past,
+            [
+                ("2021-06-19".parse().unwrap(), "cigar".to_string()),
+                ("2021-06-20".parse().unwrap(), "rebut".to_string()),
+            ]
+        );
+
+        let mut solutions = vec![
+            "cigar".to_string(),
+            "rebut".to_string(),
+            "sissy".to_string(),
+        ];
+        exclude_past_answers(&mut solutions, &past, Some("2021-06-20".parse().unwrap()));
+        assert_eq!(solutions, ["rebut", "sissy"]);
+        exclude_past_answers(&mut solutions, &past, None);
+        assert_eq!(solutions, ["sissy"]);
+
+        let error = LoadOptions::default()
+            .load_past_answers("2021-06-19\n".as_bytes())
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Line 1: No word after 2021-06-19");
+        let error = LoadOptions::default()
+            .load_past_answers("6/19/2021,cigar\n".as_bytes())
+            .unwrap_err();
+        assert!(error.to_string().starts_with("Line 1: Invalid date"));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_load_json() {
+        let json = r#"{
+            "language": "en",
+            "license": "CC0-1.0",
+            "words": ["aahed", {"word": "CIGAR", "solution": true, "frequency": 3}]
+        }"#;
+        let (list, report) = LoadOptions::lenient().load_json(json.as_bytes()).unwrap();
+        assert!(report.is_clean());
+        assert_eq!(list.language.as_deref(), Some("en"));
+        assert_eq!(list.source, None);
+        assert_eq!(list.guessable, [("aahed".to_string(), 1.0)]);
+        assert_eq!(list.solutions, [("cigar".to_string(), 3.0)]);
+
+        let error = LoadOptions::default()
+            .load_json(json.as_bytes())
+            .unwrap_err();
+        assert!(error.to_string().starts_with("Line 2: Invalid word: CIGAR"));
+
+        let json = r#"{"word_length": 6, "words": []}"#;
+        assert!(LoadOptions::default().load_json(json.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_unicode_normalization() {
+        // "señor" with a precomposed "ñ", then with "n" and a combining tilde.
+        let list = "se\u{f1}or\nsen\u{303}or\n";
+        assert!(load_list(list.as_bytes()).is_err());
+
+        let options = LoadOptions {
+            nfc: true,
+            ..LoadOptions::default()
+        };
+        assert_eq!(options.load(list.as_bytes()).unwrap(), ["señor", "señor"]);
+
+        let options = LoadOptions {
+            fold_accents: true,
+            ..LoadOptions::default()
+        };
+        assert_eq!(
+            options.load("señor\nétude\n".as_bytes()).unwrap(),
+            ["senor", "etude"]
+        );
+    }
 }
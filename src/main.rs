@@ -1,24 +1,233 @@
-use std::io::{stdin, stdout};
+use std::collections::HashMap;
+use std::io::{stderr, stdin, stdout, IsTerminal};
+use std::time::Duration;
 
-use argparse::{ArgumentParser, Parse, Store, StoreOption, StoreTrue};
+use argparse::{ArgumentParser, Collect, List, Parse, Store, StoreFalse, StoreOption, StoreTrue};
 
+pub mod challenges;
 pub mod histogram;
+pub mod list_diff;
+pub mod openers;
+pub mod trees;
+pub mod verify;
 
-use wordle_solver::loader::load_list_from_file;
-use wordle_solver::read_guess_interactively;
-use wordle_solver::score::{compute_score, read_score_interactively};
+use wordle_solver::default_thread_count;
+use wordle_solver::letters::LetterReport;
+use wordle_solver::lists;
+use wordle_solver::loader::{
+    exclude_past_answers, is_stdin_path, load_bundled, load_list_from_file, split_merged_list,
+    Date, LoadOptions, LoadReport,
+};
+use wordle_solver::score::{
+    clean_score_input, is_valid_word, DetailScore, ScoreFormat, ScoringRules,
+};
+use wordle_solver::session::GameSession;
 use wordle_solver::solver::{Solver, Strategy};
 
+/// `wordle-solver lists`: show or fetch the cached word lists.
+fn lists_command(args: Vec<String>) {
+    let mut action = "show".to_string();
+    let mut names: Vec<String> = Vec::new();
+
+    {
+        let mut parser = ArgumentParser::new();
+        parser.set_description(concat!(
+            "Manage cached word lists. Besides the built-in sources (wordle and nyt), sources ",
+            "are registered in sources.txt in the cache directory, one per line: a name, the ",
+            "guessable list URL and the solutions list URL. Downloading needs curl."
+        ));
+        parser.refer(&mut action).add_argument(
+            "action",
+            Store,
+            "show (default): list sources and cached lists; fetch: download lists",
+        );
+        parser.refer(&mut names).add_argument(
+            "names",
+            List,
+            "Which sources to fetch; all of them if omitted",
+        );
+        if let Err(code) = parser.parse(args, &mut stdout(), &mut stderr()) {
+            std::process::exit(code);
+        }
+    }
+
+    let dir = lists::cache_dir().unwrap_or_else(|| {
+        eprintln!("Can't find a cache directory; set WORDLE_SOLVER_CACHE");
+        std::process::exit(2);
+    });
+    let sources = lists::read_sources(&dir).unwrap_or_else(|e| {
+        eprintln!("{}: {}", lists::sources_path(&dir).display(), e);
+        std::process::exit(1);
+    });
+
+    match action.as_str() {
+        "show" => {
+            let cached = lists::cached_names(&dir).unwrap_or_default();
+            println!("Cache directory: {}", dir.display());
+            for source in sources.iter() {
+                let state = if cached.contains(&source.name) {
+                    "fetched"
+                } else {
+                    "not fetched"
+                };
+                println!("{} ({})", source.name, state);
+            }
+        }
+        "fetch" => {
+            for name in names.iter() {
+                if !sources.iter().any(|source| &source.name == name) {
+                    eprintln!("No source named {}; see `wordle-solver lists`", name);
+                    std::process::exit(2);
+                }
+            }
+            for source in sources.iter() {
+                if !names.is_empty() && !names.contains(&source.name) {
+                    continue;
+                }
+                println!("Fetching {}", source.name);
+                if let Err(e) = lists::fetch(&dir, source) {
+                    eprintln!("{}: {}", source.name, e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        _ => {
+            eprintln!("Unknown action {}; expected show or fetch", action);
+            std::process::exit(2);
+        }
+    }
+}
+
+/// `wordle-solver letters`: show letter statistics for the remaining possibilities.
+fn letters_command(args: Vec<String>) {
+    let mut solutions_path = String::new();
+    let mut moves: Vec<String> = Vec::new();
+    let mut scoring_rules = ScoringRules::Wordle;
+
+    {
+        let mut parser = ArgumentParser::new();
+        parser.set_description(concat!(
+            "Show how letters are distributed in each position of the possible solutions, and ",
+            "how much information each letter gives. The possibilities are narrowed down by the ",
+            "guesses and scores given after the solutions list, e.g. 'crane aapaa'."
+        ));
+        parser.refer(&mut solutions_path).required().add_argument(
+            "solutions",
+            Store,
+            "The solutions list",
+        );
+        parser.refer(&mut moves).add_argument(
+            "moves",
+            List,
+            "Guesses made so far, each followed by its score",
+        );
+        parser.refer(&mut scoring_rules).add_option(
+            &["--scoring-rules"],
+            Parse,
+            "How guesses are scored: wordle (default) or all-present",
+        );
+        if let Err(code) = parser.parse(args, &mut stdout(), &mut stderr()) {
+            std::process::exit(code);
+        }
+    }
+
+    if !moves.len().is_multiple_of(2) {
+        eprintln!("Expected pairs of a guess and a score");
+        std::process::exit(2);
+    }
+    let mut history: Vec<(String, DetailScore)> = Vec::new();
+    for pair in moves.chunks_exact(2) {
+        let guess = pair[0].to_lowercase();
+        if !is_valid_word(&guess) {
+            eprintln!("Invalid guess: {}", pair[0]);
+            std::process::exit(2);
+        }
+        match clean_score_input(&pair[1]).parse::<DetailScore>() {
+            Ok(score) => history.push((guess, score)),
+            Err(e) => {
+                eprintln!("{}: {}", pair[1], e);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    let solutions = load_list_from_file(solutions_path.as_ref()).unwrap_or_else(|e| {
+        eprintln!("{}: {}", solutions_path, e);
+        std::process::exit(1);
+    });
+    let possibilities: Vec<&str> = solutions
+        .iter()
+        .map(String::as_str)
+        .filter(|word| {
+            history
+                .iter()
+                .all(|(guess, score)| scoring_rules.is_consistent(guess, *score, word))
+        })
+        .collect();
+    if possibilities.is_empty() {
+        eprintln!("No solutions are consistent with those scores");
+        std::process::exit(1);
+    }
+
+    for line in LetterReport::new(&possibilities).lines() {
+        println!("{}", line);
+    }
+}
+
 fn main() {
-    let mut input = stdin().lock();
-    let mut output = stdout();
+    let mut args: Vec<String> = std::env::args().collect();
+    let subcommand: Option<fn(Vec<String>)> = match args.get(1).map(String::as_str) {
+        Some("lists") => Some(lists_command),
+        Some("letters") => Some(letters_command),
+        Some("diff-lists") => Some(list_diff::command),
+        Some("rank-openers") => Some(openers::command),
+        Some("eval-tree") => Some(trees::eval_command),
+        Some("export-tree") => Some(trees::export_command),
+        Some("prove") => Some(trees::prove_command),
+        Some("challenges") => Some(challenges::command),
+        Some("verify") => Some(verify::command),
+        Some("verify-scores") => Some(verify::scores_command),
+        _ => None,
+    };
+    if let Some(subcommand) = subcommand {
+        // Parse the subcommand's arguments as if it were its own program.
+        let program = args.remove(0);
+        args[0] = format!("{} {}", program, args[0]);
+        subcommand(args);
+        return;
+    }
 
     let mut do_histogram = false;
-    let mut thread_count = 8;
+    let mut report_options = histogram::ReportOptions {
+        progress: stderr().is_terminal(),
+        ..Default::default()
+    };
+    let mut thread_count = default_thread_count();
     let mut predetermined_solution: Option<String> = None;
     let mut enter_guesses = false;
     let mut hard_mode = false;
-    let mut strategy = Strategy::GroupSize;
+    let mut strategy: Option<Strategy> = None;
+    let mut configs: Vec<histogram::Configuration> = Vec::new();
+    let mut first_guess: Option<String> = None;
+    let mut guess_list_path: Option<String> = None;
+    let mut time_limit_ms: Option<u64> = None;
+    let mut explain = false;
+    let mut deterministic = false;
+    let mut max_guesses: Option<usize> = None;
+    let mut failure_threshold: Option<usize> = None;
+    let mut sample: Option<usize> = None;
+    let mut seed: Option<u64> = None;
+    let mut score_format = ScoreFormat::default_for_stdout();
+    let mut scoring_rules = ScoringRules::Wordle;
+    let mut lenient_lists = false;
+    let mut fold_accents = false;
+    let mut skip_invalid = false;
+    let mut filter_clauses: Vec<String> = Vec::new();
+    let mut lists_name: Option<String> = None;
+    let mut cache_lists = false;
+    let mut past_answers_path: Option<String> = None;
+    let mut used_before: Option<Date> = None;
+    let mut min_solution_frequency: Option<f64> = None;
 
     let mut guessable_path = "".to_string();
     let mut solutions_path = "".to_string();
@@ -46,45 +255,427 @@ fn main() {
             StoreTrue,
             concat!(
                 "Run both solver strategies on every possible solution; report number of guesses ",
-                "required for each. Ignores --self-score and --strategy."
+                "required for each. Ignores --self-score. With --strategy, only run that one."
             ),
         );
         parser.refer(&mut strategy).add_option(
             &["--strategy"],
-            Parse,
+            StoreOption,
             "Which solving strategy to use: groupcount or groupsize (default)",
         );
+        parser.refer(&mut guess_list_path).add_option(
+            &["--guess-list"],
+            StoreOption,
+            "Only suggest guesses from this list (remaining possibilities are always allowed)",
+        );
+        parser.refer(&mut time_limit_ms).add_option(
+            &["--time-limit"],
+            StoreOption,
+            "Spend at most this many milliseconds choosing each guess",
+        );
+        parser.refer(&mut explain).add_option(
+            &["--explain"],
+            StoreTrue,
+            "Explain how each suggested guess compares to the alternatives",
+        );
+        parser.refer(&mut deterministic).add_option(
+            &["--deterministic"],
+            StoreTrue,
+            concat!(
+                "Make guesses independent of word list order: consider words alphabetically and ",
+                "break ties in favor of the alphabetically first possible solution"
+            ),
+        );
+        parser.refer(&mut max_guesses).add_option(
+            &["--max-guesses"],
+            StoreOption,
+            "Number of guesses allowed before the game is lost (default: unlimited)",
+        );
+        parser.refer(&mut score_format).add_option(
+            &["--score-format"],
+            Parse,
+            concat!(
+                "How to print scores: apc, byg, emoji, or ansi (default: ansi on a terminal, ",
+                "otherwise apc)"
+            ),
+        );
+        parser.refer(&mut scoring_rules).add_option(
+            &["--scoring-rules"],
+            Parse,
+            concat!(
+                "How the game scores repeated letters: wordle (default), or all-present, where ",
+                "every copy of a letter that's elsewhere in the solution is present"
+            ),
+        );
+        parser.refer(&mut lenient_lists).add_option(
+            &["--lenient-lists"],
+            StoreTrue,
+            concat!(
+                "Accept uppercase words, blank lines, # comments, and decomposed accented ",
+                "letters in word lists"
+            ),
+        );
+        parser.refer(&mut fold_accents).add_option(
+            &["--fold-accents"],
+            StoreTrue,
+            "Remove accents from words in word lists, e.g. for games that ignore them",
+        );
+        parser.refer(&mut skip_invalid).add_option(
+            &["--skip-invalid"],
+            StoreTrue,
+            "Skip invalid and duplicate words in word lists instead of failing",
+        );
+        parser.refer(&mut configs).add_option(
+            &["--config"],
+            Collect,
+            concat!(
+                "A configuration for --solve-all to compare: a strategy, optionally followed by ",
+                ":hard and :opener=<word>, e.g. groupsize:hard:opener=crane. Can be repeated."
+            ),
+        );
+        parser.refer(&mut first_guess).add_option(
+            &["--first-guess"],
+            StoreOption,
+            "With --solve-all, start every game with this guess (unless a --config sets an opener)",
+        );
+        parser.refer(&mut report_options.format).add_option(
+            &["--output"],
+            Parse,
+            "Output format for --solve-all: text (default), csv, or json",
+        );
+        parser.refer(&mut report_options.breakdown).add_option(
+            &["--breakdown"],
+            StoreOption,
+            concat!(
+                "With --solve-all, also summarize results by a feature of the answers: ",
+                "first-letter, repeated-letters, vowels, or frequency"
+            ),
+        );
+        parser
+            .refer(&mut report_options.checkpoint_path)
+            .add_option(
+                &["--checkpoint"],
+                StoreOption,
+                "With --solve-all, save results to this file as they're done",
+            );
+        parser.refer(&mut report_options.resume).add_option(
+            &["--resume"],
+            StoreTrue,
+            "With --checkpoint, skip answers already saved in the checkpoint file",
+        );
+        parser.refer(&mut report_options.worst).add_option(
+            &["--worst"],
+            Store,
+            "With --solve-all, list this many answers that took the most guesses, and the guesses",
+        );
+        parser.refer(&mut sample).add_option(
+            &["--sample"],
+            StoreOption,
+            "With --solve-all, only play this many answers, chosen at random",
+        );
+        parser.refer(&mut seed).add_option(
+            &["--seed"],
+            StoreOption,
+            "With --sample, the random seed; the same seed chooses the same answers (default 0)",
+        );
+        parser.refer(&mut failure_threshold).add_option(
+            &["--failure-threshold"],
+            StoreOption,
+            concat!(
+                "With --solve-all, answers taking more than this many guesses (e.g. 6) are ",
+                "failures, which are listed and make the exit status non-zero (default: not ",
+                "checked)"
+            ),
+        );
+        parser.refer(&mut report_options.timing).add_option(
+            &["--timing"],
+            StoreTrue,
+            concat!(
+                "With --solve-all, time every turn, and report the slowest answers, the mean ",
+                "time of each turn, and guesses per second"
+            ),
+        );
+        parser.refer(&mut report_options.quiet).add_option(
+            &["--quiet"],
+            StoreTrue,
+            "With --solve-all, only print the summary, not each answer's result",
+        );
+        parser.refer(&mut report_options.progress).add_option(
+            &["--no-progress"],
+            StoreFalse,
+            "With --solve-all, don't show progress on stderr",
+        );
+        parser.refer(&mut report_options.trace_path).add_option(
+            &["--trace"],
+            StoreOption,
+            "With --solve-all, write every game's guesses and scores to this file, as JSON lines",
+        );
         parser.refer(&mut thread_count).add_option(
             &["--thread-count"],
             Parse,
-            "Thread count for --solve-all runs",
+            "Thread count for --solve-all runs (default: one per CPU)",
+        );
+        parser.refer(&mut filter_clauses).add_option(
+            &["--filter"],
+            Collect,
+            concat!(
+                "Leave words out of the lists: exclude=<regex>, exclude-letters=<letters>, ",
+                "no-plurals, only-plurals, or min-percentile=<0-100> (by frequency). Can be ",
+                "repeated."
+            ),
         );
-        parser.refer(&mut guessable_path).required().add_argument(
+        parser.refer(&mut cache_lists).add_option(
+            &["--cache-lists"],
+            StoreTrue,
+            "Save loaded lists in a binary cache next to them, to load faster next time",
+        );
+        parser.refer(&mut lists_name).add_option(
+            &["--lists"],
+            StoreOption,
+            "Use cached word lists with this name, from `wordle-solver lists fetch`",
+        );
+        parser.refer(&mut past_answers_path).add_option(
+            &["--past-answers"],
+            StoreOption,
+            concat!(
+                "CSV of past answers (date,word per line); they're removed from the solution ",
+                "list, since answers aren't reused"
+            ),
+        );
+        parser.refer(&mut used_before).add_option(
+            &["--used-before"],
+            StoreOption,
+            "With --past-answers, only remove answers used before this date (YYYY-MM-DD)",
+        );
+        parser.refer(&mut min_solution_frequency).add_option(
+            &["--min-solution-frequency"],
+            StoreOption,
+            concat!(
+                "With a single word list that doesn't mark possible solutions, only treat words ",
+                "with at least this frequency as possible solutions; the rest are only guessable"
+            ),
+        );
+        parser.refer(&mut guessable_path).add_argument(
             "guessable-path",
             Store,
-            "The path to the file of guessable strings",
+            concat!(
+                "The path to the file of guessable strings, or - for stdin. If omitted, use the ",
+                "bundled lists."
+            ),
         );
-        parser.refer(&mut solutions_path).required().add_argument(
+        parser.refer(&mut solutions_path).add_argument(
             "solutions-path",
             Store,
-            "The path to the file of possible solutions",
+            concat!(
+                "The path to the file of possible solutions, or - for stdin. If omitted, ",
+                "guessable-path must mark possible solutions with a * suffix or a [solutions] ",
+                "section; if it doesn't, every word is treated as a possible solution. A .json ",
+                "guessable-path is read as a JSON word list."
+            ),
         );
         parser.parse_args_or_exit();
     }
 
+    if let Some(ref name) = lists_name {
+        if !guessable_path.is_empty() {
+            eprintln!("Pass either --lists or list paths, not both");
+            std::process::exit(2);
+        }
+        let dir = lists::cache_dir().unwrap_or_else(|| {
+            eprintln!("Can't find a cache directory; set WORDLE_SOLVER_CACHE");
+            std::process::exit(2);
+        });
+        let (guessable, solutions) = lists::find_cached(&dir, name).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        });
+        guessable_path = guessable.to_string_lossy().into_owned();
+        solutions_path = solutions.to_string_lossy().into_owned();
+    }
+
+    let stdin_list_count = [
+        Some(&guessable_path),
+        Some(&solutions_path),
+        guess_list_path.as_ref(),
+        past_answers_path.as_ref(),
+    ]
+    .iter()
+    .flatten()
+    .filter(|path| is_stdin_path(path.as_ref()))
+    .count();
+    if stdin_list_count > 1 {
+        eprintln!("Only one word list can be read from stdin");
+        std::process::exit(2);
+    }
+    if stdin_list_count == 1 && !do_histogram && (predetermined_solution.is_none() || enter_guesses)
+    {
+        eprintln!("Can't read a word list from stdin while also reading scores or guesses from it");
+        std::process::exit(2);
+    }
+
+    let mut load_options = if lenient_lists {
+        LoadOptions::lenient()
+    } else {
+        LoadOptions::default()
+    };
+    load_options.fold_accents = fold_accents;
+    load_options.skip_invalid = skip_invalid;
+    load_options.dedup = skip_invalid;
+    load_options.cache = cache_lists;
+    for clause in filter_clauses.iter() {
+        if let Err(e) = load_options.filter.add_clause(clause) {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    }
+
+    let exit_on_error = |path: &str, e: std::io::Error| -> ! {
+        eprintln!("{}: {}", path, e);
+        std::process::exit(1);
+    };
+    let print_report = |path: &str, report: &LoadReport| {
+        if !report.is_clean() {
+            eprint!("{}: {}", path, report);
+        }
+    };
+
+    // Returns words with frequencies.
+    let load_list = |path: &str| {
+        let (entries, report) = load_options
+            .load_weighted_file_with_report(path.as_ref())
+            .unwrap_or_else(|e| exit_on_error(path, e));
+        print_report(path, &report);
+        entries
+    };
+    let without_weights = |entries: Vec<(String, f64)>| -> Vec<String> {
+        entries.into_iter().map(|(word, _)| word).collect()
+    };
+
+    // Frequencies in the solution list are used as priors.
+    let with_priors = |entries: Vec<(String, f64)>| {
+        let priors: HashMap<String, f64> = entries.iter().cloned().collect();
+        (without_weights(entries), Some(priors))
+    };
+
+    let single_list = !guessable_path.is_empty() && solutions_path.is_empty();
+    if min_solution_frequency.is_some() && (!single_list || guessable_path.ends_with(".json")) {
+        eprintln!("--min-solution-frequency needs a single word list");
+        std::process::exit(2);
+    }
+
+    let (guessable_list, (mut solution_list, priors)) = if guessable_path.is_empty() {
+        let (guessable, solutions) = load_bundled().unwrap_or_else(|| {
+            eprintln!("No word lists given, and this build doesn't include bundled lists");
+            std::process::exit(2);
+        });
+        (guessable, (solutions, None))
+    } else if solutions_path.is_empty() && guessable_path.ends_with(".json") {
+        let (list, report) = load_options
+            .load_json_file(guessable_path.as_ref())
+            .unwrap_or_else(|e| exit_on_error(&guessable_path, e));
+        print_report(&guessable_path, &report);
+        (without_weights(list.guessable), with_priors(list.solutions))
+    } else if solutions_path.is_empty() {
+        let ((mut guessable, mut solutions), report) = load_options
+            .load_combined_file_with_report(guessable_path.as_ref())
+            .unwrap_or_else(|e| exit_on_error(&guessable_path, e));
+        print_report(&guessable_path, &report);
+        if let Some(min) = min_solution_frequency {
+            if !guessable.is_empty() {
+                eprintln!("--min-solution-frequency needs a list that doesn't mark solutions");
+                std::process::exit(2);
+            }
+            (guessable, solutions) = split_merged_list(solutions, |_, frequency| frequency >= min);
+            if solutions.is_empty() {
+                eprintln!(
+                    "No word in {} has frequency {} or more",
+                    guessable_path, min
+                );
+                std::process::exit(2);
+            }
+        }
+        (without_weights(guessable), with_priors(solutions))
+    } else {
+        (
+            without_weights(load_list(&guessable_path)),
+            with_priors(load_list(&solutions_path)),
+        )
+    };
+
+    if let Some(ref path) = past_answers_path {
+        let past = load_options
+            .load_past_answers_file(path.as_ref())
+            .unwrap_or_else(|e| exit_on_error(path, e));
+        exclude_past_answers(&mut solution_list, &past, used_before);
+    } else if used_before.is_some() {
+        eprintln!("--used-before needs --past-answers");
+        std::process::exit(2);
+    }
+
     if do_histogram {
-        histogram::histogram(
+        if configs.is_empty() {
+            configs = match strategy {
+                Some(strategy) => vec![histogram::Configuration::new(strategy)],
+                None => histogram::default_configurations(),
+            };
+        } else if strategy.is_some() {
+            eprintln!("Pass either --strategy or --config, not both");
+            std::process::exit(2);
+        }
+        if let Some(ref word) = first_guess {
+            for config in configs.iter_mut() {
+                config.opener.get_or_insert_with(|| word.clone());
+            }
+        }
+        for opener in configs.iter().filter_map(|config| config.opener.as_ref()) {
+            if !guessable_list.contains(opener) && !solution_list.contains(opener) {
+                eprintln!("Opener '{}' isn't in the word lists", opener);
+                std::process::exit(2);
+            }
+        }
+
+        if sample.is_none() && seed.is_some() {
+            eprintln!("--seed needs --sample");
+            std::process::exit(2);
+        }
+        report_options.failure_threshold = failure_threshold.filter(|n| *n > 0);
+        if report_options.resume && report_options.checkpoint_path.is_none() {
+            eprintln!("--resume needs --checkpoint");
+            std::process::exit(2);
+        }
+        if report_options.breakdown == Some(histogram::Feature::Frequency) {
+            let frequencies = priors.as_ref().filter(|priors| {
+                let mut values = priors.values();
+                let first = values.next();
+                values.any(|value| Some(value) != first)
+            });
+            if frequencies.is_none() {
+                eprintln!("--breakdown frequency needs frequencies in the solution list");
+                std::process::exit(2);
+            }
+            report_options.frequencies = frequencies.cloned();
+        }
+
+        let failures = histogram::histogram(
             thread_count,
-            guessable_path.as_ref(),
-            solutions_path.as_ref(),
-            hard_mode,
+            guessable_list,
+            solution_list,
+            configs,
+            histogram::RunOptions {
+                hard_mode,
+                deterministic,
+                max_guesses,
+                rules: scoring_rules,
+                sample: sample.map(|count| (count, seed.unwrap_or(0))),
+            },
+            report_options,
         );
+        if failures > 0 {
+            std::process::exit(1);
+        }
         return;
     }
 
-    let guessable_list = load_list_from_file(guessable_path.as_ref()).unwrap();
-    let solution_list = load_list_from_file(solutions_path.as_ref()).unwrap();
-
     if let Some(ref solution) = predetermined_solution {
         if !solution_list.contains(solution) {
             println!("'{}' is not in the solution list!", solution);
@@ -92,32 +683,33 @@ fn main() {
         }
     }
 
-    let mut state = Solver::new(&guessable_list, &solution_list, hard_mode, true, strategy);
-
-    loop {
-        let guess = if enter_guesses {
-            println!("Recommended: {}", state.next_guess());
-            read_guess_interactively(&mut input, &mut output, &guessable_list, &solution_list)
-        } else {
-            let g = state.next_guess();
-            println!("Guess: {}", g);
-            g
-        };
-
-        let score = match predetermined_solution {
-            Some(ref solution) => {
-                let s = compute_score(guess, solution);
-                println!("Score: {}", s);
-                s
-            }
-            None => read_score_interactively(&mut input, &mut output),
-        };
+    let guess_list = guess_list_path.map(|path| without_weights(load_list(&path)));
 
-        if score.is_win() {
-            println!("Win!");
-            break;
-        }
+    let strategy = strategy.unwrap_or(Strategy::GroupSize);
+    let mut state = Solver::new(&guessable_list, &solution_list, hard_mode, true, strategy);
+    if let Some(ref list) = guess_list {
+        state.restrict_guesses(list);
+    }
+    if let Some(ref priors) = priors {
+        state.set_priors(priors);
+    }
+    state.set_deterministic(deterministic);
+    state.set_scoring_rules(scoring_rules);
+    if let Some(max) = max_guesses {
+        state.set_max_guesses(max);
+    }
 
-        state.respond_to_score(guess, score);
+    let input = stdin().lock();
+    let output = stdout();
+    let mut session = GameSession::new(state, &guessable_list, &solution_list, input, output);
+    session.set_enter_guesses(enter_guesses);
+    session.set_explain(explain);
+    session.set_score_format(score_format);
+    if let Some(ref solution) = predetermined_solution {
+        session.set_answer(solution);
+    }
+    if let Some(ms) = time_limit_ms {
+        session.set_time_limit(Duration::from_millis(ms));
     }
+    session.play();
 }
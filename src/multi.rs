@@ -0,0 +1,1425 @@
+//! Solving several Wordle boards at once with shared guesses, as in
+//! [Quordle](https://quordle.com) or [Duotrigordle](https://duotrigordle.com): every guess is
+//! played on every board that isn't solved yet, and each board scores it separately.
+//!
+//! Every board uses the same word lists. Like the rest of the crate, only 5-letter words are
+//! supported, so games that mix boards of different lengths can't be modeled yet.
+
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::constraints::Constraints;
+use crate::eval::{eval_guess, reduce_eval};
+use crate::score::{
+    clean_score_input, DetailScore, ScoreFormat, ScoreParseError, ScoringRules, NUM_POSSIBLE_SCORES,
+};
+use crate::solver::{Solver, Strategy};
+use crate::tree::DecisionTree;
+
+/// Boards with at most this many possibilities get an estimate from
+/// `MultiSolver::expected_guesses_remaining` that plays every possibility out. It's only fully
+/// exact for half as many; in between, it shades into `estimated_guesses`, so that the estimate
+/// doesn't jump when a board crosses the limit.
+const EXACT_ESTIMATE_LIMIT: usize = 50;
+
+/// Boards with at most this many possibilities have them listed by `MultiSolver::status_lines`.
+const LISTED_POSSIBILITIES: usize = 5;
+
+/// `estimated_guesses` is calibrated on Wordle's answer list: good strategies solve its 2315
+/// words in about 3.45 guesses on average.
+const CALIBRATION_ANSWERS: f64 = 2315.0;
+const CALIBRATION_GUESSES: f64 = 3.45;
+
+/// How many identical scores two boards need before `MultiSolver::duplicate_boards` reports
+/// them. After one guess, boards often score the same by chance.
+const MIN_DUPLICATE_GUESSES: usize = 2;
+
+/// How many guesses a thread takes at a time in `MultiSolver::next_guess`.
+const GUESSES_PER_CHUNK: usize = 256;
+
+/// How `MultiSolver::next_guess` weighs a guess's effect on each board into one decision.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Objective {
+    /// The strategy's eval, combined across boards: group counts are added, and the largest
+    /// group on any board is what counts for group size.
+    #[default]
+    Combined,
+
+    /// Make the board with the most possibilities expected to be left have as few as possible.
+    Worst,
+
+    /// Make the expected number of guesses left, added up over all boards, as small as possible.
+    /// Suits games with many boards, where every guess counts.
+    Total,
+
+    /// Solve boards as soon as possible: prefer the guess most likely to solve a board, then the
+    /// one expected to narrow the most boards down to one possibility.
+    Finish,
+}
+
+impl argparse::FromCommandLine for Objective {
+    fn from_argument(s: &str) -> Result<Self, String> {
+        match s {
+            "combined" => Ok(Self::Combined),
+            "worst" => Ok(Self::Worst),
+            "total" => Ok(Self::Total),
+            "finish" => Ok(Self::Finish),
+            _ => Err("objectives are 'combined', 'worst', 'total' and 'finish'".to_string()),
+        }
+    }
+}
+
+impl FromStr for Objective {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        argparse::FromCommandLine::from_argument(s)
+    }
+}
+
+impl Display for Objective {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Objective::Combined => write!(f, "combined"),
+            Objective::Worst => write!(f, "worst"),
+            Objective::Total => write!(f, "total"),
+            Objective::Finish => write!(f, "finish"),
+        }
+    }
+}
+
+/// A rough estimate of how many guesses it takes to solve a board with this many possibilities:
+/// guessing one of a few is right 1 time in `possibilities`, and a good guess splits a larger set
+/// as many ways as it takes to get from `CALIBRATION_ANSWERS` down to one in
+/// `CALIBRATION_GUESSES - 1` guesses (about 24).
+fn estimated_guesses(possibilities: usize) -> f64 {
+    let possibilities = possibilities as f64;
+    let log_split = CALIBRATION_ANSWERS.ln() / (CALIBRATION_GUESSES - 1.0);
+    (2.0 - 1.0 / possibilities).max(1.0 + possibilities.ln() / log_split)
+}
+
+/// How a guess splits a board's possibilities under `rules`: the sizes of the groups that would
+/// get each score, other than a win, and whether the guess could win.
+fn split(guess: &str, possibilities: &[&str], rules: ScoringRules) -> (Vec<usize>, bool) {
+    let mut groups = [0; NUM_POSSIBLE_SCORES as usize];
+    for score in rules.compute_scores(guess, possibilities) {
+        groups[score.as_num() as usize] += 1;
+    }
+    let win = DetailScore::win().index() as usize;
+    let wins = groups[win] > 0;
+    groups[win] = 0;
+    (groups.into_iter().filter(|size| *size > 0).collect(), wins)
+}
+
+/// Why pasted or labeled scores couldn't be used. See `parse_score_grid`, `parse_labeled_score`
+/// and `MultiSolver::respond_to_scores`.
+#[derive(Debug, PartialEq)]
+pub enum GridError {
+    /// The squares didn't divide into rows of 5. Contains the number of squares.
+    Incomplete(usize),
+
+    /// A row of squares wasn't a valid score.
+    Score(ScoreParseError),
+
+    /// There wasn't a score for every board that needed one, or for every board.
+    WrongCount {
+        got: usize,
+        needed: usize,
+        boards: usize,
+    },
+
+    /// In a sequence game, there were more scores than guesses needing them. Contains the
+    /// number needed.
+    TooMany(usize),
+
+    /// This board's score doesn't match any of its possibilities.
+    Inconsistent(usize),
+
+    /// A labeled score didn't start with a board number and a colon. Contains the line.
+    Label(String),
+
+    /// This board doesn't need a score for the guess: it's solved, doesn't exist, or in a
+    /// sequence game, doesn't show scores yet or needs one for an earlier guess first.
+    NotNeeded(usize),
+}
+
+impl Display for GridError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GridError::Incomplete(squares) => {
+                write!(f, "{} squares don't make rows of 5", squares)
+            }
+            GridError::Score(e) => write!(f, "{}", e),
+            GridError::WrongCount {
+                got,
+                needed,
+                boards,
+            } => write!(
+                f,
+                "Got {} scores; expected {} (one per unsolved board) or {} (one per board)",
+                got, needed, boards
+            ),
+            GridError::TooMany(needed) => write!(f, "Only {} scores were needed", needed),
+            GridError::Inconsistent(board) => {
+                write!(
+                    f,
+                    "Board {}'s score doesn't match any of its possibilities",
+                    board
+                )
+            }
+            GridError::Label(line) => {
+                write!(
+                    f,
+                    "'{}' isn't a board number and a score, like '3: acppc'",
+                    line
+                )
+            }
+            GridError::NotNeeded(board) => {
+                write!(f, "Board {} doesn't need a score for this guess", board)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GridError {}
+
+/// Parse a score labeled with its board, like "3: acppc". The score can be in any format that
+/// `DetailScore::from_str` accepts, and is cleaned up with `clean_score_input` first.
+pub fn parse_labeled_score(line: &str) -> Result<(usize, DetailScore), GridError> {
+    let label_error = || GridError::Label(line.trim().to_string());
+    let (board, score) = line.split_once(':').ok_or_else(label_error)?;
+    let board = board.trim().parse().map_err(|_| label_error())?;
+    let score = clean_score_input(score).parse().map_err(GridError::Score)?;
+    Ok((board, score))
+}
+
+/// Find the rows of emoji squares in pasted text, as Quordle or Duotrigordle show them, and parse
+/// each as a score (see `DetailScore::from_str`). Anything other than squares is ignored, so rows
+/// can be separated by spaces or newlines, or not at all.
+pub fn parse_score_grid(text: &str) -> Result<Vec<DetailScore>, GridError> {
+    let squares: Vec<char> = text
+        .chars()
+        .filter(|c| matches!(c, '⬛' | '⬜' | '🟩' | '🟨' | '🟧' | '🟦'))
+        .collect();
+    if !squares.len().is_multiple_of(5) {
+        return Err(GridError::Incomplete(squares.len()));
+    }
+    squares
+        .chunks(5)
+        .map(|row| {
+            row.iter()
+                .collect::<String>()
+                .parse()
+                .map_err(GridError::Score)
+        })
+        .collect()
+}
+
+/// A `Solver` per board, and guess selection that weighs a guess by how well it splits every
+/// unsolved board's possibilities together.
+///
+/// Play goes in rounds: call `next_guess` (or pick a guess of your own), then `next_round`, then
+/// `respond_to_score` for each board that `index_needing_response` asks for, until `all_done`.
+#[derive(Clone)]
+pub struct MultiSolver<'a> {
+    solvers: Vec<Solver<'a>>,
+    responded: Vec<bool>,
+    done: Vec<bool>,
+
+    /// For each board, how many guesses had been played when it joined without having them
+    /// replayed onto it. See `add_board`.
+    joined: Vec<usize>,
+
+    guessable_list: &'a [String],
+    solution_list: &'a [String],
+    strategy: Strategy,
+
+    /// Only guess words that use every hint revealed on the unsolved boards. See
+    /// `is_hard_mode_legal`.
+    hard_mode: bool,
+
+    /// The number of guesses allowed in total, if limited.
+    max_guesses: Option<usize>,
+
+    /// Only the first unsolved board shows scores. See `set_sequence`.
+    sequence: bool,
+
+    /// How many threads `next_guess` evaluates guesses on.
+    thread_count: usize,
+
+    /// How `next_guess` weighs the boards against each other.
+    objective: Objective,
+
+    /// How the game scores guesses, on every board.
+    rules: ScoringRules,
+
+    /// Count each group of `duplicate_boards` as one board in `next_guess`.
+    merge_duplicates: bool,
+}
+
+impl<'a> MultiSolver<'a> {
+    /// Boards are numbered from 0 to `count - 1`; every board starts with the whole solution list
+    /// as its possibilities.
+    pub fn new(
+        count: usize,
+        guessable_list: &'a [String],
+        solution_list: &'a [String],
+        strategy: Strategy,
+    ) -> MultiSolver<'a> {
+        let mut solvers = Vec::new();
+        for _ in 0..count {
+            solvers.push(Solver::new(
+                guessable_list,
+                solution_list,
+                false,
+                false,
+                strategy,
+            ));
+        }
+        MultiSolver {
+            solvers,
+            responded: vec![false; count],
+            done: vec![false; count],
+            joined: vec![0; count],
+            guessable_list,
+            solution_list,
+            strategy,
+            hard_mode: false,
+            max_guesses: None,
+            sequence: false,
+            thread_count: 1,
+            objective: Objective::default(),
+            rules: ScoringRules::Wordle,
+            merge_duplicates: false,
+        }
+    }
+
+    /// Add a board to a game in progress, for games that add boards as you go, and return its
+    /// number. With `replay`, the board shows scores for the guesses made so far, which
+    /// `index_needing_response` and `pending_guess` ask for before this round's; otherwise it
+    /// starts with the whole solution list and only shows scores from the next round on.
+    pub fn add_board(&mut self, replay: bool) -> usize {
+        let joined = if replay { 0 } else { self.guesses_used() };
+        let mut solver = Solver::new(
+            self.guessable_list,
+            self.solution_list,
+            false,
+            false,
+            self.strategy,
+        );
+        solver.set_scoring_rules(self.rules);
+        self.solvers.push(solver);
+        self.responded.push(true);
+        self.done.push(false);
+        self.joined.push(joined);
+        self.solvers.len() - 1
+    }
+
+    /// Use scoring rules other than Wordle's, on every board. Set this before responding to any
+    /// scores.
+    pub fn set_scoring_rules(&mut self, rules: ScoringRules) {
+        self.rules = rules;
+        for solver in self.solvers.iter_mut() {
+            solver.set_scoring_rules(rules);
+        }
+    }
+
+    /// Choose how `next_guess` weighs the boards against each other.
+    pub fn set_objective(&mut self, objective: Objective) {
+        self.objective = objective;
+    }
+
+    /// Weigh each group of `duplicate_boards` as one board in `next_guess`, rather than counting
+    /// the same possibilities several times. Suits games like Kilordle, where boards often share
+    /// an answer.
+    pub fn set_merge_duplicates(&mut self, merge_duplicates: bool) {
+        self.merge_duplicates = merge_duplicates;
+    }
+
+    /// Groups of unsolved boards, in board order, that have scored every guess so far the same,
+    /// so they may have the same answer. Boards are only grouped once they've scored at least two
+    /// guesses, since a single score often matches by chance.
+    pub fn duplicate_boards(&self) -> Vec<Vec<usize>> {
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        for board in self.unsolved_boards() {
+            if self.solvers[board].history().len() < MIN_DUPLICATE_GUESSES {
+                continue;
+            }
+            let same = |other: &usize| {
+                self.joined[*other] == self.joined[board]
+                    && self.solvers[*other].history() == self.solvers[board].history()
+            };
+            match groups.iter_mut().find(|group| same(&group[0])) {
+                Some(group) => group.push(board),
+                None => groups.push(vec![board]),
+            }
+        }
+        groups.retain(|group| group.len() > 1);
+        groups
+    }
+
+    /// Evaluate guesses on this many threads in `next_guess`. The result is the same as with one.
+    pub fn set_thread_count(&mut self, thread_count: usize) {
+        self.thread_count = thread_count.max(1);
+    }
+
+    /// Play a sequence game, like Quordle Sequence: only the first unsolved board shows scores,
+    /// and once it's solved, the next board shows the scores of every guess made so far. Guesses
+    /// are chosen for the board that shows scores.
+    pub fn set_sequence(&mut self, sequence: bool) {
+        self.sequence = sequence;
+    }
+
+    /// The boards that will show scores for the next guess: every unsolved board, or in a
+    /// sequence game, the first one.
+    pub fn active_boards(&self) -> Vec<usize> {
+        let unsolved = self.unsolved_boards();
+        if self.sequence {
+            unsolved.into_iter().take(1).collect()
+        } else {
+            unsolved
+        }
+    }
+
+    /// The guesses played so far, in order.
+    pub fn played_guesses(&self) -> Vec<&'a str> {
+        (0..self.guesses_used())
+            .map(|n| {
+                self.solvers
+                    .iter()
+                    .zip(self.joined.iter())
+                    .find_map(|(solver, joined)| solver.history().get(n.checked_sub(*joined)?))
+                    .unwrap()
+                    .0
+            })
+            .collect()
+    }
+
+    /// How many guesses `board` has been scored on or joined after.
+    fn board_guesses(&self, board: usize) -> usize {
+        self.joined[board] + self.solvers[board].history().len()
+    }
+
+    /// The guess that `board` needs a score for next, if it's one that another board has already
+    /// been scored on: this round's guess, or in a sequence game, the guesses made before the
+    /// board started showing scores. `None` if the board is waiting for a new guess.
+    pub fn pending_guess(&self, board: usize) -> Option<&'a str> {
+        self.played_guesses()
+            .get(self.board_guesses(board))
+            .copied()
+    }
+
+    /// Whether `board` needs a score before the round is complete.
+    fn needs_response(&self, board: usize) -> bool {
+        let behind = self.board_guesses(board) < self.guesses_used();
+        !self.done[board]
+            && (!self.responded[board] || behind)
+            && (!self.sequence || self.active_boards() == [board])
+    }
+
+    /// Limit the number of guesses allowed: 9 in Quordle, 13 in Octordle, 37 in Duotrigordle.
+    /// Once there are only as many guesses left as unsolved boards, every guess has to solve a
+    /// board, so only words that are still possible on some board are considered.
+    pub fn set_max_guesses(&mut self, max_guesses: usize) {
+        self.max_guesses = Some(max_guesses);
+    }
+
+    /// How many guesses have been played. A guess counts once it has been scored on some board.
+    pub fn guesses_used(&self) -> usize {
+        (0..self.solvers.len())
+            .map(|board| self.board_guesses(board))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// How many more guesses are allowed, if limited.
+    pub fn guesses_remaining(&self) -> Option<usize> {
+        self.max_guesses
+            .map(|max| max.saturating_sub(self.guesses_used()))
+    }
+
+    /// The boards that haven't been solved yet.
+    pub fn unsolved_boards(&self) -> Vec<usize> {
+        (0..self.done.len())
+            .filter(|idx| !self.done[*idx])
+            .collect()
+    }
+
+    /// The words that could still be `board`'s answer.
+    pub fn board_possibilities(&self, board: usize) -> &[&'a str] {
+        self.solvers[board].get_possibilities()
+    }
+
+    /// Whether the game is lost: there are unsolved boards and no guesses left.
+    pub fn is_lost(&self) -> bool {
+        !self.all_done() && self.guesses_remaining() == Some(0)
+    }
+
+    /// Play in hard mode, as sequence-style games require: every guess has to use the hints
+    /// revealed on all the boards so far. See `is_hard_mode_legal`.
+    pub fn set_hard_mode(&mut self, hard_mode: bool) {
+        self.hard_mode = hard_mode;
+    }
+
+    /// Whether `guess` is allowed: in hard mode, it has to keep every letter scored correct on
+    /// an unsolved board in its place, and contain every letter scored correct or present on an
+    /// unsolved board, as many times as any one score showed it.
+    ///
+    /// This is Wordle's own hard mode rule, not the single-board solver's stricter one of only
+    /// guessing words that could be the answer: once two boards score a guess differently, no
+    /// word could be the answer to both. Solved boards stop counting, since a board's hints can
+    /// rule out another board's answer for good.
+    pub fn is_hard_mode_legal(&self, guess: &str) -> bool {
+        self.hard_mode_hints()
+            .is_none_or(|hints| hints.matches(guess))
+    }
+
+    /// An estimate of how many more guesses `board` needs, if it were played on its own with the
+    /// solver's strategy and every possibility were equally likely. Small possibility sets are
+    /// played out exactly (unless the strategy gets stuck on them); larger ones are estimated from
+    /// their size, and ones in between get a mix of both (see `EXACT_ESTIMATE_LIMIT`). 0 for a
+    /// solved board.
+    pub fn expected_guesses_remaining(&self, board: usize) -> f64 {
+        if self.done[board] {
+            return 0.0;
+        }
+        let possibilities = self.solvers[board].get_possibilities().len();
+        let estimated = estimated_guesses(possibilities);
+        if possibilities > EXACT_ESTIMATE_LIMIT {
+            return estimated;
+        }
+
+        let mut solver = self.solvers[board].clone();
+        solver.set_verbose(false);
+        let Ok(tree) = DecisionTree::build(&solver) else {
+            return estimated;
+        };
+        let exact_limit = EXACT_ESTIMATE_LIMIT / 2;
+        let weight = possibilities.saturating_sub(exact_limit) as f64
+            / (EXACT_ESTIMATE_LIMIT - exact_limit) as f64;
+        tree.average_guesses() + weight * (estimated - tree.average_guesses())
+    }
+
+    /// A line per board, saying how it was solved, or how many possibilities it has left and
+    /// about how many more guesses it needs (see `expected_guesses_remaining`). Boards that are
+    /// nearly done have their possibilities listed, most likely first.
+    pub fn status_lines(&self) -> Vec<String> {
+        let active = self.active_boards();
+        (0..self.solvers.len())
+            .map(|board| {
+                let solver = &self.solvers[board];
+                if self.done[board] {
+                    let (answer, _) = solver.history()[solver.history().len() - 1];
+                    let guesses = self.board_guesses(board);
+                    return format!("Board {}: solved in {} ({})", board, guesses, answer);
+                }
+                if !active.contains(&board) {
+                    return format!("Board {}: not showing scores yet", board);
+                }
+
+                let count = solver.get_possibilities().len();
+                let mut line = format!(
+                    "Board {}: {} {}, about {:.1} more guesses",
+                    board,
+                    count,
+                    if count == 1 {
+                        "possibility"
+                    } else {
+                        "possibilities"
+                    },
+                    self.expected_guesses_remaining(board)
+                );
+                if count <= LISTED_POSSIBILITIES {
+                    let words: Vec<&str> = solver
+                        .possibility_probabilities()
+                        .iter()
+                        .map(|(word, _)| *word)
+                        .collect();
+                    line.push_str(&format!(": {}", words.join(", ")));
+                }
+                line
+            })
+            .collect()
+    }
+
+    /// A table of every board's scores so far, at most `width` columns wide: a header row of the
+    /// guesses, then a row per board with its number, a ✓ if it's solved, and its score for each
+    /// guess it was scored on. If the guesses don't all fit, the earliest ones are left out, and
+    /// a column of "…" shows where.
+    pub fn progress_lines(&self, format: ScoreFormat, width: usize) -> Vec<String> {
+        let played = self.played_guesses();
+        let cell = format.width();
+        let digits = self.solvers.len().saturating_sub(1).to_string().len();
+        let columns = width.saturating_sub(digits + 2) / (cell + 1);
+        let first = if played.len() <= columns {
+            0
+        } else {
+            let columns = width.saturating_sub(digits + 4) / (cell + 1);
+            played.len() - columns.max(1)
+        };
+        let ellipsis = if first > 0 { " …" } else { "" };
+
+        let mut header = format!("{:width$}{}", "", ellipsis, width = digits + 2);
+        for guess in played[first..].iter() {
+            header.push_str(&format!(" {:cell$}", guess));
+        }
+        let mut lines = vec![header.trim_end().to_string()];
+
+        for board in 0..self.solvers.len() {
+            let mark = if self.done[board] { '✓' } else { ' ' };
+            let mut line = format!("{:>digits$} {}{}", board, mark, ellipsis);
+            let history = self.solvers[board].history();
+            for round in first..played.len() {
+                match round
+                    .checked_sub(self.joined[board])
+                    .and_then(|n| history.get(n))
+                {
+                    Some((_, score)) => line.push_str(&format!(" {}", score.format(format))),
+                    None => line.push_str(&" ".repeat(cell + 1)),
+                }
+            }
+            lines.push(line.trim_end().to_string());
+        }
+        lines
+    }
+
+    /// Whether `guess` has already been played. In hard mode, `next_guess` only repeats a guess
+    /// when nothing else is allowed, which means the boards can't all be solved any more.
+    pub fn was_played(&self, guess: &str) -> bool {
+        self.solvers
+            .iter()
+            .any(|solver| solver.history().iter().any(|(played, _)| *played == guess))
+    }
+
+    /// The hints that hard mode guesses have to use, as constraints, or `None` if not in hard
+    /// mode.
+    fn hard_mode_hints(&self) -> Option<Constraints> {
+        if !self.hard_mode {
+            return None;
+        }
+        let mut hints = Constraints::default();
+        for (index, solver) in self.solvers.iter().enumerate() {
+            if !self.done[index] {
+                hints.merge(&Constraints::from_history(solver.history()));
+            }
+        }
+        // Only the letters that were found count, not the ones ruled out.
+        hints.excluded = Default::default();
+        hints.max_counts.clear();
+        if self.rules == ScoringRules::AllPresent {
+            // Every copy of a letter in the guess can be marked present for a single copy in the
+            // answer, so repeats don't show how many copies there are.
+            for count in hints.min_counts.values_mut() {
+                *count = (*count).min(1);
+            }
+        }
+        Some(hints)
+    }
+
+    /// The first board that hasn't been given a score for this round's guess, skipping solved
+    /// boards, and in a sequence game, boards that don't show scores yet. A sequence game board
+    /// that has just started showing them needs a score for each guess in turn; see
+    /// `pending_guess`. `None` means the round is complete.
+    pub fn index_needing_response(&self) -> Option<usize> {
+        (0..self.responded.len()).find(|idx| self.needs_response(*idx))
+    }
+
+    /// Whether every board has been solved.
+    pub fn all_done(&self) -> bool {
+        self.done.iter().all(|d| *d)
+    }
+
+    /// The guess to play on every unsolved board. If some board is down to one possibility,
+    /// that's the guess; otherwise it's the guess with the best combined eval across the
+    /// unsolved boards, preferring one that's still a possibility on as many boards as possible.
+    /// In a sequence game, only the board showing scores counts. With `set_merge_duplicates`,
+    /// boards that may share an answer count once. In hard mode, only allowed
+    /// guesses are considered, and with no guesses to spare (see `set_max_guesses`), only ones
+    /// that could solve a board.
+    ///
+    /// Panics if every board is solved.
+    pub fn next_guess(&self) -> &'a str {
+        let hints = self.hard_mode_hints();
+        let is_allowed = |guess: &str| hints.as_ref().is_none_or(|h| h.matches(guess));
+        let active = self.active_boards();
+        let is_possible = |guess: &str| {
+            active
+                .iter()
+                .any(|idx| self.solvers[*idx].get_possibilities().contains(&guess))
+        };
+        // In hard mode, there might not be an allowed guess that could solve a board, in which
+        // case there's nothing to lose by trying for information instead.
+        let must_solve = self
+            .guesses_remaining()
+            .is_some_and(|remaining| remaining <= self.unsolved_boards().len())
+            && active.iter().any(|idx| {
+                self.solvers[*idx]
+                    .get_possibilities()
+                    .iter()
+                    .any(|word| is_allowed(word))
+            });
+
+        for index in active.iter() {
+            let possibilities = self.solvers[*index].get_possibilities();
+            if possibilities.len() == 1 && is_allowed(possibilities[0]) {
+                return possibilities[0];
+            }
+        }
+
+        let candidates: Vec<&'a str> = self
+            .solution_list
+            .iter()
+            .chain(self.guessable_list.iter())
+            .map(String::as_str)
+            .filter(|guess| is_allowed(guess) && (!must_solve || is_possible(guess)))
+            .collect();
+        let evaluated: Vec<usize> = if self.merge_duplicates {
+            let duplicates = self.duplicate_boards();
+            active
+                .iter()
+                .copied()
+                .filter(|idx| !duplicates.iter().any(|group| group[1..].contains(idx)))
+                .collect()
+        } else {
+            active.clone()
+        };
+        let evals = self.evaluate(&candidates, &evaluated);
+
+        let mut best_eval = (f64::MIN, f64::MIN);
+        let mut best_guesses: Vec<&str> = Vec::new();
+
+        for (guess, eval) in candidates.into_iter().zip(evals) {
+            if eval > best_eval {
+                best_eval = eval;
+                best_guesses.clear();
+            }
+            if eval == best_eval {
+                best_guesses.push(guess);
+            }
+        }
+
+        best_guesses
+            .iter()
+            .max_by_key(|guess| {
+                active
+                    .iter()
+                    .filter(|idx| self.solvers[**idx].get_possibilities().contains(guess))
+                    .count()
+            })
+            .unwrap()
+    }
+
+    /// The strategy's eval of a guess over the `boards`' possibilities combined.
+    fn combined_eval(&self, guess: &str, boards: &[usize]) -> (f64, f64) {
+        let reduced = boards
+            .iter()
+            .map(|index| &self.solvers[*index])
+            .map(|solver| eval_guess(guess, solver.get_possibilities(), self.rules))
+            .reduce(reduce_eval)
+            .unwrap();
+        if self.strategy == Strategy::GroupCount {
+            (reduced.count as f64, reduced.size as f64)
+        } else {
+            (reduced.size as f64, reduced.count as f64)
+        }
+    }
+
+    /// How good a guess is for the `boards`, according to the objective. Higher is better.
+    fn objective_eval(&self, guess: &str, boards: &[usize]) -> (f64, f64) {
+        let splits = boards.iter().map(|index| {
+            let possibilities = self.solvers[*index].get_possibilities();
+            let (groups, _) = split(guess, possibilities, self.rules);
+            (groups, possibilities.len() as f64)
+        });
+        match self.objective {
+            Objective::Combined => self.combined_eval(guess, boards),
+            Objective::Worst => {
+                // The expected number of possibilities left on each board.
+                let expected: Vec<f64> = splits
+                    .map(|(groups, total)| {
+                        groups.iter().map(|size| (size * size) as f64).sum::<f64>() / total
+                    })
+                    .collect();
+                let worst = expected.iter().copied().fold(0.0, f64::max);
+                (-worst, -expected.iter().sum::<f64>())
+            }
+            Objective::Total => {
+                // A won board needs no more guesses.
+                let total: f64 = splits
+                    .map(|(groups, total)| {
+                        groups
+                            .iter()
+                            .map(|size| *size as f64 / total * estimated_guesses(*size))
+                            .sum::<f64>()
+                    })
+                    .sum();
+                (-total, 0.0)
+            }
+            Objective::Finish => {
+                let (mut solved, mut narrowed) = (0.0, 0.0);
+                for index in boards {
+                    let possibilities = self.solvers[*index].get_possibilities();
+                    let (groups, wins) = split(guess, possibilities, self.rules);
+                    let singles = groups.iter().filter(|size| **size == 1).count();
+                    solved += wins as usize as f64 / possibilities.len() as f64;
+                    narrowed += singles as f64 / possibilities.len() as f64;
+                }
+                (solved, narrowed)
+            }
+        }
+    }
+
+    /// `objective_eval` for each guess, using `thread_count` threads.
+    fn evaluate(&self, guesses: &[&str], boards: &[usize]) -> Vec<(f64, f64)> {
+        let eval = |guess: &str| self.objective_eval(guess, boards);
+        if self.thread_count == 1 {
+            return guesses.iter().map(|guess| eval(guess)).collect();
+        }
+
+        let next_chunk = AtomicUsize::new(0);
+        let mut chunks: Vec<(usize, Vec<(f64, f64)>)> = std::thread::scope(|scope| {
+            let threads: Vec<_> = (0..self.thread_count)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut chunks = Vec::new();
+                        loop {
+                            let index = next_chunk.fetch_add(1, Ordering::Relaxed);
+                            let Some(chunk) = guesses.chunks(GUESSES_PER_CHUNK).nth(index) else {
+                                break;
+                            };
+                            chunks.push((index, chunk.iter().map(|guess| eval(guess)).collect()));
+                        }
+                        chunks
+                    })
+                })
+                .collect();
+            threads
+                .into_iter()
+                .flat_map(|thread| thread.join().unwrap())
+                .collect()
+        });
+        chunks.sort_by_key(|(index, _)| *index);
+        chunks.into_iter().flat_map(|(_, evals)| evals).collect()
+    }
+
+    /// Record this round's scores for several boards at once: either one for each board that
+    /// still needs one, in board order, or one for every board, in which case the scores for
+    /// solved boards are ignored. In a sequence game, or when a board added with `add_board` is
+    /// catching up, the scores are taken in the order `index_needing_response` would ask for
+    /// them, for the guesses `pending_guess` gives. Nothing is recorded unless every score is
+    /// usable.
+    pub fn respond_to_scores(
+        &mut self,
+        guess: &'a str,
+        scores: &[DetailScore],
+    ) -> Result<(), GridError> {
+        let needed: Vec<usize> = (0..self.solvers.len())
+            .filter(|idx| self.needs_response(*idx))
+            .collect();
+        let catching_up = needed.iter().any(|idx| {
+            self.pending_guess(*idx)
+                .is_some_and(|pending| pending != guess)
+        });
+        if self.sequence || catching_up {
+            let mut trial = self.clone();
+            for (count, score) in scores.iter().enumerate() {
+                let Some(board) = trial.index_needing_response() else {
+                    return Err(GridError::TooMany(count));
+                };
+                let guess = trial.pending_guess(board).unwrap_or(guess);
+                trial.check_score(board, guess, *score)?;
+                trial.respond_to_score(board, guess, *score);
+            }
+            *self = trial;
+            return Ok(());
+        }
+
+        let responses: Vec<(usize, DetailScore)> = if scores.len() == needed.len() {
+            needed.into_iter().zip(scores.iter().copied()).collect()
+        } else if scores.len() == self.solvers.len() {
+            needed.into_iter().map(|idx| (idx, scores[idx])).collect()
+        } else {
+            return Err(GridError::WrongCount {
+                got: scores.len(),
+                needed: needed.len(),
+                boards: self.solvers.len(),
+            });
+        };
+
+        for (board, score) in responses.iter() {
+            self.check_score(*board, guess, *score)?;
+        }
+        for (board, score) in responses {
+            self.respond_to_score(board, guess, score);
+        }
+        Ok(())
+    }
+
+    /// The boards that still need a score for `guess`, this round's guess, in board order. Boards
+    /// in a sequence game that are catching up on earlier guesses aren't included.
+    pub fn boards_needing_score(&self, guess: &str) -> Vec<usize> {
+        (0..self.solvers.len())
+            .filter(|board| {
+                self.needs_response(*board)
+                    && self
+                        .pending_guess(*board)
+                        .is_none_or(|pending| pending == guess)
+            })
+            .collect()
+    }
+
+    /// Record this round's scores for some boards, given with their board numbers in any order,
+    /// as `parse_labeled_score` reads them. Boards left out still need scores afterwards. Nothing
+    /// is recorded unless every score is for a board in `boards_needing_score` and is usable.
+    pub fn respond_to_labeled_scores(
+        &mut self,
+        guess: &'a str,
+        scores: &[(usize, DetailScore)],
+    ) -> Result<(), GridError> {
+        let needed = self.boards_needing_score(guess);
+        for (count, (board, score)) in scores.iter().enumerate() {
+            if !needed.contains(board) || scores[..count].iter().any(|(b, _)| b == board) {
+                return Err(GridError::NotNeeded(*board));
+            }
+            self.check_score(*board, guess, *score)?;
+        }
+        for (board, score) in scores {
+            self.respond_to_score(*board, guess, *score);
+        }
+        Ok(())
+    }
+
+    /// Check that some possibility for `board` would give `guess` this score.
+    fn check_score(&self, board: usize, guess: &str, score: DetailScore) -> Result<(), GridError> {
+        let rules = self.solvers[board].scoring_rules();
+        if self.solvers[board]
+            .get_possibilities()
+            .iter()
+            .any(|word| rules.is_consistent(guess, score, word))
+        {
+            Ok(())
+        } else {
+            Err(GridError::Inconsistent(board))
+        }
+    }
+
+    /// Record `board`'s score for this round's guess, or for its `pending_guess` if it's catching
+    /// up on earlier ones. A winning score marks the board as solved.
+    ///
+    /// Panics if the board doesn't need a score.
+    pub fn respond_to_score(&mut self, board: usize, guess: &'a str, score: DetailScore) {
+        assert!(self.needs_response(board));
+        self.solvers[board].respond_to_score(guess, score);
+        self.responded[board] = true;
+        if score.is_win() {
+            self.done[board] = true;
+        }
+    }
+
+    /// Start a new round, so that every unsolved board needs a score again.
+    pub fn next_round(&mut self) {
+        self.responded = vec![false; self.responded.len()];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::score::compute_score;
+
+    fn words(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    /// Play a game against `answers`, one per board, and return the guesses made.
+    fn play<'a>(solver: &mut MultiSolver<'a>, answers: &[&str]) -> Vec<&'a str> {
+        let mut guesses = Vec::new();
+        while !solver.all_done() {
+            assert!(guesses.len() < 20, "not solved: {:?}", guesses);
+            let guess = solver.next_guess();
+            guesses.push(guess);
+            solver.next_round();
+            while let Some(board) = solver.index_needing_response() {
+                solver.respond_to_score(board, guess, compute_score(guess, answers[board]));
+            }
+        }
+        guesses
+    }
+
+    #[test]
+    fn test_solve() {
+        let guessable = words(&["blimp", "compt", "aahed"]);
+        let solutions = words(&[
+            "cigar", "rebut", "sissy", "humph", "awake", "blush", "focal", "evade", "naval",
+            "serve", "heath", "dwarf", "model", "karma", "stink", "grade", "quiet", "bench",
+        ]);
+        let answers = ["humph", "naval", "quiet", "humph"];
+
+        for strategy in [Strategy::GroupSize, Strategy::GroupCount] {
+            let mut solver = MultiSolver::new(answers.len(), &guessable, &solutions, strategy);
+            let guesses = play(&mut solver, &answers);
+            for answer in answers {
+                assert!(guesses.contains(&answer));
+            }
+        }
+    }
+
+    #[test]
+    fn test_rounds() {
+        let solutions = words(&["cigar", "rebut", "sissy", "humph"]);
+        let mut solver = MultiSolver::new(2, &[], &solutions, Strategy::GroupSize);
+        assert_eq!(solver.index_needing_response(), Some(0));
+
+        solver.respond_to_score(0, "cigar", compute_score("cigar", "cigar"));
+        solver.respond_to_score(1, "cigar", compute_score("cigar", "sissy"));
+        assert_eq!(solver.index_needing_response(), None);
+        assert!(!solver.all_done());
+
+        // The solved board doesn't need a score any more.
+        solver.next_round();
+        assert_eq!(solver.index_needing_response(), Some(1));
+        assert_eq!(solver.next_guess(), "sissy");
+        solver.respond_to_score(1, "sissy", compute_score("sissy", "sissy"));
+        assert!(solver.all_done());
+    }
+
+    #[test]
+    fn test_hard_mode() {
+        let guessable = words(&["blimp", "compt", "aahed"]);
+        let solutions = words(&[
+            "cigar", "rebut", "sissy", "humph", "awake", "blush", "focal", "evade", "naval",
+            "serve", "heath", "dwarf", "model", "karma", "stink", "grade", "quiet", "bench",
+        ]);
+        let answers = ["humph", "quiet"];
+
+        let mut solver =
+            MultiSolver::new(answers.len(), &guessable, &solutions, Strategy::GroupSize);
+        solver.set_hard_mode(true);
+        let guesses = play(&mut solver, &answers);
+        for answer in answers {
+            assert!(guesses.contains(&answer));
+        }
+
+        // "blush" scores "h" correct against "humph", and "u" present against both.
+        let mut solver = MultiSolver::new(2, &guessable, &solutions, Strategy::GroupSize);
+        solver.respond_to_score(0, "blush", compute_score("blush", "humph"));
+        solver.respond_to_score(1, "blush", compute_score("blush", "quiet"));
+        assert!(solver.is_hard_mode_legal("cigar"));
+        solver.set_hard_mode(true);
+        assert!(!solver.is_hard_mode_legal("cigar"));
+        assert!(!solver.is_hard_mode_legal("quiet"));
+        assert!(solver.is_hard_mode_legal("hutch"));
+        assert!(solver.is_hard_mode_legal(solver.next_guess()));
+
+        // Once "humph" is solved, its "h" doesn't have to be used any more.
+        solver.next_round();
+        solver.respond_to_score(0, "humph", compute_score("humph", "humph"));
+        assert!(solver.is_hard_mode_legal("quiet"));
+
+        // "serve" finds an "e" in second place and a "v", and nothing else has both.
+        let answers = ["humph", "naval", "quiet", "bench"];
+        let mut solver =
+            MultiSolver::new(answers.len(), &guessable, &solutions, Strategy::GroupSize);
+        solver.set_hard_mode(true);
+        for (board, answer) in answers.iter().enumerate() {
+            solver.respond_to_score(board, "serve", compute_score("serve", answer));
+        }
+        assert_eq!(solver.next_guess(), "serve");
+        assert!(solver.was_played("serve"));
+    }
+
+    #[test]
+    fn test_max_guesses() {
+        let guessable = words(&["blimp", "compt", "aahed"]);
+        let solutions = words(&[
+            "cigar", "rebut", "sissy", "humph", "awake", "blush", "focal", "evade", "naval",
+            "serve", "heath", "dwarf", "model", "karma", "stink", "grade", "quiet", "bench",
+        ]);
+        let mut solver = MultiSolver::new(2, &guessable, &solutions, Strategy::GroupSize);
+        solver.set_max_guesses(3);
+        assert_eq!(solver.guesses_remaining(), Some(3));
+
+        solver.respond_to_score(0, "compt", compute_score("compt", "humph"));
+        solver.respond_to_score(1, "compt", compute_score("compt", "quiet"));
+        assert_eq!(solver.guesses_used(), 1);
+        assert_eq!(solver.guesses_remaining(), Some(2));
+
+        // With a guess per board left, every guess has to be able to solve one.
+        let guess = solver.next_guess();
+        assert!([0, 1]
+            .iter()
+            .any(|idx| solver.solvers[*idx].get_possibilities().contains(&guess)));
+
+        solver.next_round();
+        let answers = ["humph", "quiet"];
+        for (board, answer) in answers.iter().enumerate() {
+            solver.respond_to_score(board, guess, compute_score(guess, answer));
+        }
+        solver.next_round();
+        let board = solver.index_needing_response().unwrap();
+        solver.respond_to_score(board, "aahed", compute_score("aahed", answers[board]));
+        assert_eq!(solver.guesses_remaining(), Some(0));
+        assert!(solver.is_lost());
+    }
+
+    #[test]
+    fn test_expected_guesses_remaining() {
+        let solutions = words(&["cigar", "rebut", "sissy", "humph", "awake", "blush"]);
+        let mut solver = MultiSolver::new(2, &[], &solutions, Strategy::GroupSize);
+        solver.respond_to_score(0, "humph", compute_score("humph", "blush"));
+        solver.respond_to_score(1, "humph", compute_score("humph", "humph"));
+
+        // Board 0 is down to "blush", and board 1 is solved.
+        assert_eq!(solver.expected_guesses_remaining(0), 1.0);
+        assert_eq!(solver.expected_guesses_remaining(1), 0.0);
+
+        let fresh = MultiSolver::new(1, &[], &solutions, Strategy::GroupSize);
+        let expected = fresh.expected_guesses_remaining(0);
+        assert!(expected > 1.0 && expected < 3.0);
+
+        // At the limit, the estimate is all `estimated_guesses`, and at half of it, all exact.
+        let letters = "abcdefghij";
+        let many: Vec<String> = (0..EXACT_ESTIMATE_LIMIT)
+            .map(|i| {
+                let first = letters.chars().nth(i / 10).unwrap();
+                let second = letters.chars().nth(i % 10).unwrap();
+                format!("{}{}xyz", first, second)
+            })
+            .collect();
+        let solver = MultiSolver::new(1, &[], &many, Strategy::GroupSize);
+        let estimate = solver.expected_guesses_remaining(0);
+        assert!((estimate - estimated_guesses(EXACT_ESTIMATE_LIMIT)).abs() < 1e-9);
+
+        let half = &many[..EXACT_ESTIMATE_LIMIT / 2];
+        let solver = MultiSolver::new(1, &[], half, Strategy::GroupSize);
+        let tree = DecisionTree::build(&Solver::new(&[], half, false, false, Strategy::GroupSize));
+        assert_eq!(
+            solver.expected_guesses_remaining(0),
+            tree.unwrap().average_guesses()
+        );
+    }
+
+    #[test]
+    fn test_parse_score_grid() {
+        let quordle = "🟨⬜⬜⬜⬜ 🟩🟩🟩🟩🟩\n⬛️🟨⬛️⬛️🟩 ⬛️⬛️⬛️⬛️⬛️\n";
+        let scores = parse_score_grid(quordle).unwrap();
+        let expected: Vec<DetailScore> = ["paaaa", "ccccc", "apaac", "aaaaa"]
+            .iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+        assert_eq!(scores, expected);
+        assert_eq!(
+            parse_score_grid("🟩🟩🟩🟩🟩🟩"),
+            Err(GridError::Incomplete(6))
+        );
+        assert_eq!(parse_score_grid("no squares"), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_respond_to_scores() {
+        let solutions = words(&["cigar", "rebut", "sissy", "humph", "awake", "blush"]);
+        let score = |answer| compute_score("humph", answer);
+        let mut solver = MultiSolver::new(3, &[], &solutions, Strategy::GroupSize);
+        solver
+            .respond_to_scores("humph", &[score("blush"), score("humph"), score("cigar")])
+            .unwrap();
+        assert_eq!(solver.board_possibilities(0), ["blush"]);
+        assert_eq!(solver.unsolved_boards(), [0, 2]);
+
+        // One score per unsolved board, or one for every board.
+        solver.next_round();
+        let wrong = solver.respond_to_scores("blush", &[score("blush")]);
+        assert_eq!(
+            wrong,
+            Err(GridError::WrongCount {
+                got: 1,
+                needed: 2,
+                boards: 3
+            })
+        );
+        let all = [
+            compute_score("blush", "blush"),
+            compute_score("blush", "humph"),
+            compute_score("blush", "cigar"),
+        ];
+        solver.respond_to_scores("blush", &all).unwrap();
+        assert_eq!(solver.unsolved_boards(), [2]);
+
+        // A score that no possibility would get is rejected, and nothing is recorded.
+        solver.next_round();
+        let impossible = "ccccp".parse().unwrap();
+        assert_eq!(
+            solver.respond_to_scores("awake", &[impossible]),
+            Err(GridError::Inconsistent(2))
+        );
+        assert_eq!(solver.index_needing_response(), Some(2));
+    }
+
+    #[test]
+    fn test_labeled_scores() {
+        assert_eq!(
+            parse_labeled_score(" 12: A.P.C.C.A"),
+            Ok((12, "apcca".parse().unwrap()))
+        );
+        assert_eq!(
+            parse_labeled_score("🟩🟨⬛⬛🟩"),
+            Err(GridError::Label("🟩🟨⬛⬛🟩".to_string()))
+        );
+        assert!(matches!(
+            parse_labeled_score("3: acp"),
+            Err(GridError::Score(_))
+        ));
+
+        let solutions = words(&["cigar", "rebut", "sissy", "humph", "awake", "blush"]);
+        let score = |answer| compute_score("humph", answer);
+        let mut solver = MultiSolver::new(3, &[], &solutions, Strategy::GroupSize);
+        solver
+            .respond_to_labeled_scores("humph", &[(2, score("cigar")), (0, score("humph"))])
+            .unwrap();
+        assert_eq!(solver.boards_needing_score("humph"), [1]);
+        assert_eq!(solver.unsolved_boards(), [1, 2]);
+
+        // Board 0 is solved, and board 3 doesn't exist.
+        for board in [0, 3] {
+            assert_eq!(
+                solver.respond_to_labeled_scores("humph", &[(board, score("blush"))]),
+                Err(GridError::NotNeeded(board))
+            );
+        }
+        solver
+            .respond_to_labeled_scores("humph", &[(1, score("blush"))])
+            .unwrap();
+        assert_eq!(solver.index_needing_response(), None);
+    }
+
+    #[test]
+    fn test_scoring_rules() {
+        let solutions = words(&["cigar", "rebut", "sissy", "humph", "awake", "blush"]);
+        let mut solver = MultiSolver::new(2, &[], &solutions, Strategy::GroupSize);
+        // Under Wordle's rules, "blush" has only one "s" to mark.
+        let score = ScoringRules::AllPresent.compute_score("sissy", "blush");
+        assert_eq!(
+            solver.respond_to_scores("sissy", &[score, score]),
+            Err(GridError::Inconsistent(0))
+        );
+
+        solver.set_scoring_rules(ScoringRules::AllPresent);
+        solver.respond_to_scores("sissy", &[score, score]).unwrap();
+        assert_eq!(solver.board_possibilities(1), ["blush"]);
+        assert_eq!(solver.add_board(false), 2);
+        assert_eq!(solver.solvers[2].scoring_rules(), ScoringRules::AllPresent);
+    }
+
+    #[test]
+    fn test_sequence() {
+        let solutions = words(&["cigar", "rebut", "sissy", "humph", "awake", "blush"]);
+        let mut solver = MultiSolver::new(3, &[], &solutions, Strategy::GroupSize);
+        solver.set_sequence(true);
+        assert_eq!(solver.active_boards(), [0]);
+
+        solver.respond_to_score(0, "humph", compute_score("humph", "blush"));
+        assert_eq!(solver.index_needing_response(), None);
+        assert_eq!(solver.next_guess(), "blush");
+
+        // Solving board 0 shows board 1's scores for both guesses, and "humph" solves it.
+        solver.next_round();
+        solver.respond_to_score(0, "blush", compute_score("blush", "blush"));
+        assert_eq!(solver.index_needing_response(), Some(1));
+        assert_eq!(solver.pending_guess(1), Some("humph"));
+        solver
+            .respond_to_scores("blush", &[compute_score("humph", "humph")])
+            .unwrap();
+
+        // Board 2 catches up on both guesses.
+        assert_eq!(solver.active_boards(), [2]);
+        assert_eq!(solver.pending_guess(2), Some("humph"));
+        let scores = [
+            compute_score("humph", "sissy"),
+            compute_score("blush", "sissy"),
+            compute_score("blush", "sissy"),
+        ];
+        assert_eq!(
+            solver.respond_to_scores("blush", &scores),
+            Err(GridError::TooMany(2))
+        );
+        solver.respond_to_scores("blush", &scores[..2]).unwrap();
+        assert_eq!(solver.index_needing_response(), None);
+        assert_eq!(solver.played_guesses(), ["humph", "blush"]);
+        assert_eq!(solver.board_possibilities(2), ["sissy"]);
+    }
+
+    #[test]
+    fn test_add_board() {
+        let solutions = words(&["cigar", "rebut", "sissy", "humph", "awake", "blush"]);
+        let mut solver = MultiSolver::new(1, &[], &solutions, Strategy::GroupSize);
+        solver.respond_to_score(0, "humph", compute_score("humph", "blush"));
+        solver.next_round();
+        solver.respond_to_score(0, "blush", compute_score("blush", "blush"));
+
+        // A board joining without a replay starts fresh, and counts guesses from the start.
+        assert_eq!(solver.add_board(false), 1);
+        assert_eq!(solver.index_needing_response(), None);
+        assert_eq!(solver.board_possibilities(1).len(), solutions.len());
+
+        // A board joining with a replay catches up on both guesses.
+        assert_eq!(solver.add_board(true), 2);
+        assert_eq!(solver.index_needing_response(), Some(2));
+        assert_eq!(solver.pending_guess(2), Some("humph"));
+        let scores = [
+            compute_score("humph", "sissy"),
+            compute_score("blush", "sissy"),
+        ];
+        solver.respond_to_scores("blush", &scores).unwrap();
+        assert_eq!(solver.index_needing_response(), None);
+        assert_eq!(solver.board_possibilities(2), ["sissy"]);
+
+        solver.next_round();
+        assert_eq!(solver.unsolved_boards(), [1, 2]);
+        solver.respond_to_score(1, "sissy", compute_score("sissy", "sissy"));
+        solver.respond_to_score(2, "sissy", compute_score("sissy", "sissy"));
+        assert_eq!(solver.played_guesses(), ["humph", "blush", "sissy"]);
+        assert_eq!(solver.status_lines()[1], "Board 1: solved in 3 (sissy)");
+    }
+
+    #[test]
+    fn test_progress_lines() {
+        let solutions = words(&["cigar", "rebut", "sissy", "humph", "awake", "blush"]);
+        let mut solver = MultiSolver::new(3, &[], &solutions, Strategy::GroupSize);
+        for guess in ["humph", "blush", "sissy"] {
+            solver.next_round();
+            while let Some(board) = solver.index_needing_response() {
+                let answer = ["blush", "sissy", "cigar"][board];
+                solver.respond_to_score(board, guess, compute_score(guess, answer));
+            }
+        }
+        assert_eq!(
+            solver.progress_lines(ScoreFormat::Letters, 80),
+            [
+                "    humph blush sissy",
+                "0 ✓ apaac ccccc",
+                "1 ✓ aaaaa aaaca ccccc",
+                "2   aaaaa aaaaa acaaa",
+            ]
+        );
+        assert_eq!(
+            solver.progress_lines(ScoreFormat::Emoji, 30),
+            [
+                "    … blush      sissy",
+                "0 ✓ … 🟩🟩🟩🟩🟩",
+                "1 ✓ … ⬛⬛⬛🟩⬛ 🟩🟩🟩🟩🟩",
+                "2   … ⬛⬛⬛⬛⬛ ⬛🟩⬛⬛⬛",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_status_lines() {
+        let solutions = words(&["cigar", "rebut", "sissy", "humph", "awake", "blush"]);
+        let mut solver = MultiSolver::new(3, &[], &solutions, Strategy::GroupSize);
+        solver.respond_to_score(0, "humph", compute_score("humph", "humph"));
+        solver.respond_to_score(1, "humph", compute_score("humph", "blush"));
+        solver.respond_to_score(2, "humph", compute_score("humph", "cigar"));
+        assert_eq!(
+            solver.status_lines(),
+            [
+                "Board 0: solved in 1 (humph)",
+                "Board 1: 1 possibility, about 1.0 more guesses: blush",
+                "Board 2: 3 possibilities, about 1.7 more guesses: cigar, sissy, awake",
+            ]
+        );
+
+        let mut sequence = MultiSolver::new(2, &[], &solutions, Strategy::GroupSize);
+        sequence.set_sequence(true);
+        assert_eq!(
+            sequence.status_lines()[1],
+            "Board 1: not showing scores yet"
+        );
+    }
+
+    #[test]
+    fn test_thread_count() {
+        let guessable = words(&["blimp", "compt", "aahed"]);
+        let solutions = words(&[
+            "cigar", "rebut", "sissy", "humph", "awake", "blush", "focal", "evade", "naval",
+            "serve", "heath", "dwarf", "model", "karma", "stink", "grade", "quiet", "bench",
+        ]);
+        let answers = ["humph", "karma", "quiet", "bench"];
+        for strategy in [Strategy::GroupSize, Strategy::GroupCount] {
+            let mut serial = MultiSolver::new(answers.len(), &guessable, &solutions, strategy);
+            let mut parallel = serial.clone();
+            parallel.set_thread_count(4);
+            assert_eq!(play(&mut serial, &answers), play(&mut parallel, &answers));
+        }
+    }
+
+    #[test]
+    fn test_objectives() {
+        let guessable = words(&["blimp", "compt", "aahed"]);
+        let solutions = words(&[
+            "cigar", "rebut", "sissy", "humph", "awake", "blush", "focal", "evade", "naval",
+            "serve", "heath", "dwarf", "model", "karma", "stink", "grade", "quiet", "bench",
+        ]);
+        let answers = ["humph", "karma", "quiet", "bench"];
+        for objective in ["combined", "worst", "total", "finish"] {
+            let objective: Objective = objective.parse().unwrap();
+            assert_eq!(objective.to_string().parse(), Ok(objective));
+            let mut solver =
+                MultiSolver::new(answers.len(), &guessable, &solutions, Strategy::GroupSize);
+            solver.set_objective(objective);
+            let guesses = play(&mut solver, &answers);
+            for answer in answers {
+                assert!(guesses.contains(&answer));
+            }
+        }
+
+        // With one board that's down to two possibilities and one that's wide open, finishing
+        // first means guessing one of the two.
+        let mut solver = MultiSolver::new(2, &guessable, &solutions, Strategy::GroupSize);
+        solver.respond_to_score(0, "humph", compute_score("humph", "model"));
+        solver.respond_to_score(1, "humph", compute_score("humph", "cigar"));
+        assert_eq!(solver.board_possibilities(0), ["model", "karma"]);
+        assert!(!["model", "karma"].contains(&solver.next_guess()));
+        solver.set_objective(Objective::Finish);
+        assert!(["model", "karma"].contains(&solver.next_guess()));
+    }
+
+    #[test]
+    fn test_duplicate_boards() {
+        let solutions = words(&[
+            "acute", "agree", "ample", "argue", "arise", "abase", "abate", "ahead", "adobe",
+        ]);
+        let answers = ["acute", "abase", "acute"];
+        let mut solver = MultiSolver::new(3, &[], &solutions, Strategy::GroupCount);
+        let mut deduplicated = MultiSolver::new(2, &[], &solutions, Strategy::GroupCount);
+        for guess in ["ahead", "adobe"] {
+            assert_eq!(solver.duplicate_boards(), Vec::<Vec<usize>>::new());
+            solver.next_round();
+            deduplicated.next_round();
+            for (board, answer) in answers.iter().enumerate() {
+                let score = compute_score(guess, answer);
+                solver.respond_to_score(board, guess, score);
+                if board < 2 {
+                    deduplicated.respond_to_score(board, guess, score);
+                }
+            }
+        }
+        assert_eq!(solver.duplicate_boards(), [[0, 2]]);
+        assert_eq!(solver.next_guess(), "argue");
+
+        // Merged, board 2 doesn't count, so the choice is the same as without it.
+        solver.set_merge_duplicates(true);
+        assert_eq!(solver.next_guess(), "arise");
+        assert_eq!(deduplicated.next_guess(), "arise");
+    }
+
+    #[test]
+    fn test_next_guess_prefers_solved_board() {
+        let solutions = words(&["cigar", "rebut", "sissy", "humph", "awake", "blush"]);
+        let mut solver = MultiSolver::new(2, &[], &solutions, Strategy::GroupSize);
+        // "humph" against "blush" leaves only "blush" on board 0.
+        solver.respond_to_score(0, "humph", compute_score("humph", "blush"));
+        solver.respond_to_score(1, "humph", compute_score("humph", "cigar"));
+        assert_eq!(solver.next_guess(), "blush");
+    }
+}
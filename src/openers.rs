@@ -0,0 +1,203 @@
+use std::io::{stderr, stdout};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use argparse::{ArgumentParser, List, Parse, Store, StoreTrue};
+
+use wordle_solver::default_thread_count;
+use wordle_solver::loader::load_list_from_file;
+use wordle_solver::solver::{Outcome, Solver, Strategy};
+
+use crate::histogram::run_solver;
+
+/// How an opener did over every answer.
+#[derive(Debug, PartialEq)]
+struct OpenerResult<'a> {
+    opener: &'a str,
+
+    /// Position among the strategy's one-step ranking of openers, if it was chosen from there.
+    eval_rank: Option<usize>,
+    mean: f64,
+    worst: usize,
+}
+
+/// Sort results best first: lowest mean, then lowest worst case. Ties keep their order.
+fn rank(results: &mut [OpenerResult]) {
+    results.sort_by(|a, b| a.mean.total_cmp(&b.mean).then(a.worst.cmp(&b.worst)));
+}
+
+/// Play every answer starting with `opener`, split across threads. Returns the mean and worst
+/// number of guesses. Games can't be lost without a guess limit.
+fn play_all<'a>(
+    new_solver: &(impl Fn() -> Solver<'a> + Sync),
+    opener: &'a str,
+    answers: &[String],
+    thread_count: usize,
+) -> (f64, usize) {
+    let next_index = AtomicUsize::new(0);
+    let guess_counts: Vec<usize> = std::thread::scope(|scope| {
+        let threads: Vec<_> = (0..thread_count.max(1))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut counts = Vec::new();
+                    while let Some(answer) = answers.get(next_index.fetch_add(1, Ordering::Relaxed))
+                    {
+                        match run_solver(new_solver(), opener, answer).0 {
+                            Outcome::Won(n) => counts.push(n),
+                            Outcome::Lost => unreachable!(),
+                        }
+                    }
+                    counts
+                })
+            })
+            .collect();
+        threads
+            .into_iter()
+            .flat_map(|thread| thread.join().unwrap())
+            .collect()
+    });
+
+    let total: usize = guess_counts.iter().sum();
+    let mean = total as f64 / guess_counts.len().max(1) as f64;
+    (mean, guess_counts.into_iter().max().unwrap_or(0))
+}
+
+/// `wordle-solver rank-openers`: play full games over every answer with each of several openers,
+/// and rank them by how many guesses they take.
+pub fn command(args: Vec<String>) {
+    let mut guessable_path = String::new();
+    let mut solutions_path = String::new();
+    let mut openers: Vec<String> = Vec::new();
+    let mut top = 10;
+    let mut strategy = Strategy::GroupSize;
+    let mut hard_mode = false;
+    let mut thread_count = default_thread_count();
+
+    {
+        let mut parser = ArgumentParser::new();
+        parser.set_description(concat!(
+            "Rank openers by the average and worst-case number of guesses over every answer, ",
+            "playing full games with the solver"
+        ));
+        parser.refer(&mut guessable_path).required().add_argument(
+            "guessable",
+            Store,
+            "The guessable list",
+        );
+        parser.refer(&mut solutions_path).required().add_argument(
+            "solutions",
+            Store,
+            "The solutions list",
+        );
+        parser.refer(&mut openers).add_argument(
+            "openers",
+            List,
+            "Openers to rank; if omitted, the strategy's best --top openers",
+        );
+        parser.refer(&mut top).add_option(
+            &["--top"],
+            Store,
+            "How many of the strategy's best openers to rank (default 10)",
+        );
+        parser.refer(&mut strategy).add_option(
+            &["--strategy"],
+            Parse,
+            "Which solving strategy to use: groupcount or groupsize (default)",
+        );
+        parser.refer(&mut hard_mode).add_option(
+            &["--hard-mode"],
+            StoreTrue,
+            "Only guess words that are possible solutions",
+        );
+        parser.refer(&mut thread_count).add_option(
+            &["--thread-count"],
+            Store,
+            "Number of threads to use (default: one per CPU)",
+        );
+        if let Err(code) = parser.parse(args, &mut stdout(), &mut stderr()) {
+            std::process::exit(code);
+        }
+    }
+
+    let [guessable, solutions] = [guessable_path, solutions_path].map(|path| {
+        load_list_from_file(path.as_ref()).unwrap_or_else(|e| {
+            eprintln!("{}: {}", path, e);
+            std::process::exit(1);
+        })
+    });
+
+    let new_solver = || Solver::new(&guessable, &solutions, hard_mode, false, strategy);
+    let candidates: Vec<(&str, Option<usize>)> = if openers.is_empty() {
+        let ranked = new_solver().ranked_guesses();
+        ranked
+            .into_iter()
+            .take(top)
+            .enumerate()
+            .map(|(i, opener)| (opener, Some(i + 1)))
+            .collect()
+    } else {
+        openers
+            .iter()
+            .map(|opener| {
+                let word = guessable
+                    .iter()
+                    .chain(solutions.iter())
+                    .find(|w| *w == opener);
+                let Some(word) = word else {
+                    eprintln!("{} is not in the word lists", opener);
+                    std::process::exit(2);
+                };
+                (word.as_str(), None)
+            })
+            .collect()
+    };
+
+    let mut results: Vec<OpenerResult> = candidates
+        .into_iter()
+        .map(|(opener, eval_rank)| {
+            let (mean, worst) = play_all(&new_solver, opener, &solutions, thread_count);
+            OpenerResult {
+                opener,
+                eval_rank,
+                mean,
+                worst,
+            }
+        })
+        .collect();
+    rank(&mut results);
+
+    println!("RANK  OPENER    MEAN  WORST  EVAL RANK");
+    for (i, result) in results.iter().enumerate() {
+        let eval_rank = result.eval_rank.map_or("-".to_string(), |r| r.to_string());
+        println!(
+            "{:>4}  {:<6}  {:.3}  {:>5}  {:>9}",
+            i + 1,
+            result.opener,
+            result.mean,
+            result.worst,
+            eval_rank
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rank() {
+        let result = |opener, mean, worst| OpenerResult {
+            opener,
+            eval_rank: None,
+            mean,
+            worst,
+        };
+        let mut results = vec![
+            result("crate", 3.5, 6),
+            result("soare", 3.4, 6),
+            result("roate", 3.4, 5),
+        ];
+        rank(&mut results);
+        let openers: Vec<&str> = results.iter().map(|r| r.opener).collect();
+        assert_eq!(openers, ["roate", "soare", "crate"]);
+    }
+}
@@ -0,0 +1,221 @@
+//! A search for decision trees that solve every answer within a number of guesses, to establish
+//! worst-case guarantees. Unlike `Solver`, which commits to one guess at each step, the search
+//! backtracks and tries other guesses when one doesn't work out. A tree it finds is a certificate
+//! that can be checked independently with `tree::evaluate`.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::score::{DetailScore, ScoreTable, ScoringRules, NUM_POSSIBLE_SCORES};
+use crate::tree::DecisionTree;
+
+pub struct Prover<'a> {
+    /// Every word that can be guessed, each once.
+    words: Vec<&'a str>,
+
+    /// The words in the solution list, each once.
+    solutions: Vec<&'a str>,
+    rules: ScoringRules,
+
+    /// Every word's score against every solution, built when first needed, since searches score
+    /// the same pairs over and over.
+    table: Option<ScoreTable>,
+
+    /// How many guesses to try at each step, if limited.
+    candidate_limit: Option<usize>,
+
+    /// Results of earlier searches, by possibilities and number of guesses.
+    memo: HashMap<(Vec<&'a str>, usize), Option<DecisionTree>>,
+}
+
+impl<'a> Prover<'a> {
+    pub fn new(guessable_list: &'a [String], solution_list: &'a [String]) -> Self {
+        let mut seen = HashSet::new();
+        let solutions: Vec<&str> = solution_list
+            .iter()
+            .map(String::as_str)
+            .filter(|word| seen.insert(*word))
+            .collect();
+        let mut words = solutions.clone();
+        words.extend(
+            guessable_list
+                .iter()
+                .map(String::as_str)
+                .filter(|word| seen.insert(*word)),
+        );
+        Prover {
+            words,
+            solutions,
+            rules: ScoringRules::Wordle,
+            table: None,
+            candidate_limit: None,
+            memo: HashMap::new(),
+        }
+    }
+
+    pub fn set_scoring_rules(&mut self, rules: ScoringRules) {
+        self.rules = rules;
+        self.table = None;
+        self.memo.clear();
+    }
+
+    /// Only try this many guesses at each step: the ones that split the possibilities into the
+    /// most groups. This makes the search much faster, but if it fails, there may still be a tree
+    /// that uses other guesses.
+    pub fn set_candidate_limit(&mut self, limit: Option<usize>) {
+        self.candidate_limit = limit;
+        self.memo.clear();
+    }
+
+    /// Whether every guess is tried at each step, so that failing to find a tree proves that
+    /// there isn't one.
+    pub fn is_exhaustive(&self) -> bool {
+        self.candidate_limit
+            .is_none_or(|limit| limit >= self.words.len())
+    }
+
+    /// A tree that solves every one of `possibilities` within `guesses` guesses, if one can be
+    /// found.
+    pub fn solve(&mut self, possibilities: &[&'a str], guesses: usize) -> Option<DecisionTree> {
+        if guesses == 0 || possibilities.is_empty() {
+            return None;
+        }
+        if let [answer] = possibilities {
+            let mut tree = DecisionTree::new(answer);
+            tree.wins = true;
+            return Some(tree);
+        }
+        if guesses == 1 {
+            return None;
+        }
+
+        let key = (possibilities.to_vec(), guesses);
+        if let Some(result) = self.memo.get(&key) {
+            return result.clone();
+        }
+        let result = self.search(possibilities, guesses);
+        self.memo.insert(key, result.clone());
+        result
+    }
+
+    fn search(&mut self, possibilities: &[&'a str], guesses: usize) -> Option<DecisionTree> {
+        let table = self.table.get_or_insert_with(|| {
+            ScoreTable::with_rules(&self.words, &self.solutions, self.rules)
+        });
+        // The possibilities' columns in the table, unless some of them aren't in the solution
+        // list (see `Solver::add_possibility`).
+        let columns: Option<Vec<usize>> = possibilities
+            .iter()
+            .map(|word| table.solution_id(word))
+            .collect();
+
+        // Try the guesses that split the possibilities into the most groups first, preferring
+        // ones that could win. A guess that doesn't split them at all makes no progress. With
+        // two guesses left, every group has to be a single word.
+        let mut candidates: Vec<(usize, bool, usize)> = (0..self.words.len())
+            .map(|index| {
+                let mut seen = [false; NUM_POSSIBLE_SCORES as usize];
+                let mut count = 0;
+                for score in self.scores(index, possibilities, columns.as_deref()) {
+                    count += !seen[score.index() as usize] as usize;
+                    seen[score.index() as usize] = true;
+                }
+                (count, possibilities.contains(&self.words[index]), index)
+            })
+            .filter(|(count, _, _)| *count > 1 && (guesses > 2 || *count == possibilities.len()))
+            .collect();
+        candidates.sort_by_key(|(count, could_win, _)| std::cmp::Reverse((*count, *could_win)));
+        if let Some(limit) = self.candidate_limit {
+            candidates.truncate(limit);
+        }
+
+        'candidates: for (_, _, index) in candidates {
+            let mut groups: Vec<(DetailScore, Vec<&'a str>)> = Vec::new();
+            let mut group_of = [usize::MAX; NUM_POSSIBLE_SCORES as usize];
+            let scores = self.scores(index, possibilities, columns.as_deref());
+            for (score, word) in scores.into_iter().zip(possibilities.iter()) {
+                let slot = &mut group_of[score.index() as usize];
+                if *slot == usize::MAX {
+                    *slot = groups.len();
+                    groups.push((score, Vec::new()));
+                }
+                groups[*slot].1.push(word);
+            }
+            // Check the biggest groups first; they're the likeliest to fail.
+            groups.sort_by_key(|(_, group)| std::cmp::Reverse(group.len()));
+
+            let mut tree = DecisionTree::new(self.words[index]);
+            for (score, group) in groups {
+                if score.is_win() {
+                    tree.wins = true;
+                    continue;
+                }
+                match self.solve(&group, guesses - 1) {
+                    Some(child) => {
+                        tree.children.insert(score, child);
+                    }
+                    None => continue 'candidates,
+                }
+            }
+            return Some(tree);
+        }
+        None
+    }
+
+    /// The scores of `self.words[index]` against each of `possibilities`, from the table if
+    /// they're all in it (`columns`), or computed if not.
+    fn scores(
+        &self,
+        index: usize,
+        possibilities: &[&str],
+        columns: Option<&[usize]>,
+    ) -> Vec<DetailScore> {
+        match (columns, self.table.as_ref()) {
+            (Some(columns), Some(table)) => {
+                let row = table.row(index);
+                columns.iter().map(|column| row[*column]).collect()
+            }
+            _ => self
+                .rules
+                .compute_scores(self.words[index], possibilities)
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::evaluate;
+
+    #[test]
+    fn test_solve() {
+        let solutions = ["cigar", "rebut", "sissy", "humph", "awake", "blush"].map(String::from);
+        let possibilities: Vec<&str> = solutions.iter().map(String::as_str).collect();
+        let mut prover = Prover::new(&[], &solutions);
+        assert!(prover.is_exhaustive());
+        assert_eq!(prover.solve(&possibilities, 1), None);
+
+        let tree = prover.solve(&possibilities, 3).unwrap();
+        let report = evaluate(&tree, &[], &solutions, ScoringRules::Wordle, false);
+        assert!(report.problems.is_empty());
+        assert!(report.worst_case().unwrap() <= 3);
+
+        prover.set_candidate_limit(Some(1));
+        assert!(!prover.is_exhaustive());
+    }
+
+    #[test]
+    fn test_solve_beyond_solution_list() {
+        // "films" tells apart all of these but "night" and "tight", and it can be the answer too.
+        let guessable = ["films"].map(String::from);
+        let solutions = ["fight", "light", "might", "night", "sight", "tight"].map(String::from);
+        let mut prover = Prover::new(&guessable, &solutions);
+        let mut possibilities: Vec<&str> = solutions.iter().map(String::as_str).collect();
+        possibilities.push("films");
+
+        let tree = prover.solve(&possibilities, 3).unwrap();
+        assert_eq!(tree.guess, "films");
+        assert!(tree.wins);
+        assert_eq!(prover.solve(&possibilities, 2), None);
+    }
+}
@@ -1,11 +1,14 @@
+use std::collections::HashMap;
 use std::fmt::{Display, Write};
 use std::hash::Hash;
-use std::io::BufRead;
+use std::io::{BufRead, IsTerminal};
 use std::num::Wrapping;
+use std::str::FromStr;
 
+/// The score for a single letter of a guess.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 #[repr(u8)]
-enum LetterScore {
+pub enum LetterScore {
     Absent = 0,
     Present,
     Correct,
@@ -13,22 +16,99 @@ enum LetterScore {
 
 const LETTERS: [char; 3] = ['a', 'p', 'c'];
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// The score for a guess. Scores are ordered by their `index`.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct DetailScore(u8);
 
 pub const NUM_POSSIBLE_SCORES: u8 = 3u8.pow(5);
 
 impl DetailScore {
+    /// The winning score, "ccccc".
+    pub const fn win() -> Self {
+        DetailScore(NUM_POSSIBLE_SCORES - 1)
+    }
+
     pub fn is_win(&self) -> bool {
-        self.0 == NUM_POSSIBLE_SCORES - 1
+        *self == Self::win()
     }
     pub fn as_num(&self) -> u8 {
         self.0
     }
 
-    #[allow(dead_code)]
-    pub fn all_possible() -> Vec<Self> {
-        Vec::from_iter((0..NUM_POSSIBLE_SCORES).map(DetailScore))
+    /// The score's compact numeric form, suitable for exchanging with other programs. This is a
+    /// stable format: the score read as a base-3 number, with the leftmost letter as the most
+    /// significant digit, and absent = 0, present = 1, correct = 2. So "aaaaa" is 0, "aaaap" is 1,
+    /// and "ccccc" (a win) is 242. Same as `as_num`.
+    pub fn index(&self) -> u8 {
+        self.0
+    }
+
+    /// The inverse of `index`. Returns None if `index` is out of range (at least
+    /// `NUM_POSSIBLE_SCORES`).
+    pub fn from_index(index: u8) -> Option<Self> {
+        if index < NUM_POSSIBLE_SCORES {
+            Some(DetailScore(index))
+        } else {
+            None
+        }
+    }
+
+    fn count(&self, letter_score: LetterScore) -> u8 {
+        self.letters()
+            .iter()
+            .filter(|letter| **letter == letter_score)
+            .count() as u8
+    }
+
+    /// Number of letters in the right place.
+    pub fn correct_count(&self) -> u8 {
+        self.count(LetterScore::Correct)
+    }
+
+    /// Number of letters in the solution, but not in the right place.
+    pub fn present_count(&self) -> u8 {
+        self.count(LetterScore::Present)
+    }
+
+    /// Number of letters not in the solution.
+    pub fn absent_count(&self) -> u8 {
+        self.count(LetterScore::Absent)
+    }
+
+    /// A sort key for how close a guess with this score was to the solution: more correct letters
+    /// is closer, and ties are broken by more present letters. Greater is closer.
+    pub fn closeness(&self) -> (u8, u8) {
+        (self.correct_count(), self.present_count())
+    }
+
+    /// The score for the letter at `index` (0 is leftmost). Panics if `index` is out of range.
+    pub fn letter(&self, index: usize) -> LetterScore {
+        assert!(index < 5, "letter index out of range: {}", index);
+        match self.0 / 3u8.pow(4 - index as u32) % 3 {
+            0 => LetterScore::Absent,
+            1 => LetterScore::Present,
+            _ => LetterScore::Correct,
+        }
+    }
+
+    /// The score for each letter, from left to right.
+    pub fn letters(&self) -> [LetterScore; 5] {
+        let mut result = [LetterScore::Absent; 5];
+        let mut num = self.0;
+        for letter in result.iter_mut().rev() {
+            *letter = match num % 3 {
+                0 => LetterScore::Absent,
+                1 => LetterScore::Present,
+                _ => LetterScore::Correct,
+            };
+            num /= 3;
+        }
+        result
+    }
+
+    /// Every possible score, in increasing order.
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..NUM_POSSIBLE_SCORES).map(DetailScore)
     }
 
     /// Returns an equivalent of a score used by Absurdle. It's called "entropyLost" in Absurdle,
@@ -43,6 +123,7 @@ impl DetailScore {
     /// - Count of PRESENT letters
     /// - Count of ABSENT letters
     /// - Each letter from left to right, with CORRECT > PRESENT > ABSENT
+    ///
     /// For efficiency, pack these into an int instead of using a real tuple.
     #[allow(dead_code)]
     pub fn absurdle_entropy_lost(&self) -> u32 {
@@ -80,6 +161,109 @@ impl Display for DetailScore {
     }
 }
 
+/// Ways of displaying a score.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ScoreFormat {
+    /// "a", "p", and "c" letters, as `Display` prints them.
+    Letters,
+
+    /// "b" (black), "y" (yellow), and "g" (green) letters.
+    ColorLetters,
+
+    /// Emoji squares, as Wordle shares them.
+    Emoji,
+
+    /// The letters on colored backgrounds, using ANSI escape codes.
+    Ansi,
+}
+
+impl argparse::FromCommandLine for ScoreFormat {
+    fn from_argument(s: &str) -> Result<Self, String> {
+        match s {
+            "apc" => Ok(Self::Letters),
+            "byg" => Ok(Self::ColorLetters),
+            "emoji" => Ok(Self::Emoji),
+            "ansi" => Ok(Self::Ansi),
+            _ => Err("score formats are 'apc', 'byg', 'emoji', and 'ansi'".to_string()),
+        }
+    }
+}
+
+impl ScoreFormat {
+    /// Colored output if stdout is a terminal and the NO_COLOR environment variable isn't set
+    /// (https://no-color.org), otherwise plain letters.
+    pub fn default_for_stdout() -> Self {
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        if !no_color && std::io::stdout().is_terminal() {
+            ScoreFormat::Ansi
+        } else {
+            ScoreFormat::Letters
+        }
+    }
+
+    /// How many columns a score takes up on screen: emoji squares are two columns wide, and ANSI
+    /// escape codes take up none.
+    pub fn width(self) -> usize {
+        if self == ScoreFormat::Emoji {
+            10
+        } else {
+            5
+        }
+    }
+}
+
+impl DetailScore {
+    /// Render using "b" (black; absent), "y" (yellow; present), and "g" (green; correct).
+    pub fn to_color_letters(&self) -> String {
+        self.letters()
+            .iter()
+            .map(|letter| match letter {
+                LetterScore::Absent => 'b',
+                LetterScore::Present => 'y',
+                LetterScore::Correct => 'g',
+            })
+            .collect()
+    }
+
+    /// Render as a row of emoji squares: 🟩 (correct), 🟨 (present), and ⬛ (absent).
+    pub fn to_emoji(&self) -> String {
+        self.letters()
+            .iter()
+            .map(|letter| match letter {
+                LetterScore::Absent => '⬛',
+                LetterScore::Present => '🟨',
+                LetterScore::Correct => '🟩',
+            })
+            .collect()
+    }
+
+    /// Render as letters on green, yellow, and gray backgrounds, using ANSI escape codes.
+    pub fn to_ansi(&self) -> String {
+        let mut result = String::new();
+        for letter in self.letters() {
+            let color = match letter {
+                LetterScore::Absent => "97;100",
+                LetterScore::Present => "30;43",
+                LetterScore::Correct => "30;42",
+            };
+            result.push_str(&format!(
+                "\x1b[{}m{}\x1b[0m",
+                color, LETTERS[letter as usize]
+            ));
+        }
+        result
+    }
+
+    pub fn format(&self, format: ScoreFormat) -> String {
+        match format {
+            ScoreFormat::Letters => self.to_string(),
+            ScoreFormat::ColorLetters => self.to_color_letters(),
+            ScoreFormat::Emoji => self.to_emoji(),
+            ScoreFormat::Ansi => self.to_ansi(),
+        }
+    }
+}
+
 fn pack_score(score: &[LetterScore; 5]) -> DetailScore {
     let mut num = 0;
     for letter in score.iter() {
@@ -89,11 +273,276 @@ fn pack_score(score: &[LetterScore; 5]) -> DetailScore {
     DetailScore(num)
 }
 
+/// Whether `word` is valid input for `compute_score`: exactly 5 lowercase letters. Letters outside
+/// of 'a' through 'z' (e.g. 'ñ' or Cyrillic) are allowed, as are letters from scripts that don't
+/// have case.
+pub fn is_valid_word(word: &str) -> bool {
+    if word.len() == 5 {
+        return word.as_bytes().iter().all(u8::is_ascii_lowercase);
+    }
+    word.chars().count() == 5
+        && word.chars().all(|c| {
+            c.is_ascii_lowercase() || (!c.is_ascii() && c.is_alphabetic() && !c.is_uppercase())
+        })
+}
+
+/// Why `try_compute_score` couldn't score a guess.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ScoreError {
+    InvalidGuess(String),
+    InvalidSolution(String),
+}
+
+impl Display for ScoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (which, word) = match self {
+            ScoreError::InvalidGuess(word) => ("guess", word),
+            ScoreError::InvalidSolution(word) => ("solution", word),
+        };
+        write!(
+            f,
+            "Invalid {}: {} (must be 5 lowercase letters)",
+            which, word
+        )
+    }
+}
+
+impl std::error::Error for ScoreError {}
+
+/// How a game scores letters that are in the solution but not in the guessed position.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ScoringRules {
+    /// Each letter of the solution accounts for at most one correct or present letter in the
+    /// guess, so a letter guessed twice with only one copy in the solution is marked once.
+    #[default]
+    Wordle,
+
+    /// Every letter of the guess that's somewhere in the solution, but not in that position, is
+    /// present, no matter how many copies there are. Some Wordle clones score this way.
+    AllPresent,
+}
+
+impl argparse::FromCommandLine for ScoringRules {
+    fn from_argument(s: &str) -> Result<Self, String> {
+        match s {
+            "wordle" => Ok(Self::Wordle),
+            "all-present" => Ok(Self::AllPresent),
+            _ => Err("scoring rules are 'wordle' and 'all-present'".to_string()),
+        }
+    }
+}
+
+impl Display for ScoringRules {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScoringRules::Wordle => write!(f, "wordle"),
+            ScoringRules::AllPresent => write!(f, "all-present"),
+        }
+    }
+}
+
+impl ScoringRules {
+    /// Like the free function `compute_score`, under these rules.
+    pub fn compute_score(&self, guess: &str, solution: &str) -> DetailScore {
+        match self {
+            ScoringRules::Wordle => compute_score(guess, solution),
+            ScoringRules::AllPresent => compute_score_all_present(guess, solution),
+        }
+    }
+
+    /// Like the free function `compute_scores`, under these rules.
+    pub fn compute_scores<'s, 'w: 's>(
+        &self,
+        guess: &'s str,
+        solutions: &'s [&'w str],
+    ) -> impl Iterator<Item = DetailScore> + 's {
+        let rules = *self;
+        let prepared = if rules == ScoringRules::Wordle && guess.len() == 5 {
+            Some(prepare_guess(guess))
+        } else {
+            None
+        };
+        solutions.iter().map(move |solution| match prepared {
+            Some(ref prepared) if solution.len() == 5 => score_prepared(prepared, solution),
+            _ => rules.compute_score(guess, solution),
+        })
+    }
+
+    /// Like the free function `partition`, under these rules.
+    pub fn partition<'w>(
+        &self,
+        guess: &str,
+        words: &[&'w str],
+    ) -> HashMap<DetailScore, Vec<&'w str>> {
+        let mut result: HashMap<DetailScore, Vec<&'w str>> = HashMap::new();
+        for (word, score) in words.iter().zip(self.compute_scores(guess, words)) {
+            result.entry(score).or_default().push(word);
+        }
+        result
+    }
+
+    /// Like the free function `is_consistent`, under these rules.
+    pub fn is_consistent(&self, guess: &str, score: DetailScore, candidate: &str) -> bool {
+        match self {
+            ScoringRules::Wordle => is_consistent(guess, score, candidate),
+            ScoringRules::AllPresent => compute_score_all_present(guess, candidate) == score,
+        }
+    }
+
+    /// Like the free function `is_hard_mode_legal`, under these rules.
+    pub fn is_hard_mode_legal(&self, history: &[(&str, DetailScore)], guess: &str) -> bool {
+        history
+            .iter()
+            .all(|(prev_guess, score)| self.is_consistent(prev_guess, *score, guess))
+    }
+}
+
+/// Like `compute_score`, but checks that both words are valid first. Use this for words that
+/// didn't come from the loader or another validated source.
+pub fn try_compute_score(guess: &str, solution: &str) -> Result<DetailScore, ScoreError> {
+    if !is_valid_word(guess) {
+        return Err(ScoreError::InvalidGuess(guess.to_string()));
+    }
+    if !is_valid_word(solution) {
+        return Err(ScoreError::InvalidSolution(solution.to_string()));
+    }
+    Ok(compute_score(guess, solution))
+}
+
+/// Compute the score that `guess` would get if `solution` were the solution, under Wordle's rules
+/// (see `ScoringRules` for others). This is the hot path of every strategy, so it doesn't check
+/// its inputs: both words must be valid (see `is_valid_word`). Words of 'a' through 'z' take a
+/// fast path that skips bounds checks, so anything else that happens to be 5 bytes long leads to
+/// out-of-bounds accesses. Use `try_compute_score` for unvalidated input.
 pub fn compute_score(guess: &str, solution: &str) -> DetailScore {
+    // A valid word is 5 bytes long exactly when it's all ASCII.
+    if guess.len() == 5 && solution.len() == 5 {
+        score_prepared(&prepare_guess(guess), solution)
+    } else {
+        compute_score_unicode(guess, solution)
+    }
+}
+
+/// Score `guess` against each word in `solutions`, in order. This is equivalent to calling
+/// `compute_score` for each one, but the guess-side setup is only done once, so prefer this for
+/// scoring a guess against a whole possibility list. The same input requirements apply.
+pub fn compute_scores<'s, 'w: 's>(
+    guess: &'s str,
+    solutions: &'s [&'w str],
+) -> impl Iterator<Item = DetailScore> + 's {
+    ScoringRules::Wordle.compute_scores(guess, solutions)
+}
+
+/// Group `words` by the score `guess` would get if each were the solution. Words keep their
+/// relative order within each group. Use this when the words themselves are needed; if only the
+/// group sizes matter, counting scores from `compute_scores` is cheaper.
+pub fn partition<'w>(guess: &str, words: &[&'w str]) -> HashMap<DetailScore, Vec<&'w str>> {
+    ScoringRules::Wordle.partition(guess, words)
+}
+
+/// The slow path of `compute_score`, for words with letters outside of 'a' through 'z'. Instead of
+/// counting letters in an array indexed by letter, this pairs up letters directly, which works for
+/// any alphabet.
+fn compute_score_unicode(guess: &str, solution: &str) -> DetailScore {
+    let guess: Vec<char> = guess.chars().collect();
+    let solution: Vec<char> = solution.chars().collect();
+
+    let mut result = [LetterScore::Absent; 5];
+
+    // Letters of the solution that weren't matched by a correct letter in the guess, and so are
+    // available to make a guess letter elsewhere PRESENT.
+    let mut unmatched = [None; 5];
+
+    for i in 0..5 {
+        if guess[i] == solution[i] {
+            result[i] = LetterScore::Correct;
+        } else {
+            unmatched[i] = Some(solution[i]);
+        }
+    }
+
+    for i in 0..5 {
+        if result[i] == LetterScore::Correct {
+            continue;
+        }
+        if let Some(slot) = unmatched.iter_mut().find(|c| **c == Some(guess[i])) {
+            *slot = None;
+            result[i] = LetterScore::Present;
+        }
+    }
+
+    pack_score(&result)
+}
+
+/// `compute_score` under `ScoringRules::AllPresent`.
+fn compute_score_all_present(guess: &str, solution: &str) -> DetailScore {
+    let solution: Vec<char> = solution.chars().collect();
     let mut result = [LetterScore::Absent; 5];
-    let a = 'a' as usize;
 
+    for (i, c) in guess.chars().enumerate() {
+        if solution[i] == c {
+            result[i] = LetterScore::Correct;
+        } else if solution.contains(&c) {
+            result[i] = LetterScore::Present;
+        }
+    }
+
+    pack_score(&result)
+}
+
+/// A slow scorer, written to be obviously correct rather than fast, to check `compute_score`
+/// against. A guess letter is correct if it matches the solution letter in its position.
+/// Otherwise, it's present if the solution has more copies of the letter than the guess has
+/// correct copies, plus copies marked present earlier in the guess.
+pub fn reference_score(guess: &str, solution: &str) -> DetailScore {
+    let guess: Vec<char> = guess.chars().collect();
+    let solution: Vec<char> = solution.chars().collect();
+    let count = |word: &[char], letter: char| word.iter().filter(|c| **c == letter).count();
+
+    let mut result = [LetterScore::Absent; 5];
+    for i in 0..5 {
+        if guess[i] == solution[i] {
+            result[i] = LetterScore::Correct;
+        }
+    }
+    for i in 0..5 {
+        if result[i] == LetterScore::Correct {
+            continue;
+        }
+        let letter = guess[i];
+        let correct = (0..5)
+            .filter(|j| guess[*j] == letter && result[*j] == LetterScore::Correct)
+            .count();
+        let present_before = (0..i)
+            .filter(|j| guess[*j] == letter && result[*j] == LetterScore::Present)
+            .count();
+        if count(&solution, letter) > correct + present_before {
+            result[i] = LetterScore::Present;
+        }
+    }
+
+    pack_score(&result)
+}
+
+/// The guess's letters as indices into the alphabet ('a' is 0).
+fn prepare_guess(guess: &str) -> [u8; 5] {
     let guess_bytes = guess.as_bytes();
+    let mut result = [0; 5];
+
+    // Go unsafe to avoid bounds checks.
+    unsafe {
+        for (i, letter) in result.iter_mut().enumerate() {
+            *letter = *guess_bytes.get_unchecked(i) - b'a';
+        }
+    }
+
+    result
+}
+
+fn score_prepared(guess: &[u8; 5], solution: &str) -> DetailScore {
+    let mut result = [LetterScore::Absent; 5];
+    let a = b'a';
+
     let sol_bytes = solution.as_bytes();
 
     // Count how many of each letter there is in the solution.
@@ -103,24 +552,24 @@ pub fn compute_score(guess: &str, solution: &str) -> DetailScore {
     unsafe {
         for i in 0..5 {
             let c = *sol_bytes.get_unchecked(i);
-            *solution_counts.get_unchecked_mut(c as usize - a) += 1;
+            *solution_counts.get_unchecked_mut((c - a) as usize) += 1;
         }
 
         // Identify correct letters.
         for i in 0..5 {
-            let c_guess = *guess_bytes.get_unchecked(i);
-            if c_guess == *sol_bytes.get_unchecked(i) {
+            let c_guess = *guess.get_unchecked(i);
+            if c_guess + a == *sol_bytes.get_unchecked(i) {
                 // Subtract this letter from solution_counts so that other copies of the same letter
                 // elsewhere in the guess don't use this letter in the solution to count a PRESENT.
-                *solution_counts.get_unchecked_mut(c_guess as usize - a) -= 1;
+                *solution_counts.get_unchecked_mut(c_guess as usize) -= 1;
                 *result.get_unchecked_mut(i) = LetterScore::Correct;
             }
         }
 
         for i in 0..5 {
-            let c_guess = *guess_bytes.get_unchecked(i);
+            let c_guess = *guess.get_unchecked(i);
             let res_i = result.get_unchecked_mut(i);
-            let solcount = solution_counts.get_unchecked_mut(c_guess as usize - a);
+            let solcount = solution_counts.get_unchecked_mut(c_guess as usize);
             if *res_i != LetterScore::Correct && solcount.0 > 0 {
                 *solcount -= 1;
                 *res_i = LetterScore::Present;
@@ -131,27 +580,220 @@ pub fn compute_score(guess: &str, solution: &str) -> DetailScore {
     pack_score(&result)
 }
 
-/// Turn a 5-letter string of "a", "c", and "p" into a DetailScore.
-fn parse_score_string(score_str: &str) -> Option<DetailScore> {
-    if score_str.len() != 5 {
-        return None;
+/// Whether `candidate` could be the solution given that `guess` got `score`; equivalent to
+/// `compute_score(guess, candidate) == score`, with the same input requirements. This is what
+/// filtering possibilities needs, and it stops at the first letter that doesn't match instead of
+/// computing the whole score.
+pub fn is_consistent(guess: &str, score: DetailScore, candidate: &str) -> bool {
+    if guess.len() != 5 || candidate.len() != 5 {
+        return compute_score_unicode(guess, candidate) == score;
     }
 
-    let mut result = [LetterScore::Absent; 5];
+    let expected = score.letters();
+    let guess = guess.as_bytes();
+    let candidate = candidate.as_bytes();
+
+    // Correct letters don't depend on anything else, so check them before doing any counting.
+    for i in 0..5 {
+        if (guess[i] == candidate[i]) != (expected[i] == LetterScore::Correct) {
+            return false;
+        }
+    }
+
+    // Count the candidate's letters that weren't used up by correct letters.
+    let mut unmatched_counts = [0u8; 26];
+    for i in 0..5 {
+        if guess[i] != candidate[i] {
+            unmatched_counts[(candidate[i] - b'a') as usize] += 1;
+        }
+    }
+
+    for i in 0..5 {
+        if expected[i] == LetterScore::Correct {
+            continue;
+        }
+        let count = &mut unmatched_counts[(guess[i] - b'a') as usize];
+        let present = *count > 0;
+        if present != (expected[i] == LetterScore::Present) {
+            return false;
+        }
+        if present {
+            *count -= 1;
+        }
+    }
+
+    true
+}
+
+/// Whether `guess` is allowed in hard mode, given the guesses made so far and the scores they got.
+/// A guess is allowed if it could be the solution, i.e. it would have gotten the same score for
+/// every previous guess.
+pub fn is_hard_mode_legal(history: &[(&str, DetailScore)], guess: &str) -> bool {
+    history
+        .iter()
+        .all(|(prev_guess, score)| is_consistent(prev_guess, *score, guess))
+}
+
+/// Why a string couldn't be parsed as a score.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ScoreParseError {
+    /// The string didn't have exactly 5 characters. Contains the number it had.
+    WrongLength(usize),
+
+    /// The character at this index isn't a valid score letter or a colored square.
+    InvalidCharacter { index: usize, character: char },
+
+    /// The string mixes the a/p/c and b/y/g notations, so it's probably a typo.
+    MixedNotation,
+}
+
+impl Display for ScoreParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScoreParseError::WrongLength(len) => {
+                write!(f, "Score has {} characters; expected 5", len)
+            }
+            ScoreParseError::InvalidCharacter { index, character } => write!(
+                f,
+                "Invalid character '{}' at index {}; expected a/c/p, b/y/g, or a colored square",
+                character, index
+            ),
+            ScoreParseError::MixedNotation => {
+                write!(f, "Score mixes a/c/p letters with b/y/g letters")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScoreParseError {}
+
+/// Parse the letters of a score, as described for `DetailScore::from_str`. If `allow_unknown` is
+/// set, "?" marks a letter whose score isn't known.
+fn parse_score_letters(
+    score_str: &str,
+    allow_unknown: bool,
+) -> Result<[Option<LetterScore>; 5], ScoreParseError> {
+    // Emoji are sometimes followed by a variation selector, which isn't a separate tile.
+    let chars: Vec<char> = score_str.chars().filter(|c| *c != '\u{fe0f}').collect();
+    if chars.len() != 5 {
+        return Err(ScoreParseError::WrongLength(chars.len()));
+    }
+
+    let mut result = [None; 5];
+    let mut seen_apc = false;
+    let mut seen_byg = false;
 
-    for (i, c) in score_str.chars().enumerate() {
-        result[i] = match c {
-            'a' => LetterScore::Absent,
-            'c' => LetterScore::Correct,
-            'p' => LetterScore::Present,
-            _ => return None,
+    for (index, character) in chars.into_iter().enumerate() {
+        seen_apc |= matches!(character, 'a' | 'c' | 'p');
+        seen_byg |= matches!(character, 'b' | 'g' | 'y');
+        result[index] = match character {
+            'a' | 'b' | '⬛' | '⬜' => Some(LetterScore::Absent),
+            'c' | 'g' | '🟩' | '🟧' => Some(LetterScore::Correct),
+            'p' | 'y' | '🟨' | '🟦' => Some(LetterScore::Present),
+            '?' if allow_unknown => None,
+            _ => return Err(ScoreParseError::InvalidCharacter { index, character }),
         }
     }
 
-    Some(pack_score(&result))
+    if seen_apc && seen_byg {
+        return Err(ScoreParseError::MixedNotation);
+    }
+
+    Ok(result)
+}
+
+impl FromStr for DetailScore {
+    type Err = ScoreParseError;
+
+    /// Parse a 5-letter string of "a" (absent), "c" (correct), and "p" (present), or of "b"
+    /// (black; absent), "g" (green; correct), and "y" (yellow; present), or a row of emoji squares
+    /// as Wordle shares them: 🟩 (correct), 🟨 (present), and ⬛ or ⬜ (absent). The high contrast
+    /// colors 🟧 (correct) and 🟦 (present) are accepted too.
+    fn from_str(score_str: &str) -> Result<Self, Self::Err> {
+        let letters = parse_score_letters(score_str, false)?;
+        Ok(pack_score(&letters.map(|letter| letter.unwrap())))
+    }
+}
+
+impl TryFrom<&str> for DetailScore {
+    type Error = ScoreParseError;
+
+    fn try_from(score_str: &str) -> Result<Self, Self::Error> {
+        score_str.parse()
+    }
 }
 
-/// Read a 5-letter a/c/p string from stdin via interactive prompts.
+/// A score where some letters may be unknown, e.g. when transcribing a blurry screenshot.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct PartialScore([Option<LetterScore>; 5]);
+
+impl PartialScore {
+    pub fn letters(&self) -> [Option<LetterScore>; 5] {
+        self.0
+    }
+
+    /// The full score, if no letters are unknown.
+    pub fn complete(&self) -> Option<DetailScore> {
+        if self.0.iter().all(Option::is_some) {
+            Some(pack_score(&self.0.map(|letter| letter.unwrap())))
+        } else {
+            None
+        }
+    }
+
+    /// Whether `score` is a possible completion of this one, i.e. it agrees on every known letter.
+    pub fn matches(&self, score: DetailScore) -> bool {
+        self.0
+            .iter()
+            .zip(score.letters())
+            .all(|(known, actual)| known.is_none_or(|known| known == actual))
+    }
+}
+
+impl From<DetailScore> for PartialScore {
+    fn from(score: DetailScore) -> Self {
+        PartialScore(score.letters().map(Some))
+    }
+}
+
+impl Display for PartialScore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for letter in self.0 {
+            f.write_char(letter.map_or('?', |letter| LETTERS[letter as usize]))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for PartialScore {
+    type Err = ScoreParseError;
+
+    /// Parse a score as for `DetailScore`, with "?" for unknown letters.
+    fn from_str(score_str: &str) -> Result<Self, Self::Err> {
+        parse_score_letters(score_str, true).map(PartialScore)
+    }
+}
+
+const SCORE_HELP: &str = "Score must be 5 characters, all either 'a' (absent), 'c' (correct), \
+    or 'p' (present); or all either 'b' (black), 'g' (green), or 'y' (yellow). Enter 'w' for a \
+    win.";
+
+/// Tidy up a typed-in score before parsing: lowercase it, and drop whitespace and punctuation
+/// other than "?", so that e.g. "C-P-A-A-A" works. "w" and "win" become "ccccc".
+pub fn clean_score_input(input: &str) -> String {
+    let cleaned: String = input
+        .chars()
+        .filter(|c| !c.is_whitespace() && (!c.is_ascii_punctuation() || *c == '?'))
+        .flat_map(char::to_lowercase)
+        .collect();
+    match cleaned.as_str() {
+        "w" | "win" => "ccccc".to_string(),
+        _ => cleaned,
+    }
+}
+
+/// Read a score from stdin via interactive prompts. Input is cleaned up with `clean_score_input`
+/// before parsing.
 pub fn read_score_interactively(
     input: &mut dyn BufRead,
     output: &mut dyn std::io::Write,
@@ -165,15 +807,231 @@ pub fn read_score_interactively(
         buf.clear();
         input.read_line(&mut buf).unwrap();
 
-        match parse_score_string(buf.trim_end()) {
-            Some(score) => return score,
-            None => println!(
-        "Score must be 5 characters, all either 'a' (absent), 'c' (correct), or 'p' (present)."
+        match clean_score_input(&buf).parse() {
+            Ok(score) => return score,
+            Err(e) => println!("{}. {}", e, SCORE_HELP),
+        }
+    }
+}
+
+/// A response from the game to a guess.
+pub enum Response {
+    Score(DetailScore),
+
+    /// A score with some letters unknown.
+    Partial(PartialScore),
+
+    /// The user found out the answer, e.g. after losing. Not validated.
+    Answer(String),
+
+    /// The game didn't accept the guess as a word.
+    Rejected,
+}
+
+/// Like `read_score_interactively`, but also allows entering "x" to indicate that the game
+/// rejected the guess, "?" for letters whose score isn't known, and "answer=<word>" to reveal the
+/// answer.
+pub fn read_response_interactively(
+    input: &mut dyn BufRead,
+    output: &mut dyn std::io::Write,
+) -> Response {
+    let mut buf = String::new();
+
+    loop {
+        output.write_all(b"Score: ").unwrap();
+        output.flush().unwrap();
+
+        buf.clear();
+        input.read_line(&mut buf).unwrap();
+
+        let trimmed = buf.trim();
+        if let Some(answer) = trimmed.strip_prefix("answer=") {
+            return Response::Answer(answer.trim().to_string());
+        }
+        let cleaned = clean_score_input(trimmed);
+        if cleaned == "x" {
+            return Response::Rejected;
+        }
+
+        match cleaned.parse::<PartialScore>() {
+            Ok(partial) => match partial.complete() {
+                Some(score) => return Response::Score(score),
+                None => return Response::Partial(partial),
+            },
+            Err(e) => println!(
+                "{}. {} Use '?' for unknown letters. Enter 'x' if the game didn't accept the guess.",
+                e, SCORE_HELP
             ),
         }
     }
 }
 
+/// Precomputed scores for every combination of a guess from one list and a solution from another.
+/// Building the table costs one `compute_score` per pair up front; after that, every lookup is an
+/// array access.
+pub struct ScoreTable {
+    guesses: Vec<String>,
+    solutions: Vec<String>,
+    guess_ids: HashMap<String, usize>,
+    solution_ids: HashMap<String, usize>,
+
+    /// Row-major: one row per guess, one column per solution.
+    scores: Vec<DetailScore>,
+}
+
+impl ScoreTable {
+    /// Build the table. Words are identified by their index in the given lists. All words must be
+    /// valid (see `is_valid_word`).
+    pub fn new<G: AsRef<str>, S: AsRef<str>>(guesses: &[G], solutions: &[S]) -> Self {
+        Self::with_rules(guesses, solutions, ScoringRules::Wordle)
+    }
+
+    /// Like `new`, with scores under `rules`.
+    pub fn with_rules<G: AsRef<str>, S: AsRef<str>>(
+        guesses: &[G],
+        solutions: &[S],
+        rules: ScoringRules,
+    ) -> Self {
+        let guesses: Vec<String> = guesses.iter().map(|g| g.as_ref().to_string()).collect();
+        let solutions: Vec<String> = solutions.iter().map(|s| s.as_ref().to_string()).collect();
+        let solution_strs: Vec<&str> = solutions.iter().map(|s| s.as_str()).collect();
+
+        let mut scores = Vec::with_capacity(guesses.len() * solutions.len());
+        for guess in guesses.iter() {
+            scores.extend(rules.compute_scores(guess, &solution_strs));
+        }
+
+        // If a word appears more than once, the first occurrence wins.
+        let ids = |words: &[String]| {
+            let mut ids = HashMap::new();
+            for (id, word) in words.iter().enumerate() {
+                ids.entry(word.clone()).or_insert(id);
+            }
+            ids
+        };
+
+        ScoreTable {
+            guess_ids: ids(&guesses),
+            solution_ids: ids(&solutions),
+            guesses,
+            solutions,
+            scores,
+        }
+    }
+
+    pub fn guess_id(&self, guess: &str) -> Option<usize> {
+        self.guess_ids.get(guess).copied()
+    }
+
+    pub fn solution_id(&self, solution: &str) -> Option<usize> {
+        self.solution_ids.get(solution).copied()
+    }
+
+    pub fn guess(&self, guess_id: usize) -> &str {
+        &self.guesses[guess_id]
+    }
+
+    pub fn solution(&self, solution_id: usize) -> &str {
+        &self.solutions[solution_id]
+    }
+
+    pub fn guess_count(&self) -> usize {
+        self.guesses.len()
+    }
+
+    pub fn solution_count(&self) -> usize {
+        self.solutions.len()
+    }
+
+    /// The score `guess_id` would get if `solution_id` were the solution.
+    pub fn lookup(&self, guess_id: usize, solution_id: usize) -> DetailScore {
+        assert!(solution_id < self.solutions.len());
+        self.scores[guess_id * self.solutions.len() + solution_id]
+    }
+
+    /// The scores `guess_id` would get against every solution, indexed by solution ID.
+    pub fn row(&self, guess_id: usize) -> &[DetailScore] {
+        let width = self.solutions.len();
+        &self.scores[guess_id * width..(guess_id + 1) * width]
+    }
+}
+
+/// Serde support. By default, scores are serialized as "a"/"c"/"p" strings, and can be
+/// deserialized from either a string (letters or emoji) or the compact number that `as_num`
+/// returns. To serialize as the number instead, use `#[serde(with = "score::serde_as_num")]`.
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use serde::de::{Error, Unexpected, Visitor};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{DetailScore, NUM_POSSIBLE_SCORES};
+
+    impl Serialize for DetailScore {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_str(self)
+        }
+    }
+
+    struct ScoreVisitor;
+
+    impl Visitor<'_> for ScoreVisitor {
+        type Value = DetailScore;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(
+                f,
+                "a score string or a number less than {}",
+                NUM_POSSIBLE_SCORES
+            )
+        }
+
+        fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+            v.parse().map_err(E::custom)
+        }
+
+        fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> {
+            u8::try_from(v)
+                .ok()
+                .and_then(DetailScore::from_index)
+                .ok_or_else(|| E::invalid_value(Unexpected::Unsigned(v), &self))
+        }
+
+        fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> {
+            match u64::try_from(v) {
+                Ok(v) => self.visit_u64(v),
+                Err(_) => Err(E::invalid_value(Unexpected::Signed(v), &self)),
+            }
+        }
+    }
+
+    impl<'de> Deserialize<'de> for DetailScore {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_any(ScoreVisitor)
+        }
+    }
+
+    /// For use with `#[serde(with = "...")]`, to serialize scores as their compact number.
+    pub mod serde_as_num {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(
+            score: &DetailScore,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            serializer.serialize_u8(score.as_num())
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<DetailScore, D::Error> {
+            deserializer.deserialize_u8(ScoreVisitor)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+pub use serde_impl::serde_as_num;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -183,7 +1041,7 @@ mod tests {
         macro_rules! assert_score {
             ($expected:literal, $guess:literal, $solution:literal) => {
                 assert_eq!(
-                    parse_score_string($expected).unwrap(),
+                    $expected.parse::<DetailScore>().unwrap(),
                     compute_score($guess, $solution)
                 );
             };
@@ -202,4 +1060,324 @@ mod tests {
         assert_score!("apaac", "arise", "verge");
         assert_score!("pacca", "repeg", "paper");
     }
+
+    #[test]
+    fn test_reference_score() {
+        let words = [
+            "squid", "squib", "espoo", "glorp", "footy", "aabbb", "acccc", "motto", "lofty",
+            "arise", "verge", "repeg", "paper", "señor", "niños",
+        ];
+        for guess in words {
+            for solution in words {
+                assert_eq!(
+                    reference_score(guess, solution),
+                    compute_score(guess, solution),
+                    "{} {}",
+                    guess,
+                    solution
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_is_hard_mode_legal() {
+        let history = [
+            ("arise", "apaac".parse().unwrap()),
+            ("verge", "acccc".parse().unwrap()),
+        ];
+
+        assert!(is_hard_mode_legal(&[], "squid"));
+        assert!(is_hard_mode_legal(&history[..1], "verge"));
+        assert!(is_hard_mode_legal(&history, "merge"));
+        assert!(!is_hard_mode_legal(&history, "verge"));
+        assert!(!is_hard_mode_legal(&history[..1], "arise"));
+    }
+
+    #[test]
+    fn test_letters() {
+        use LetterScore::*;
+
+        let score = "cpaac".parse::<DetailScore>().unwrap();
+        assert_eq!(score.letters(), [Correct, Present, Absent, Absent, Correct]);
+        for (i, letter) in score.letters().iter().enumerate() {
+            assert_eq!(score.letter(i), *letter);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            "acp".parse::<DetailScore>(),
+            Err(ScoreParseError::WrongLength(3))
+        );
+        assert_eq!(
+            "acpxa".parse::<DetailScore>(),
+            Err(ScoreParseError::InvalidCharacter {
+                index: 3,
+                character: 'x'
+            })
+        );
+        assert_eq!(DetailScore::try_from("ccccc").map(|s| s.is_win()), Ok(true));
+        assert_eq!(DetailScore::try_from("ccccc"), Ok(DetailScore::win()));
+    }
+
+    #[test]
+    fn test_partial_score() {
+        let partial: PartialScore = "c?paa".parse().unwrap();
+        assert_eq!(partial.to_string(), "c?paa");
+        assert_eq!(partial.complete(), None);
+        assert!(partial.matches("ccpaa".parse().unwrap()));
+        assert!(partial.matches("cppaa".parse().unwrap()));
+        assert!(!partial.matches("ccaaa".parse().unwrap()));
+
+        let full: DetailScore = "cppaa".parse().unwrap();
+        assert_eq!(PartialScore::from(full).complete(), Some(full));
+        assert_eq!(
+            "g?y⬛b".parse::<PartialScore>(),
+            Ok("c?paa".parse().unwrap())
+        );
+        assert!("c?paa".parse::<DetailScore>().is_err());
+    }
+
+    #[test]
+    fn test_parse_emoji() {
+        let expected = "cpaac".parse::<DetailScore>().unwrap();
+        assert_eq!("🟩🟨⬛⬛🟩".parse(), Ok(expected));
+        assert_eq!("🟩🟨⬜⬜🟩".parse(), Ok(expected));
+        assert_eq!("🟧🟦⬛\u{fe0f}⬜🟧".parse(), Ok(expected));
+        assert_eq!(
+            "🟩🟨⬛⬛".parse::<DetailScore>(),
+            Err(ScoreParseError::WrongLength(4))
+        );
+    }
+
+    #[test]
+    fn test_formats() {
+        let score = "cpaac".parse::<DetailScore>().unwrap();
+        assert_eq!(score.format(ScoreFormat::Letters), "cpaac");
+        assert_eq!(score.format(ScoreFormat::Emoji), "🟩🟨⬛⬛🟩");
+        assert_eq!(score.to_emoji().parse(), Ok(score));
+        assert_eq!(ScoreFormat::Letters.width(), 5);
+        assert_eq!(ScoreFormat::Ansi.width(), 5);
+        assert_eq!(ScoreFormat::Emoji.width(), 10);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let score = "cpaac".parse::<DetailScore>().unwrap();
+        assert_eq!(serde_json::to_string(&score).unwrap(), "\"cpaac\"");
+        assert_eq!(
+            serde_json::from_str::<DetailScore>("\"cpaac\"").unwrap(),
+            score
+        );
+
+        let num = score.as_num().to_string();
+        assert_eq!(serde_json::from_str::<DetailScore>(&num).unwrap(), score);
+        assert!(serde_json::from_str::<DetailScore>("243").is_err());
+
+        let mut serializer = serde_json::Serializer::new(Vec::new());
+        serde_as_num::serialize(&score, &mut serializer).unwrap();
+        assert_eq!(serializer.into_inner(), num.into_bytes());
+    }
+
+    #[test]
+    fn test_counts() {
+        let score = "cpaac".parse::<DetailScore>().unwrap();
+        assert_eq!(score.correct_count(), 2);
+        assert_eq!(score.present_count(), 1);
+        assert_eq!(score.absent_count(), 2);
+
+        let mut scores: Vec<DetailScore> = ["ppppp", "caaaa", "ccccc", "cpaaa"]
+            .iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+        scores.sort_by_key(|score| std::cmp::Reverse(score.closeness()));
+        let sorted: Vec<String> = scores.iter().map(|s| s.to_string()).collect();
+        assert_eq!(sorted, ["ccccc", "cpaaa", "caaaa", "ppppp"]);
+    }
+
+    #[test]
+    fn test_try_compute_score() {
+        assert_eq!(
+            try_compute_score("arise", "verge"),
+            Ok(compute_score("arise", "verge"))
+        );
+        assert_eq!(
+            try_compute_score("aris", "verge"),
+            Err(ScoreError::InvalidGuess("aris".to_string()))
+        );
+        assert_eq!(
+            try_compute_score("arise", "VERGE"),
+            Err(ScoreError::InvalidSolution("VERGE".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_compute_scores() {
+        let solutions = ["maker", "squib", "glorp", "footy"];
+        let scores: Vec<DetailScore> = compute_scores("espoo", &solutions).collect();
+        let expected: Vec<DetailScore> = solutions
+            .iter()
+            .map(|solution| compute_score("espoo", solution))
+            .collect();
+        assert_eq!(scores, expected);
+    }
+
+    #[test]
+    fn test_score_table() {
+        let guesses = ["espoo", "arise", "squid"];
+        let solutions = ["maker", "glorp", "footy", "verge"];
+        let table = ScoreTable::new(&guesses, &solutions);
+
+        for (g, guess) in guesses.iter().enumerate() {
+            assert_eq!(table.guess_id(guess), Some(g));
+            for (s, solution) in solutions.iter().enumerate() {
+                assert_eq!(table.lookup(g, s), compute_score(guess, solution));
+                assert_eq!(table.row(g)[s], compute_score(guess, solution));
+            }
+        }
+        assert_eq!(table.solution_id("verge"), Some(3));
+        assert_eq!(table.solution_id("espoo"), None);
+
+        let rules = ScoringRules::AllPresent;
+        let table = ScoreTable::with_rules(&guesses, &solutions, rules);
+        assert_eq!(table.lookup(1, 0), rules.compute_score("arise", "maker"));
+    }
+
+    #[test]
+    fn test_compute_score_unicode() {
+        assert_eq!(compute_score("señor", "señal"), "cccaa".parse().unwrap());
+        assert_eq!(compute_score("niños", "señor"), "aaccp".parse().unwrap());
+        assert_eq!(compute_score("мышка", "кошка"), "aaccc".parse().unwrap());
+
+        // Mixed ASCII and non-ASCII words, and the batch version.
+        assert_eq!(compute_score("arise", "señor"), "apapp".parse().unwrap());
+        let solutions = ["señor", "verge"];
+        let scores: Vec<DetailScore> = compute_scores("arise", &solutions).collect();
+        assert_eq!(
+            scores,
+            [
+                compute_score("arise", "señor"),
+                compute_score("arise", "verge")
+            ]
+        );
+
+        assert!(is_valid_word("señor"));
+        assert!(!is_valid_word("SEÑOR"));
+        assert!(!is_valid_word("seño"));
+    }
+
+    #[test]
+    fn test_is_consistent() {
+        let words = [
+            "arise", "verge", "espoo", "glorp", "footy", "motto", "lofty", "repeg", "paper",
+            "aabbb", "acccc", "señor", "señal", "niños",
+        ];
+        for guess in words {
+            for solution in words {
+                let score = compute_score(guess, solution);
+                for candidate in words {
+                    assert_eq!(
+                        is_consistent(guess, score, candidate),
+                        compute_score(guess, candidate) == score,
+                        "guess {} score {} candidate {}",
+                        guess,
+                        score,
+                        candidate
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_clean_score_input() {
+        assert_eq!(clean_score_input(" CPAAA\n"), "cpaaa");
+        assert_eq!(clean_score_input("c-p a.a,a"), "cpaaa");
+        assert_eq!(clean_score_input("c?paa!"), "c?paa");
+        assert_eq!(clean_score_input("Win"), "ccccc");
+        assert_eq!(clean_score_input("w"), "ccccc");
+        assert_eq!(clean_score_input("🟩 🟨 ⬛ ⬛ ⬛"), "🟩🟨⬛⬛⬛");
+
+        let mut input = "WIN\n".as_bytes();
+        let score = read_score_interactively(&mut input, &mut std::io::sink());
+        assert!(score.is_win());
+    }
+
+    #[test]
+    fn test_partition() {
+        let words = ["cigar", "rebut", "sissy", "humph", "awake", "blush"];
+        let groups = partition("sissy", &words);
+        assert_eq!(groups.len(), 4);
+        assert_eq!(
+            groups[&"aaaaa".parse().unwrap()],
+            ["rebut", "humph", "awake"]
+        );
+        assert_eq!(groups[&"acaaa".parse().unwrap()], ["cigar"]);
+        assert_eq!(groups[&"aaaca".parse().unwrap()], ["blush"]);
+        assert_eq!(groups.values().map(Vec::len).sum::<usize>(), words.len());
+    }
+
+    #[test]
+    fn test_scoring_rules() {
+        let all_present = ScoringRules::AllPresent;
+        assert_eq!(
+            all_present.compute_score("espoo", "glorp"),
+            "aappp".parse().unwrap()
+        );
+        assert_eq!(
+            all_present.compute_score("motto", "lofty"),
+            "acpcp".parse().unwrap()
+        );
+        assert_eq!(
+            all_present.compute_score("squid", "maker"),
+            "aaaaa".parse().unwrap()
+        );
+
+        let solutions = ["glorp", "lofty", "señor"];
+        for guess in ["espoo", "motto", "niños"] {
+            for rules in [ScoringRules::Wordle, all_present] {
+                let scores: Vec<DetailScore> = rules.compute_scores(guess, &solutions).collect();
+                for (solution, score) in solutions.iter().zip(scores) {
+                    assert_eq!(score, rules.compute_score(guess, solution));
+                    assert!(rules.is_consistent(guess, score, solution));
+                }
+            }
+        }
+        assert!(!all_present.is_consistent("motto", "acaca".parse().unwrap(), "lofty"));
+    }
+
+    #[test]
+    fn test_color_letters() {
+        let score = "cpaac".parse::<DetailScore>().unwrap();
+        assert_eq!("gybbg".parse(), Ok(score));
+        assert_eq!(score.format(ScoreFormat::ColorLetters), "gybbg");
+        assert_eq!(
+            "gypbg".parse::<DetailScore>(),
+            Err(ScoreParseError::MixedNotation)
+        );
+    }
+
+    #[test]
+    fn test_index() {
+        assert_eq!("aaaaa".parse::<DetailScore>().unwrap().index(), 0);
+        assert_eq!("aaaap".parse::<DetailScore>().unwrap().index(), 1);
+        assert_eq!("paaaa".parse::<DetailScore>().unwrap().index(), 81);
+        assert!(DetailScore::from_index(242).unwrap().is_win());
+        assert_eq!(DetailScore::from_index(243), None);
+
+        for score in DetailScore::all() {
+            assert_eq!(DetailScore::from_index(score.index()), Some(score));
+        }
+    }
+
+    #[test]
+    fn test_ordering() {
+        let all: Vec<DetailScore> = DetailScore::all().collect();
+        assert_eq!(all.len(), NUM_POSSIBLE_SCORES as usize);
+        assert!(all.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(all.last().unwrap().is_win());
+    }
 }
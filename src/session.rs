@@ -0,0 +1,246 @@
+use std::io::{BufRead, Write};
+use std::time::Duration;
+
+use crate::read_guess_interactively;
+use crate::score::{is_valid_word, read_response_interactively, Response, ScoreFormat};
+use crate::solver::{Explanation, Outcome, Solver};
+
+/// Plays a whole game: repeatedly picks a guess, gets a score for it, and feeds the score back to
+/// the solver, until the game is won. Prompts and messages go to `output`; anything the user types
+/// in comes from `input`.
+pub struct GameSession<'a, R: BufRead, W: Write> {
+    solver: Solver<'a>,
+    guessable_list: &'a [String],
+    solution_list: &'a [String],
+    input: R,
+    output: W,
+
+    /// If set, compute scores against this word instead of asking for them.
+    answer: Option<&'a str>,
+
+    /// Let the user enter their own guesses instead of using the solver's.
+    enter_guesses: bool,
+
+    /// Maximum time to spend choosing each guess.
+    time_limit: Option<Duration>,
+
+    /// Print how each suggested guess compares to the alternatives.
+    explain: bool,
+
+    /// How to print scores.
+    score_format: ScoreFormat,
+}
+
+impl<'a, R: BufRead, W: Write> GameSession<'a, R, W> {
+    pub fn new(
+        solver: Solver<'a>,
+        guessable_list: &'a [String],
+        solution_list: &'a [String],
+        input: R,
+        output: W,
+    ) -> Self {
+        GameSession {
+            solver,
+            guessable_list,
+            solution_list,
+            input,
+            output,
+            answer: None,
+            enter_guesses: false,
+            time_limit: None,
+            explain: false,
+            score_format: ScoreFormat::Letters,
+        }
+    }
+
+    /// Score guesses against `answer` automatically, instead of asking for scores.
+    pub fn set_answer(&mut self, answer: &'a str) {
+        self.answer = Some(answer);
+    }
+
+    /// Ask for each guess instead of using the solver's suggestion.
+    pub fn set_enter_guesses(&mut self, enter_guesses: bool) {
+        self.enter_guesses = enter_guesses;
+    }
+
+    pub fn set_time_limit(&mut self, time_limit: Duration) {
+        self.time_limit = Some(time_limit);
+    }
+
+    pub fn set_explain(&mut self, explain: bool) {
+        self.explain = explain;
+    }
+
+    pub fn set_score_format(&mut self, score_format: ScoreFormat) {
+        self.score_format = score_format;
+    }
+
+    pub fn solver(&self) -> &Solver<'a> {
+        &self.solver
+    }
+
+    fn suggest(&mut self) -> &'a str {
+        match self.time_limit {
+            Some(limit) => {
+                let (guess, complete) = self.solver.suggest_within(limit);
+                if !complete {
+                    writeln!(
+                        self.output,
+                        "Ran out of time; this guess may not be the best"
+                    )
+                    .unwrap();
+                }
+                guess
+            }
+            None => self.solver.next_guess(),
+        }
+    }
+
+    fn print_explanation(&mut self, explanation: &Explanation) {
+        writeln!(self.output, "{}: {}", explanation.guess, explanation.stats).unwrap();
+        for (other, stats) in explanation.runners_up.iter() {
+            writeln!(self.output, "  vs. {}: {}", other, stats).unwrap();
+        }
+    }
+
+    /// Check the scores entered so far against the answer the game revealed.
+    fn report_answer(&mut self, answer: &str) {
+        if !is_valid_word(answer) {
+            writeln!(self.output, "'{}' isn't a valid word", answer).unwrap();
+            return;
+        }
+        let mismatches = self.solver.verify_answer(answer);
+        if mismatches.is_empty() {
+            writeln!(self.output, "All scores are consistent with {}", answer).unwrap();
+        }
+        for mismatch in mismatches {
+            writeln!(self.output, "{}", mismatch).unwrap();
+        }
+    }
+
+    /// Play until the game is won, the solver runs out of guesses, or the user reveals the answer
+    /// (which counts as a loss). Guesses that the game rejected don't count.
+    pub fn play(&mut self) -> Outcome {
+        loop {
+            if self.solver.guesses_remaining() == Some(0) {
+                writeln!(self.output, "Out of guesses!").unwrap();
+                return Outcome::Lost;
+            }
+
+            let suggestion = self.suggest();
+            if self.explain {
+                let explanation = self.solver.explain_guess(suggestion);
+                self.print_explanation(&explanation);
+            }
+
+            let guess = if self.enter_guesses {
+                writeln!(self.output, "Recommended: {}", suggestion).unwrap();
+                read_guess_interactively(
+                    &mut self.input,
+                    &mut self.output,
+                    self.guessable_list,
+                    self.solution_list,
+                )
+            } else {
+                writeln!(self.output, "Guess: {}", suggestion).unwrap();
+                suggestion
+            };
+
+            let score = match self.answer {
+                Some(answer) => {
+                    let s = self.solver.scoring_rules().compute_score(guess, answer);
+                    writeln!(self.output, "Score: {}", s.format(self.score_format)).unwrap();
+                    s
+                }
+                None => match read_response_interactively(&mut self.input, &mut self.output) {
+                    Response::Score(score) => score,
+                    Response::Partial(partial) => {
+                        self.solver.respond_to_partial_score(guess, partial);
+                        continue;
+                    }
+                    Response::Rejected => {
+                        if let Err(e) = self.solver.mark_unguessable(guess) {
+                            writeln!(self.output, "{}", e).unwrap();
+                        }
+                        continue;
+                    }
+                    Response::Answer(answer) => {
+                        self.report_answer(&answer);
+                        return Outcome::Lost;
+                    }
+                },
+            };
+            if score.is_win() {
+                writeln!(self.output, "Win!").unwrap();
+                return Outcome::Won(self.solver.guesses_used() + 1);
+            }
+
+            self.solver.respond_to_score(guess, score);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{SolverError, Strategy};
+
+    #[test]
+    fn test_play() {
+        let solutions: Vec<String> = ["cigar", "rebut", "sissy", "humph", "awake", "blush"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let solver = Solver::new(&[], &solutions, false, false, Strategy::GroupSize);
+
+        let mut output = Vec::new();
+        let outcome = {
+            let input = std::io::empty();
+            let mut session = GameSession::new(solver, &[], &solutions, input, &mut output);
+            session.set_answer("humph");
+            session.play()
+        };
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.ends_with("Score: ccccc\nWin!\n"));
+        assert_eq!(outcome, Outcome::Won(output.matches("Guess: ").count()));
+    }
+
+    #[test]
+    fn test_out_of_guesses() {
+        let solutions = ["fight", "light", "might", "night", "sight", "tight"].map(String::from);
+        let mut solver = Solver::new(&[], &solutions, false, false, Strategy::GroupSize);
+        solver.set_max_guesses(1);
+
+        let mut output = Vec::new();
+        let outcome = {
+            let input = std::io::empty();
+            let mut session = GameSession::new(solver, &[], &solutions, input, &mut output);
+            session.set_answer("tight");
+            session.play()
+        };
+
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.matches("Guess: ").count(), 1);
+        assert!(output.ends_with("Out of guesses!\n"));
+        assert_eq!(outcome, Outcome::Lost);
+    }
+
+    #[test]
+    fn test_reject_last_possibility() {
+        let solutions = vec!["cigar".to_string()];
+        let solver = Solver::new(&[], &solutions, false, false, Strategy::GroupSize);
+
+        let mut output = Vec::new();
+        let outcome = {
+            let input = "x\nccccc\n".as_bytes();
+            let mut session = GameSession::new(solver, &[], &solutions, input, &mut output);
+            session.play()
+        };
+
+        let output = String::from_utf8(output).unwrap();
+        let error = SolverError::NoPossibilitiesLeft.to_string();
+        assert!(output.contains(&format!("Score: {}\nGuess: cigar\n", error)));
+        assert_eq!(outcome, Outcome::Won(1));
+    }
+}
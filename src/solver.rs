@@ -1,7 +1,13 @@
-use crate::eval::eval_guess;
-use crate::score::{compute_score, DetailScore};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
 
-#[derive(Clone, Copy, PartialEq)]
+use crate::eval::{eval_guess, guess_stats, GuessStats};
+use crate::knowledge::LetterKnowledge;
+use crate::score::{DetailScore, PartialScore, ScoringRules};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Strategy {
     GroupSize,
     GroupCount,
@@ -17,6 +23,110 @@ impl argparse::FromCommandLine for Strategy {
     }
 }
 
+impl FromStr for Strategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        argparse::FromCommandLine::from_argument(s)
+    }
+}
+
+impl Display for Strategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Strategy::GroupSize => write!(f, "groupsize"),
+            Strategy::GroupCount => write!(f, "groupcount"),
+        }
+    }
+}
+
+/// The result of a game. Derived ordering puts better outcomes first: fewer guesses is better, and
+/// any win is better than a loss.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Outcome {
+    /// Solved in this many guesses.
+    Won(usize),
+
+    /// Ran out of guesses.
+    Lost,
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Won(n) => write!(f, "{}", n),
+            Outcome::Lost => write!(f, "X"),
+        }
+    }
+}
+
+/// Errors from changing the possibility set directly.
+#[derive(Debug, PartialEq)]
+pub enum SolverError {
+    /// The word isn't in either word list.
+    UnknownWord(String),
+
+    /// The word couldn't be the solution, given the score that this guess got.
+    Inconsistent {
+        word: String,
+        guess: String,
+        score: DetailScore,
+    },
+
+    /// The change would leave no possible solutions.
+    NoPossibilitiesLeft,
+}
+
+impl Display for SolverError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolverError::UnknownWord(word) => write!(f, "'{}' is not in the word lists", word),
+            SolverError::Inconsistent { word, guess, score } => write!(
+                f,
+                "'{}' can't be the solution, because '{}' got the score {}",
+                word, guess, score
+            ),
+            SolverError::NoPossibilitiesLeft => write!(f, "No possibilities would be left"),
+        }
+    }
+}
+
+impl std::error::Error for SolverError {}
+
+/// A recorded score that doesn't match the one the guess would have gotten against the answer, as
+/// found by `Solver::verify_answer`.
+#[derive(Debug, PartialEq)]
+pub struct Mismatch<'a> {
+    /// Which guess this was, starting from 1.
+    pub turn: usize,
+    pub guess: &'a str,
+    pub recorded: DetailScore,
+    pub expected: DetailScore,
+}
+
+impl Display for Mismatch<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Guess {} ({}) was scored {}, but should have been {}",
+            self.turn, self.guess, self.recorded, self.expected
+        )
+    }
+}
+
+/// How many alternatives `Solver::explain_guess` compares the guess against.
+const RUNNER_UP_COUNT: usize = 3;
+
+/// A comparison of a guess against the best alternatives to it.
+pub struct Explanation<'a> {
+    pub guess: &'a str,
+    pub stats: GuessStats,
+
+    /// The next best guesses according to the strategy, best first.
+    pub runners_up: Vec<(&'a str, GuessStats)>,
+}
+
+#[derive(Clone)]
 pub struct Solver<'a> {
     /// Possible solutions that haven't been eliminated yet.
     possibilities: Vec<&'a str>,
@@ -27,17 +137,40 @@ pub struct Solver<'a> {
     /// All words that can be solutions.
     solution_list: &'a [String],
 
-    /// Guesses made so far, and the scores they got. Only used in hard mode.
+    /// If set, only these words (plus the remaining possibilities) are considered as guesses.
+    guess_candidates: Option<&'a [String]>,
+
+    /// Relative likelihood of each word being the solution, e.g. word frequencies. If unset, all
+    /// possibilities are equally likely.
+    priors: Option<&'a HashMap<String, f64>>,
+
+    /// Words that the game refused to accept as guesses.
+    unguessable: HashSet<String>,
+
+    /// Guesses made so far, and the scores they got.
     history: Vec<(&'a str, DetailScore)>,
 
+    /// Guesses that got a score with some letters unknown, with the turn each was played on,
+    /// starting from 1. They use up guesses, but aren't in `history`.
+    partial_history: Vec<(usize, &'a str, PartialScore)>,
+
     /// Only allowed to guess words that match scores seen so far.
     hard_mode: bool,
 
     /// Whether to print log messages.
     verbose: bool,
 
+    /// The number of guesses allowed in total, if limited.
+    max_guesses: Option<usize>,
+
+    /// Consider guesses in alphabetical order, so results don't depend on word list order.
+    deterministic: bool,
+
     /// Which solving strategy to use.
     strategy: Strategy,
+
+    /// How the game scores guesses.
+    rules: ScoringRules,
 }
 
 impl<'a> Solver<'a> {
@@ -52,18 +185,232 @@ impl<'a> Solver<'a> {
             possibilities: Vec::from_iter(solution_list.iter().map(|s| s.as_str())),
             guessable_list,
             solution_list,
+            guess_candidates: None,
+            priors: None,
+            unguessable: HashSet::new(),
             history: Vec::new(),
+            partial_history: Vec::new(),
             hard_mode,
             verbose,
+            max_guesses: None,
+            deterministic: false,
             strategy,
+            rules: ScoringRules::Wordle,
+        }
+    }
+
+    /// Create a solver for a game with a single word list that doesn't distinguish possible
+    /// solutions from other guessable words. Every word is treated as a possible solution. If
+    /// there's a way to guess which words are unlikely to be solutions, split the list with
+    /// `loader::split_merged_list` and use `new` instead.
+    pub fn with_single_list(
+        word_list: &'a [String],
+        hard_mode: bool,
+        verbose: bool,
+        strategy: Strategy,
+    ) -> Self {
+        Self::new(&[], word_list, hard_mode, verbose, strategy)
+    }
+
+    /// Only consider words from `candidates` when choosing a guess, e.g. to avoid suggesting
+    /// obscure words. The remaining possibilities are always considered too, since the answer has
+    /// to be guessed eventually. This doesn't affect which words can be solutions.
+    pub fn restrict_guesses(&mut self, candidates: &'a [String]) {
+        self.guess_candidates = Some(candidates);
+    }
+
+    /// Use these weights as the relative likelihood of each word being the solution. Words that
+    /// aren't in the map have a weight of zero. This affects probabilities that are reported, not
+    /// which guesses are chosen.
+    pub fn set_priors(&mut self, priors: &'a HashMap<String, f64>) {
+        self.priors = Some(priors);
+    }
+
+    /// The probability of `word` being the solution, given the scores seen so far.
+    pub fn probability(&self, word: &str) -> f64 {
+        if !self.possibilities.contains(&word) {
+            return 0.0;
+        }
+        match self.total_prior_weight() {
+            Some(total) => self.prior_weight(word) / total,
+            None => 1.0 / self.possibilities.len() as f64,
+        }
+    }
+
+    /// Each remaining possibility with its probability of being the solution, most likely first.
+    pub fn possibility_probabilities(&self) -> Vec<(&'a str, f64)> {
+        let total = self.total_prior_weight();
+        let mut result: Vec<(&'a str, f64)> = self
+            .possibilities
+            .iter()
+            .map(|word| match total {
+                Some(total) => (*word, self.prior_weight(word) / total),
+                None => (*word, 1.0 / self.possibilities.len() as f64),
+            })
+            .collect();
+        result.sort_by(|a, b| b.1.total_cmp(&a.1));
+        result
+    }
+
+    fn prior_weight(&self, word: &str) -> f64 {
+        self.priors
+            .and_then(|priors| priors.get(word))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// Total prior weight of the remaining possibilities, or None if probabilities should be
+    /// uniform (no priors set, or none of the possibilities have any weight).
+    fn total_prior_weight(&self) -> Option<f64> {
+        self.priors?;
+        let total: f64 = self
+            .possibilities
+            .iter()
+            .map(|w| self.prior_weight(w))
+            .sum();
+        if total > 0.0 {
+            Some(total)
+        } else {
+            None
         }
     }
 
+    /// `GuessStats` for a guess, with the win probability taking priors into account.
+    pub(crate) fn stats(&self, guess: &str) -> GuessStats {
+        let mut stats = guess_stats(guess, &self.possibilities, self.rules);
+        stats.win_probability = self.probability(guess);
+        stats
+    }
+
+    /// What the scores so far have revealed about individual letters.
+    pub fn letter_knowledge(&self) -> LetterKnowledge {
+        LetterKnowledge::from_history(&self.history)
+    }
+
+    /// The guesses made so far, with their scores.
+    pub fn history(&self) -> &[(&'a str, DetailScore)] {
+        &self.history
+    }
+
+    /// The guesses that got partial scores so far, with the turn each was played on. See
+    /// `respond_to_partial_score`.
+    pub fn partial_history(&self) -> &[(usize, &'a str, PartialScore)] {
+        &self.partial_history
+    }
+
+    /// How many guesses have been scored, including partially.
+    pub fn guesses_used(&self) -> usize {
+        self.history.len() + self.partial_history.len()
+    }
+
     #[allow(dead_code)]
     pub fn get_possibilities(&self) -> &[&'a str] {
         &self.possibilities
     }
 
+    /// Limit the number of guesses allowed (6 in Wordle). With one guess left, only possible
+    /// solutions are considered as guesses, since any other guess would lose.
+    pub fn set_max_guesses(&mut self, max_guesses: usize) {
+        self.max_guesses = Some(max_guesses);
+    }
+
+    /// How many more guesses are allowed, if limited.
+    pub fn guesses_remaining(&self) -> Option<usize> {
+        self.max_guesses
+            .map(|max| max.saturating_sub(self.guesses_used()))
+    }
+
+    /// Use scoring rules other than Wordle's. Set this before responding to any scores.
+    pub fn set_scoring_rules(&mut self, rules: ScoringRules) {
+        self.rules = rules;
+    }
+
+    pub fn scoring_rules(&self) -> ScoringRules {
+        self.rules
+    }
+
+    /// Print log messages, or stop printing them.
+    pub fn set_verbose(&mut self, verbose: bool) {
+        self.verbose = verbose;
+    }
+
+    /// Make results independent of the order of the word lists, and of duplicates in them.
+    /// Candidates are considered in alphabetical order, so when several guesses are equally good,
+    /// the alphabetically first possible solution among them is chosen, or if none of them are
+    /// possible solutions, the alphabetically first guess. Possibilities are also kept in
+    /// alphabetical order.
+    pub fn set_deterministic(&mut self, deterministic: bool) {
+        self.deterministic = deterministic;
+        if deterministic {
+            self.possibilities.sort_unstable();
+            self.possibilities.dedup();
+        }
+    }
+
+    /// All words that should be considered as guesses, in the order they should be considered.
+    /// Ties in evaluation are broken in favor of words that come earlier.
+    fn candidate_guesses(&self) -> Box<dyn Iterator<Item = &'a str> + '_> {
+        if self.guesses_remaining() == Some(1) {
+            return Box::new(self.possibilities.iter().copied());
+        }
+        if self.deterministic {
+            let mut sorted: Vec<&'a str> = self.unordered_candidate_guesses().collect();
+            sorted.sort_unstable();
+            sorted.dedup();
+            return Box::new(sorted.into_iter());
+        }
+        self.unordered_candidate_guesses()
+    }
+
+    fn unordered_candidate_guesses(&self) -> Box<dyn Iterator<Item = &'a str> + '_> {
+        match self.guess_candidates {
+            Some(list) => Box::new(
+                self.possibilities
+                    .iter()
+                    .copied()
+                    .chain(list.iter().map(|s| s.as_str())),
+            ),
+            None => Box::new(
+                self.solution_list
+                    .iter()
+                    .chain(self.guessable_list.iter())
+                    .map(|s| s.as_str()),
+            ),
+        }
+    }
+
+    /// Whether the rules allow guessing this word. For hard mode, filter out guesses that don't
+    /// match the information we have so far.
+    fn is_allowed_guess(&self, guess: &str) -> bool {
+        !self.unguessable.contains(guess)
+            && (!self.hard_mode || self.rules.is_hard_mode_legal(&self.history, guess))
+    }
+
+    /// Evaluate a guess according to the strategy. Higher is better.
+    pub(crate) fn strategy_eval(&self, guess: &str) -> (i32, i32) {
+        let eval = eval_guess(guess, &self.possibilities, self.rules);
+        if self.strategy == Strategy::GroupCount {
+            (eval.count, eval.size)
+        } else {
+            (eval.size, eval.count)
+        }
+    }
+
+    /// Of the best guesses, prefer one that is a possible solution given the scores we've gotten
+    /// so far. If there isn't one, that's OK; we won't win on this turn but it should maximize the
+    /// new info we get.
+    fn pick_from_best(&self, best_guesses: &[&'a str]) -> &'a str {
+        best_guesses
+            .iter()
+            .find(|guess| self.possibilities.contains(guess))
+            .unwrap_or_else(|| {
+                if self.verbose {
+                    println!("Guessing a word that is not a possible solution");
+                }
+                &best_guesses[0]
+            })
+    }
+
     /// Return the next word to guess.
     pub fn next_guess(&self) -> &'a str {
         if self.possibilities.len() == 1 {
@@ -73,23 +420,12 @@ impl<'a> Solver<'a> {
         let mut best_eval = (i32::MIN, i32::MIN);
         let mut best_guesses: Vec<&str> = Vec::new();
 
-        'next_guess: for guess in self.solution_list.iter().chain(self.guessable_list.iter()) {
-            // For hard mode, filter out guesses that don't match the information we have so far.
-            if self.hard_mode {
-                for (prev_guess, score) in self.history.iter() {
-                    if compute_score(prev_guess, guess) != *score {
-                        continue 'next_guess;
-                    }
-                }
+        for guess in self.candidate_guesses() {
+            if !self.is_allowed_guess(guess) {
+                continue;
             }
 
-            let eval = eval_guess(guess, &self.possibilities);
-            let eval = if self.strategy == Strategy::GroupCount {
-                (eval.count, eval.size)
-            } else {
-                (eval.size, eval.count)
-            };
-
+            let eval = self.strategy_eval(guess);
             if eval > best_eval {
                 best_eval = eval;
                 best_guesses.clear();
@@ -99,30 +435,240 @@ impl<'a> Solver<'a> {
             }
         }
 
-        // Of the best guesses, prefer one that is a possible solution given the scores we've
-        // gotten so far. If there isn't one, that's OK; we won't win on this turn but it should
-        // maximize the new info we get.
-        best_guesses
+        self.pick_from_best(&best_guesses)
+    }
+
+    /// Like `next_guess`, but gives up evaluating candidates once `budget` has elapsed, and
+    /// returns the best guess found so far. The second return value is whether every candidate
+    /// was evaluated, in which case the result is the same as `next_guess`.
+    ///
+    /// Candidates are evaluated in order of a cheap heuristic (how evenly their letters split the
+    /// remaining possibilities), so good guesses tend to be found early even with a short budget.
+    pub fn suggest_within(&self, budget: Duration) -> (&'a str, bool) {
+        let start = Instant::now();
+
+        if self.possibilities.len() == 1 {
+            return (self.possibilities[0], true);
+        }
+
+        // Each word's letters, once each.
+        let distinct_letters = |word: &str| {
+            let mut letters: Vec<char> = word.chars().collect();
+            letters.sort_unstable();
+            letters.dedup();
+            letters
+        };
+
+        // For each letter, how many possibilities contain it. A letter is most informative when
+        // it's in about half of them.
+        let mut letter_counts: HashMap<char, i32> = HashMap::new();
+        for possibility in self.possibilities.iter() {
+            for c in distinct_letters(possibility) {
+                *letter_counts.entry(c).or_insert(0) += 1;
+            }
+        }
+        let total = self.possibilities.len() as i32;
+        let heuristic = |guess: &str| -> i32 {
+            // Letters that aren't in any possibility don't help split them up.
+            distinct_letters(guess)
+                .into_iter()
+                .filter_map(|c| letter_counts.get(&c))
+                .map(|count| (*count).min(total - count))
+                .sum()
+        };
+
+        // Remember each candidate's original position, so ties are broken the same way as in
+        // `next_guess`.
+        let mut candidates: Vec<(usize, &'a str)> = self
+            .candidate_guesses()
+            .enumerate()
+            .filter(|(_, guess)| self.is_allowed_guess(guess))
+            .collect();
+        candidates.sort_by_cached_key(|(_, guess)| -heuristic(guess));
+
+        let mut best_eval = (i32::MIN, i32::MIN);
+        let mut best_guesses: Vec<(usize, &'a str)> = Vec::new();
+        let mut complete = true;
+
+        for (index, guess) in candidates {
+            if !best_guesses.is_empty() && start.elapsed() >= budget {
+                complete = false;
+                break;
+            }
+
+            let eval = self.strategy_eval(guess);
+            if eval > best_eval {
+                best_eval = eval;
+                best_guesses.clear();
+            }
+            if eval == best_eval {
+                best_guesses.push((index, guess));
+            }
+        }
+
+        best_guesses.sort_unstable();
+        let best_guesses: Vec<&'a str> = best_guesses.into_iter().map(|(_, g)| g).collect();
+        (self.pick_from_best(&best_guesses), complete)
+    }
+
+    /// Explain why `guess` (typically the result of `next_guess`) is a good choice, by comparing
+    /// it against the next best guesses according to the strategy.
+    pub fn explain_guess(&self, guess: &'a str) -> Explanation<'a> {
+        let runners_up = self
+            .ranked_guesses()
+            .into_iter()
+            .filter(|other| *other != guess)
+            .take(RUNNER_UP_COUNT)
+            .map(|other| (other, self.stats(other)))
+            .collect();
+
+        Explanation {
+            guess,
+            stats: self.stats(guess),
+            runners_up,
+        }
+    }
+
+    /// Every allowed guess, best first according to the strategy. Ties keep the order that
+    /// `next_guess` considers them in.
+    pub fn ranked_guesses(&self) -> Vec<&'a str> {
+        let mut ranked: Vec<((i32, i32), &'a str)> = self
+            .candidate_guesses()
+            .filter(|guess| self.is_allowed_guess(guess))
+            .map(|guess| (self.strategy_eval(guess), guess))
+            .collect();
+
+        // The sort is stable, so ties stay in order.
+        ranked.sort_by_key(|(eval, _)| std::cmp::Reverse(*eval));
+        let mut seen = HashSet::new();
+        ranked
+            .into_iter()
+            .map(|(_, guess)| guess)
+            // With a restricted guess list, the same word can show up twice.
+            .filter(|guess| seen.insert(*guess))
+            .collect()
+    }
+
+    /// Stop considering `word`, because the game rejected it as a guess (some clones have smaller
+    /// dictionaries than the word lists). A word the game doesn't accept can't be its solution
+    /// either, so it's also removed from the possibilities. Changes nothing if it's the only
+    /// possibility left.
+    pub fn mark_unguessable(&mut self, word: &str) -> Result<(), SolverError> {
+        if self.possibilities == [word] {
+            return Err(SolverError::NoPossibilitiesLeft);
+        }
+        self.unguessable.insert(word.to_string());
+        self.possibilities
+            .retain(|possibility| *possibility != word);
+        Ok(())
+    }
+
+    /// Find `word` in the word lists, to get a reference with the right lifetime.
+    fn find_word(&self, word: &str) -> Result<&'a str, SolverError> {
+        self.solution_list
             .iter()
-            .find(|guess| self.possibilities.contains(guess))
-            .unwrap_or_else(|| {
-                if self.verbose {
-                    println!("Guessing a word that is not a possible solution");
+            .chain(self.guessable_list.iter())
+            .find(|w| *w == word)
+            .map(|w| w.as_str())
+            .ok_or_else(|| SolverError::UnknownWord(word.to_string()))
+    }
+
+    /// Rule out `word` as the solution based on information from outside the game (e.g. the
+    /// solution is known not to be a plural). Does nothing if it was already ruled out.
+    pub fn remove_possibility(&mut self, word: &str) -> Result<(), SolverError> {
+        self.find_word(word)?;
+        if self.possibilities == [word] {
+            return Err(SolverError::NoPossibilitiesLeft);
+        }
+        self.possibilities
+            .retain(|possibility| *possibility != word);
+        Ok(())
+    }
+
+    /// Consider `word` a possible solution, even if it's not in the solution list. It must be
+    /// consistent with the scores seen so far. Does nothing if it's already a possibility.
+    pub fn add_possibility(&mut self, word: &str) -> Result<(), SolverError> {
+        let word = self.find_word(word)?;
+        if let Some((guess, score)) = self
+            .history
+            .iter()
+            .find(|(guess, score)| !self.rules.is_consistent(guess, *score, word))
+        {
+            return Err(SolverError::Inconsistent {
+                word: word.to_string(),
+                guess: guess.to_string(),
+                score: *score,
+            });
+        }
+        if !self.possibilities.contains(&word) {
+            self.possibilities.push(word);
+            if self.deterministic {
+                self.possibilities.sort_unstable();
+            }
+        }
+        Ok(())
+    }
+
+    /// Check every score so far against the true answer, e.g. once the game reveals it. Returns
+    /// the scores that must have been entered wrong, in order; if there are none, the answer was
+    /// consistent with the whole game. `answer` must be a valid word, but needn't be in the lists.
+    pub fn verify_answer(&self, answer: &str) -> Vec<Mismatch<'a>> {
+        let mut partial_turns = self.partial_history.iter().map(|(turn, _, _)| *turn);
+        let mut next_partial = partial_turns.next();
+        let mut turn = 0;
+        self.history
+            .iter()
+            .filter_map(|(guess, recorded)| {
+                turn += 1;
+                while next_partial == Some(turn) {
+                    turn += 1;
+                    next_partial = partial_turns.next();
+                }
+                let expected = self.rules.compute_score(guess, answer);
+                if expected == *recorded {
+                    None
+                } else {
+                    Some(Mismatch {
+                        turn,
+                        guess,
+                        recorded: *recorded,
+                        expected,
+                    })
                 }
-                &best_guesses[0]
             })
+            .collect()
     }
 
     /// Whittle down the possibilities set given the actual score for a guess. Note that this
     /// doesn't assume the guess is one that `next_guess` actually returned; it can be anything.
     pub fn respond_to_score(&mut self, guess: &'a str, score: DetailScore) {
-        if self.hard_mode {
-            self.history.push((guess, score));
+        self.history.push((guess, score));
+
+        self.possibilities
+            .retain(|possibility| self.rules.is_consistent(guess, score, possibility));
+
+        self.report_possibilities();
+    }
+
+    /// Like `respond_to_score`, but for a score with some letters unknown: keeps every possibility
+    /// that would have gotten some completion of the score. Partial scores are recorded in
+    /// `partial_history` rather than the history, so they use up a guess but don't constrain hard
+    /// mode guesses.
+    pub fn respond_to_partial_score(&mut self, guess: &'a str, score: PartialScore) {
+        if let Some(complete) = score.complete() {
+            self.respond_to_score(guess, complete);
+            return;
         }
+        self.partial_history
+            .push((self.guesses_used() + 1, guess, score));
 
         self.possibilities
-            .retain(|possibility| compute_score(guess, possibility) == score);
+            .retain(|possibility| score.matches(self.rules.compute_score(guess, possibility)));
+
+        self.report_possibilities();
+    }
 
+    fn report_possibilities(&self) {
         if self.possibilities.is_empty() {
             // This should not happen absent human error in playing the game.
             panic!("No possibilities left");
@@ -130,10 +676,290 @@ impl<'a> Solver<'a> {
 
         if self.verbose {
             if self.possibilities.len() <= 10 {
-                println!("Possibilities left: {}", self.possibilities.join(", "));
+                let described: Vec<String> = self
+                    .possibility_probabilities()
+                    .iter()
+                    .map(|(word, p)| format!("{} {:.0}%", word, p * 100.0))
+                    .collect();
+                println!("Possibilities left: {}", described.join(", "));
             } else {
                 println!("{} possibilities left", self.possibilities.len());
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::score::compute_score;
+
+    fn words(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_suggest_within() {
+        let guessable = words(&["blimp", "compt", "aahed"]);
+        let solutions = words(&[
+            "cigar", "rebut", "sissy", "humph", "awake", "blush", "focal", "evade", "naval",
+            "serve", "heath", "dwarf", "model", "karma", "stink", "grade", "quiet", "bench",
+        ]);
+
+        for strategy in [Strategy::GroupSize, Strategy::GroupCount] {
+            let solver = Solver::new(&guessable, &solutions, false, false, strategy);
+            assert_eq!(
+                solver.suggest_within(Duration::from_secs(60)),
+                (solver.next_guess(), true)
+            );
+
+            let (guess, complete) = solver.suggest_within(Duration::ZERO);
+            assert!(!complete);
+            assert!(guessable.iter().chain(solutions.iter()).any(|w| w == guess));
+        }
+
+        // Letters outside 'a' through 'z' count too.
+        let solutions = words(&["señor", "niños", "leños", "dueño", "sueño"]);
+        let solver = Solver::new(&[], &solutions, false, false, Strategy::GroupSize);
+        assert_eq!(
+            solver.suggest_within(Duration::from_secs(60)),
+            (solver.next_guess(), true)
+        );
+    }
+
+    #[test]
+    fn test_possibility_probabilities() {
+        let solutions = words(&["cigar", "rebut", "sissy", "humph"]);
+        let mut solver = Solver::new(&[], &solutions, false, false, Strategy::GroupSize);
+        assert_eq!(solver.probability("rebut"), 0.25);
+
+        let priors = HashMap::from([("cigar".to_string(), 3.0), ("rebut".to_string(), 1.0)]);
+        solver.set_priors(&priors);
+        assert_eq!(
+            solver.possibility_probabilities(),
+            vec![
+                ("cigar", 0.75),
+                ("rebut", 0.25),
+                ("sissy", 0.0),
+                ("humph", 0.0)
+            ]
+        );
+
+        // If no remaining possibility has any weight, fall back to uniform.
+        solver.respond_to_score("humph", compute_score("humph", "humph"));
+        assert_eq!(solver.possibility_probabilities(), vec![("humph", 1.0)]);
+    }
+
+    #[test]
+    fn test_add_remove_possibility() {
+        let guessable = words(&["crimp"]);
+        let solutions = words(&["cigar", "rebut", "sissy"]);
+        let mut solver = Solver::new(&guessable, &solutions, false, false, Strategy::GroupSize);
+
+        assert_eq!(
+            solver.remove_possibility("squid"),
+            Err(SolverError::UnknownWord("squid".to_string()))
+        );
+        assert_eq!(solver.remove_possibility("rebut"), Ok(()));
+        assert_eq!(solver.get_possibilities(), ["cigar", "sissy"]);
+
+        solver.respond_to_score("rebut", compute_score("rebut", "cigar"));
+        assert_eq!(
+            solver.remove_possibility("cigar"),
+            Err(SolverError::NoPossibilitiesLeft)
+        );
+
+        assert_eq!(solver.add_possibility("crimp"), Ok(()));
+        assert_eq!(solver.get_possibilities(), ["cigar", "crimp"]);
+        assert_eq!(
+            solver.add_possibility("rebut"),
+            Err(SolverError::Inconsistent {
+                word: "rebut".to_string(),
+                guess: "rebut".to_string(),
+                score: compute_score("rebut", "cigar"),
+            })
+        );
+    }
+
+    #[test]
+    fn test_max_guesses() {
+        // "films" tells apart most of these, but none of them can be the answer.
+        let guessable = words(&["films"]);
+        let solutions = words(&["fight", "light", "might", "night", "sight", "tight"]);
+        let mut solver = Solver::new(&guessable, &solutions, false, false, Strategy::GroupSize);
+        assert_eq!(solver.next_guess(), "films");
+
+        // With one guess left, only a possibility can win.
+        solver.set_max_guesses(1);
+        assert_eq!(solver.guesses_remaining(), Some(1));
+        assert!(solver.get_possibilities().contains(&solver.next_guess()));
+
+        solver.respond_to_score("night", compute_score("night", "tight"));
+        assert_eq!(solver.guesses_remaining(), Some(0));
+    }
+
+    #[test]
+    fn test_explain_guess() {
+        let guessable = words(&["films", "crane", "tonic"]);
+        let solutions = words(&["fight", "light", "might", "night", "sight", "tight"]);
+        let mut solver = Solver::new(&guessable, &solutions, false, false, Strategy::GroupSize);
+
+        let explanation = solver.explain_guess("films");
+        assert_eq!(explanation.stats, solver.stats("films"));
+        let runners_up: Vec<&str> = explanation.runners_up.iter().map(|(w, _)| *w).collect();
+        assert_eq!(runners_up.len(), RUNNER_UP_COUNT);
+        assert!(!runners_up.contains(&"films"));
+
+        // Runners-up are the best of the rest, in the strategy's order.
+        let ranked = solver.ranked_guesses();
+        assert_eq!(ranked[0], "films");
+        assert_eq!(runners_up, ranked[1..=RUNNER_UP_COUNT]);
+        let evals: Vec<_> = ranked.iter().map(|w| solver.strategy_eval(w)).collect();
+        assert!(evals.windows(2).all(|pair| pair[0] >= pair[1]));
+
+        // A restricted list can repeat words, and possibilities, but each is only ranked once.
+        let restricted = words(&["tonic", "fight", "tonic"]);
+        solver.restrict_guesses(&restricted);
+        let ranked = solver.ranked_guesses();
+        assert_eq!(ranked.len(), solutions.len() + 1);
+        assert_eq!(ranked.iter().filter(|w| **w == "tonic").count(), 1);
+        assert_eq!(ranked.iter().filter(|w| **w == "fight").count(), 1);
+    }
+
+    #[test]
+    fn test_restrict_guesses() {
+        let guessable = words(&["films", "crane"]);
+        let solutions = words(&["fight", "light", "might", "night", "sight", "tight"]);
+        let restricted = words(&["crane"]);
+        let mut solver = Solver::new(&guessable, &solutions, false, false, Strategy::GroupSize);
+        assert_eq!(solver.next_guess(), "films");
+
+        // Without "films", the best guess is a possibility, which stays guessable.
+        solver.restrict_guesses(&restricted);
+        assert_ne!(solver.next_guess(), "films");
+        let ranked = solver.ranked_guesses();
+        assert!(!ranked.contains(&"films"));
+        assert!(solutions.iter().all(|word| ranked.contains(&word.as_str())));
+
+        // Solutions outside the restricted list are still possibilities.
+        solver.respond_to_score("crane", compute_score("crane", "light"));
+        assert_eq!(
+            solver.get_possibilities(),
+            ["fight", "light", "might", "sight", "tight"]
+        );
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let guessable = words(&["films", "crane", "tonic"]);
+        let solutions = words(&["fight", "light", "might", "night", "sight", "tight"]);
+        let shuffled_guessable = words(&["tonic", "crane", "films", "crane"]);
+        let shuffled_solutions = words(&[
+            "sight", "tight", "might", "fight", "night", "sight", "light",
+        ]);
+        let mut solver = Solver::new(&guessable, &solutions, false, false, Strategy::GroupSize);
+        let mut shuffled = Solver::new(
+            &shuffled_guessable,
+            &shuffled_solutions,
+            false,
+            false,
+            Strategy::GroupSize,
+        );
+
+        // Equally good guesses come in list order, so the order of the lists matters.
+        assert_ne!(solver.ranked_guesses(), shuffled.ranked_guesses());
+
+        solver.set_deterministic(true);
+        shuffled.set_deterministic(true);
+        for (guess, answer) in [("crane", "light"), ("tonic", "light")] {
+            assert_eq!(solver.next_guess(), shuffled.next_guess());
+            assert_eq!(solver.ranked_guesses(), shuffled.ranked_guesses());
+            solver.respond_to_score(guess, compute_score(guess, answer));
+            shuffled.respond_to_score(guess, compute_score(guess, answer));
+        }
+        assert_eq!(solver.get_possibilities(), shuffled.get_possibilities());
+        assert_eq!(solver.next_guess(), shuffled.next_guess());
+        assert_eq!(solver.ranked_guesses(), shuffled.ranked_guesses());
+    }
+
+    #[test]
+    fn test_with_single_list() {
+        let list = words(&["cigar", "rebut", "sissy", "humph"]);
+        let mut solver = Solver::with_single_list(&list, false, false, Strategy::GroupSize);
+        assert_eq!(
+            solver.get_possibilities(),
+            ["cigar", "rebut", "sissy", "humph"]
+        );
+        assert!(list.iter().any(|word| word == solver.next_guess()));
+
+        solver.respond_to_score("humph", compute_score("humph", "rebut"));
+        assert_eq!(solver.get_possibilities(), ["rebut"]);
+    }
+
+    #[test]
+    fn test_mark_unguessable() {
+        let solutions = words(&["cigar", "rebut", "sissy"]);
+        let mut solver = Solver::new(&[], &solutions, false, false, Strategy::GroupSize);
+
+        assert_eq!(solver.mark_unguessable("rebut"), Ok(()));
+        assert_eq!(solver.get_possibilities(), ["cigar", "sissy"]);
+        assert_ne!(solver.next_guess(), "rebut");
+
+        // Rejecting the last possibility is an error, and leaves it to be guessed.
+        solver.respond_to_score("sissy", compute_score("sissy", "cigar"));
+        assert_eq!(
+            solver.mark_unguessable("cigar"),
+            Err(SolverError::NoPossibilitiesLeft)
+        );
+        assert_eq!(solver.get_possibilities(), ["cigar"]);
+        assert_eq!(solver.next_guess(), "cigar");
+    }
+
+    #[test]
+    fn test_respond_to_partial_score() {
+        let solutions = words(&["cigar", "rebut", "sissy", "humph", "awake", "blush"]);
+        let mut solver = Solver::new(&[], &solutions, false, false, Strategy::GroupSize);
+
+        // "sissy" scores "aaaca" against "blush" and "aaaaa" against three others.
+        solver.respond_to_partial_score("sissy", "aaa?a".parse().unwrap());
+        assert_eq!(
+            solver.get_possibilities(),
+            ["rebut", "humph", "awake", "blush"]
+        );
+        assert!(solver.history().is_empty());
+    }
+
+    #[test]
+    fn test_partial_score_uses_a_guess() {
+        let solutions = words(&["cigar", "rebut", "sissy", "humph", "awake", "blush"]);
+        let mut solver = Solver::new(&[], &solutions, false, false, Strategy::GroupSize);
+        solver.set_max_guesses(3);
+        solver.respond_to_partial_score("sissy", "aaa?a".parse().unwrap());
+        assert_eq!(solver.guesses_used(), 1);
+        assert_eq!(solver.guesses_remaining(), Some(2));
+
+        // The full score after it is the second guess, for `verify_answer` too.
+        solver.respond_to_score("rebut", compute_score("rebut", "humph"));
+        assert_eq!(solver.guesses_remaining(), Some(1));
+        assert_eq!(solver.verify_answer("awake")[0].turn, 2);
+    }
+
+    #[test]
+    fn test_verify_answer() {
+        let solutions = words(&["cigar", "rebut", "sissy", "humph", "awake", "blush"]);
+        let mut solver = Solver::new(&[], &solutions, false, false, Strategy::GroupSize);
+        solver.respond_to_score("sissy", compute_score("sissy", "rebut"));
+        solver.respond_to_score("rebut", compute_score("rebut", "humph"));
+
+        assert_eq!(solver.verify_answer("humph"), []);
+        assert_eq!(
+            solver.verify_answer("awake"),
+            [Mismatch {
+                turn: 2,
+                guess: "rebut",
+                recorded: compute_score("rebut", "humph"),
+                expected: compute_score("rebut", "awake"),
+            }]
+        );
+    }
+}
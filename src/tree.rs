@@ -0,0 +1,542 @@
+//! Decision trees: complete plans for playing a game, giving the guess to make after every
+//! sequence of scores. Trees can be built from a solver's choices (see `DecisionTree::build`).
+//!
+//! Trees are read and written in the text format used by other solvers: a line per answer,
+//! listing each guess and its score until the answer is guessed, e.g. `salet BBBYB trice GGGGG`.
+//! Scores can be written in any notation `DetailScore` accepts, in either case, and may be
+//! followed by a number (some tools append the number of guesses to the last one). A line may
+//! start with spaces in place of the beginning of the line before it, so that shared guesses are
+//! only written once:
+//!
+//! ```text
+//! salet BBBBB courd BBYBB nymph GGGGG
+//!                   GGGGG
+//! ```
+
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::io::{BufRead, Error, ErrorKind, Write};
+
+use crate::score::{is_valid_word, DetailScore, ScoringRules};
+use crate::solver::Solver;
+
+/// A guess, and the subtrees to follow for each score it can get. Winning scores have no subtree.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecisionTree {
+    pub guess: String,
+
+    /// Whether the guess is the answer in some of the games that reach it, i.e. whether it can
+    /// get a winning score.
+    pub wins: bool,
+    pub children: BTreeMap<DetailScore, DecisionTree>,
+}
+
+impl DecisionTree {
+    pub fn new(guess: &str) -> DecisionTree {
+        DecisionTree {
+            guess: guess.to_string(),
+            wins: false,
+            children: BTreeMap::new(),
+        }
+    }
+
+    /// Expand a solver's choices into a tree covering every remaining possibility, by guessing
+    /// what `next_guess` returns after each score. Fails if the solver makes a guess that doesn't
+    /// narrow down the possibilities, since the tree would never end.
+    pub fn build(solver: &Solver) -> Result<DecisionTree, String> {
+        let guess = solver.next_guess();
+        let mut tree = DecisionTree::new(guess);
+        let rules = solver.scoring_rules();
+        for (score, possibilities) in rules.partition(guess, solver.get_possibilities()) {
+            if score.is_win() {
+                tree.wins = true;
+                continue;
+            }
+            if possibilities.len() == solver.get_possibilities().len() {
+                let mut possibilities = possibilities;
+                possibilities.sort_unstable();
+                return Err(format!(
+                    "{} doesn't narrow down the possibilities ({})",
+                    guess,
+                    possibilities.join(", ")
+                ));
+            }
+            let mut child = solver.clone();
+            child.respond_to_score(guess, score);
+            tree.children.insert(score, DecisionTree::build(&child)?);
+        }
+        Ok(tree)
+    }
+
+    /// Read a tree in the text format described in the module documentation.
+    pub fn read(reader: impl BufRead) -> Result<DecisionTree, Error> {
+        let mut tree: Option<DecisionTree> = None;
+        let mut previous = String::new();
+
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            let invalid = |problem: String| {
+                let msg = format!("Line {}: {}", index + 1, problem);
+                Error::new(ErrorKind::InvalidData, msg)
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            // Fill in the indentation from the line before.
+            let rest = line.trim_start_matches(' ');
+            let indent = line.len() - rest.len();
+            let line = if indent > 0 {
+                let prefix: String = previous.chars().take(indent).collect();
+                if prefix.chars().count() < indent {
+                    return Err(invalid(
+                        "Indented past the end of the line before".to_string(),
+                    ));
+                }
+                prefix + rest
+            } else {
+                line
+            };
+
+            let path = parse_path(&line).map_err(invalid)?;
+            match tree {
+                None => tree = Some(DecisionTree::new(&path[0].0)),
+                Some(ref tree) if tree.guess != path[0].0 => {
+                    return Err(invalid(format!(
+                        "Starts with {}, but earlier lines start with {}",
+                        path[0].0, tree.guess
+                    )));
+                }
+                Some(_) => {}
+            }
+            tree.as_mut().unwrap().insert(&path).map_err(invalid)?;
+            previous = line;
+        }
+
+        tree.ok_or_else(|| Error::new(ErrorKind::InvalidData, "The tree is empty"))
+    }
+
+    /// Add a path, a guess and score at a time, ending with a win, that starts at this node.
+    fn insert(&mut self, path: &[(String, DetailScore)]) -> Result<(), String> {
+        let mut node = self;
+        for (i, (guess, score)) in path.iter().enumerate() {
+            if node.guess != *guess {
+                return Err(format!(
+                    "Guesses {} after {}, but an earlier line guesses {}",
+                    guess,
+                    describe_path(&path[..i]),
+                    node.guess
+                ));
+            }
+            let Some((next_guess, _)) = path.get(i + 1) else {
+                node.wins = true;
+                break;
+            };
+            node = node
+                .children
+                .entry(*score)
+                .or_insert_with(|| DecisionTree::new(next_guess));
+        }
+        Ok(())
+    }
+
+    /// Number of answers this tree solves: how many of its guesses can win.
+    pub fn answer_count(&self) -> usize {
+        self.wins as usize
+            + self
+                .children
+                .values()
+                .map(DecisionTree::answer_count)
+                .sum::<usize>()
+    }
+
+    /// Average number of guesses over the answers this tree solves, counting its own guess, with
+    /// every answer equally likely.
+    pub fn average_guesses(&self) -> f64 {
+        self.total_guesses() as f64 / self.answer_count() as f64
+    }
+
+    /// Total number of guesses over the answers this tree solves.
+    fn total_guesses(&self) -> usize {
+        self.wins as usize
+            + self
+                .children
+                .values()
+                .map(|child| child.total_guesses() + child.answer_count())
+                .sum::<usize>()
+    }
+
+    /// The lines of the text format described in the module documentation: one per answer,
+    /// in order of score, with scores in uppercase "BYG" letters and the number of guesses after
+    /// the last one. With `indent`, the guesses and scores a line shares with the line before are
+    /// replaced by spaces.
+    pub fn text_lines(&self, indent: bool) -> Vec<String> {
+        let mut paths = Vec::new();
+        self.collect_paths(&mut Vec::new(), &mut paths);
+
+        let mut lines = Vec::new();
+        let mut previous: Vec<String> = Vec::new();
+        for path in paths {
+            let mut tokens: Vec<String> = Vec::new();
+            for (guess, score) in path.iter() {
+                tokens.push(guess.to_string());
+                tokens.push(score.to_color_letters().to_uppercase());
+            }
+            tokens.last_mut().unwrap().push_str(&path.len().to_string());
+
+            // Never share the last score, so every line shows how its game ends.
+            let shared = if indent {
+                tokens
+                    .iter()
+                    .zip(previous.iter())
+                    .take(tokens.len() - 1)
+                    .take_while(|(a, b)| a == b)
+                    .count()
+            } else {
+                0
+            };
+            let line = if shared > 0 {
+                let width = tokens[..shared].join(" ").chars().count() + 1;
+                format!("{}{}", " ".repeat(width), tokens[shared..].join(" "))
+            } else {
+                tokens.join(" ")
+            };
+            lines.push(line);
+            previous = tokens;
+        }
+        lines
+    }
+
+    /// Add to `paths` every path, a guess and score at a time, from the root to a winning guess
+    /// in this subtree. `prefix` is the path from the root to this node.
+    fn collect_paths<'t>(
+        &'t self,
+        prefix: &mut Vec<(&'t str, DetailScore)>,
+        paths: &mut Vec<Vec<(&'t str, DetailScore)>>,
+    ) {
+        let mut scores: Vec<(DetailScore, Option<&DecisionTree>)> =
+            self.children.iter().map(|(s, c)| (*s, Some(c))).collect();
+        if self.wins {
+            scores.push((DetailScore::win(), None));
+        }
+        for (score, child) in scores {
+            prefix.push((&self.guess, score));
+            match child {
+                Some(child) => child.collect_paths(prefix, paths),
+                None => paths.push(prefix.clone()),
+            }
+            prefix.pop();
+        }
+    }
+
+    /// Write the tree in Graphviz's DOT language, with guesses on the nodes (doubly outlined if
+    /// they can win) and scores on the edges. Subtrees deeper than `max_depth` guesses, or past
+    /// the first `max_nodes` nodes (counting from the top), are drawn as a single node with the
+    /// number of answers they solve.
+    pub fn write_dot(
+        &self,
+        out: &mut impl Write,
+        max_depth: Option<usize>,
+        max_nodes: Option<usize>,
+    ) -> std::io::Result<()> {
+        writeln!(out, "digraph wordle {{")?;
+        writeln!(out, "  node [shape=box, fontname=monospace];")?;
+        writeln!(out, "  edge [fontname=monospace];")?;
+
+        // Breadth first, so that the node limit cuts off the deepest parts of the tree.
+        let mut queue = VecDeque::from([(self, 0, 1)]);
+        let mut next_id = 1;
+        while let Some((node, id, depth)) = queue.pop_front() {
+            let peripheries = if node.wins { 2 } else { 1 };
+            writeln!(
+                out,
+                "  n{} [label=\"{}\", peripheries={}];",
+                id, node.guess, peripheries
+            )?;
+            for (score, child) in node.children.iter() {
+                let child_id = next_id;
+                next_id += 1;
+                writeln!(out, "  n{} -> n{} [label=\"{}\"];", id, child_id, score)?;
+                let elide = max_depth.is_some_and(|max| depth >= max)
+                    || max_nodes.is_some_and(|max| child_id >= max);
+                if elide {
+                    writeln!(
+                        out,
+                        "  n{} [label=\"{} answers\", shape=plaintext];",
+                        child_id,
+                        child.answer_count()
+                    )?;
+                } else {
+                    queue.push_back((child, child_id, depth + 1));
+                }
+            }
+        }
+        writeln!(out, "}}")
+    }
+
+    /// Every guess in the tree, each once.
+    pub fn guesses(&self) -> HashSet<&str> {
+        let mut result = HashSet::from([self.guess.as_str()]);
+        for child in self.children.values() {
+            result.extend(child.guesses());
+        }
+        result
+    }
+
+    /// The guesses and scores of the game with this answer, following the tree, or `None` if
+    /// the tree has no guess for one of the scores.
+    pub fn play(&self, answer: &str, rules: ScoringRules) -> Option<Vec<(&str, DetailScore)>> {
+        let mut node = self;
+        let mut moves = Vec::new();
+        loop {
+            let score = rules.compute_score(&node.guess, answer);
+            moves.push((node.guess.as_str(), score));
+            if score.is_win() {
+                return Some(moves);
+            }
+            node = node.children.get(&score)?;
+        }
+    }
+}
+
+/// Parse a line of the text format into guesses and scores. Only the last score may be a win.
+fn parse_path(line: &str) -> Result<Vec<(String, DetailScore)>, String> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    if !tokens.len().is_multiple_of(2) {
+        return Err("Expected pairs of a guess and a score".to_string());
+    }
+
+    let mut path = Vec::new();
+    for pair in tokens.chunks_exact(2) {
+        let guess = pair[0].to_lowercase();
+        if !is_valid_word(&guess) {
+            return Err(format!("Invalid guess: {}", pair[0]));
+        }
+        let score = pair[1]
+            .trim_end_matches(|c: char| c.is_ascii_digit())
+            .to_lowercase()
+            .parse::<DetailScore>()
+            .map_err(|e| format!("{}: {}", pair[1], e))?;
+        path.push((guess, score));
+    }
+
+    match path.iter().position(|(_, score)| score.is_win()) {
+        Some(i) if i + 1 == path.len() => Ok(path),
+        Some(_) => Err("Continues after a winning score".to_string()),
+        None => Err("Doesn't end with a winning score".to_string()),
+    }
+}
+
+fn describe_path(path: &[(String, DetailScore)]) -> String {
+    if path.is_empty() {
+        return "the start".to_string();
+    }
+    let moves: Vec<String> = path.iter().map(|(g, s)| format!("{} {}", g, s)).collect();
+    moves.join(" ")
+}
+
+/// How a tree does over a solution list. See `evaluate`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TreeReport {
+    /// Number of guesses each answer took, in solution list order, or `None` if the tree
+    /// doesn't solve it.
+    pub guess_counts: Vec<Option<usize>>,
+
+    /// Reasons the tree isn't valid for the word lists. Empty if it is.
+    pub problems: Vec<String>,
+}
+
+impl TreeReport {
+    /// Average number of guesses over the answers the tree solves.
+    pub fn expected_guesses(&self) -> Option<f64> {
+        let solved: Vec<usize> = self.guess_counts.iter().flatten().copied().collect();
+        if solved.is_empty() {
+            return None;
+        }
+        Some(solved.iter().sum::<usize>() as f64 / solved.len() as f64)
+    }
+
+    /// Most guesses any answer took.
+    pub fn worst_case(&self) -> Option<usize> {
+        self.guess_counts.iter().flatten().copied().max()
+    }
+
+    /// Positions of the answers that took more than `max_guesses`, or weren't solved.
+    pub fn failures(&self, max_guesses: usize) -> Vec<usize> {
+        self.guess_counts
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| count.is_none_or(|n| n > max_guesses))
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+/// Play every answer in `solutions` by following the tree, checking that every guess is in the
+/// word lists (and legal in hard mode, if set) and that the tree has a branch for every score.
+pub fn evaluate(
+    tree: &DecisionTree,
+    guessable: &[String],
+    solutions: &[String],
+    rules: ScoringRules,
+    hard_mode: bool,
+) -> TreeReport {
+    let mut report = TreeReport::default();
+
+    let words: HashSet<&str> = guessable
+        .iter()
+        .chain(solutions.iter())
+        .map(String::as_str)
+        .collect();
+    let mut unknown: Vec<&str> = tree
+        .guesses()
+        .into_iter()
+        .filter(|guess| !words.contains(guess))
+        .collect();
+    unknown.sort_unstable();
+    for guess in unknown {
+        report
+            .problems
+            .push(format!("{} isn't in the word lists", guess));
+    }
+
+    for answer in solutions {
+        let mut node = tree;
+        let mut history: Vec<(&str, DetailScore)> = Vec::new();
+        let count = loop {
+            if hard_mode && !rules.is_hard_mode_legal(&history, &node.guess) {
+                report.problems.push(format!(
+                    "{}: guessing {} isn't allowed in hard mode",
+                    answer, node.guess
+                ));
+                break None;
+            }
+            let score = rules.compute_score(&node.guess, answer);
+            history.push((&node.guess, score));
+            if score.is_win() {
+                break Some(history.len());
+            }
+            match node.children.get(&score) {
+                Some(child) => node = child,
+                None => {
+                    let moves: Vec<String> = history
+                        .iter()
+                        .map(|(g, s)| format!("{} {}", g, s))
+                        .collect();
+                    report
+                        .problems
+                        .push(format!("{}: no guess after {}", answer, moves.join(" ")));
+                    break None;
+                }
+            }
+        };
+        report.guess_counts.push(count);
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::Strategy;
+
+    #[test]
+    fn test_read_tree() {
+        let text = concat!(
+            "crate AAPAA cigar GGGGG\n",
+            "                  BBBBB humph GGGGG3\n",
+            "crate ggggg\n"
+        );
+        let tree = DecisionTree::read(text.as_bytes()).unwrap();
+        assert_eq!(tree.guess, "crate");
+        assert_eq!(tree.children.len(), 1);
+        let cigar = &tree.children[&"aapaa".parse().unwrap()];
+        assert_eq!(cigar.guess, "cigar");
+        assert_eq!(cigar.children[&"aaaaa".parse().unwrap()].guess, "humph");
+
+        let error = DecisionTree::read("crate aapaa cigar ccccc\nslate ccccc\n".as_bytes());
+        assert!(error
+            .unwrap_err()
+            .to_string()
+            .starts_with("Line 2: Starts with slate"));
+        assert!(DecisionTree::read("crate aapaa\n".as_bytes()).is_err());
+        assert!(DecisionTree::read("crate ccccc cigar ccccc\n".as_bytes()).is_err());
+        assert!(DecisionTree::read("crate aapaa cigar\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_build() {
+        let solutions = ["cigar", "rebut", "sissy", "humph", "awake"].map(String::from);
+        let solver = Solver::new(&[], &solutions, false, false, Strategy::GroupSize);
+        let tree = DecisionTree::build(&solver).unwrap();
+        assert_eq!(tree.answer_count(), solutions.len());
+
+        let report = evaluate(&tree, &[], &solutions, ScoringRules::Wordle, false);
+        assert!(report.problems.is_empty());
+        assert_eq!(report.failures(6), Vec::<usize>::new());
+        assert_eq!(Some(tree.average_guesses()), report.expected_guesses());
+
+        let mut dot = Vec::new();
+        tree.write_dot(&mut dot, Some(1), None).unwrap();
+        let dot = String::from_utf8(dot).unwrap();
+        assert!(dot.starts_with("digraph wordle {"));
+        assert!(dot.contains(&format!("n0 [label=\"{}\"", tree.guess)));
+        assert!(dot.contains("answers\", shape=plaintext"));
+    }
+
+    #[test]
+    fn test_build_stuck() {
+        // The game rejected every anagram as a guess, but they were added back as possibilities.
+        // The only guess left scores the same against all of them.
+        let guessable = ["tales".to_string()];
+        let solutions = ["least", "stale", "slate"].map(String::from);
+        let mut solver = Solver::new(&guessable, &solutions, false, false, Strategy::GroupSize);
+        solver.set_scoring_rules(ScoringRules::AllPresent);
+        solver.mark_unguessable("least").unwrap();
+        solver.mark_unguessable("stale").unwrap();
+        solver.add_possibility("least").unwrap();
+        solver.add_possibility("stale").unwrap();
+        solver.mark_unguessable("slate").unwrap();
+        assert_eq!(
+            DecisionTree::build(&solver),
+            Err("tales doesn't narrow down the possibilities (least, stale)".to_string())
+        );
+    }
+
+    #[test]
+    fn test_text_lines() {
+        let text = "crate cppaa cigar ccccc\ncrate cppaa cigar aaaaa humph ccccc\ncrate ccccc\n";
+        let tree = DecisionTree::read(text.as_bytes()).unwrap();
+        assert_eq!(
+            tree.text_lines(false),
+            [
+                "crate GYYBB cigar BBBBB humph GGGGG3",
+                "crate GYYBB cigar GGGGG2",
+                "crate GGGGG1",
+            ]
+        );
+        let indented = tree.text_lines(true);
+        assert_eq!(indented[1], "                  GGGGG2");
+        assert_eq!(indented[2], "      GGGGG1");
+
+        let reread = DecisionTree::read(indented.join("\n").as_bytes()).unwrap();
+        assert_eq!(reread, tree);
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let text = "crate cppaa cigar ccccc\ncrate ccccc\n";
+        let tree = DecisionTree::read(text.as_bytes()).unwrap();
+        let solutions = ["cigar", "crate", "humph"].map(String::from);
+        let report = evaluate(&tree, &[], &solutions, ScoringRules::Wordle, false);
+        assert_eq!(report.guess_counts, [Some(2), Some(1), None]);
+        assert_eq!(report.expected_guesses(), Some(1.5));
+        assert_eq!(report.worst_case(), Some(2));
+        assert_eq!(report.failures(1), [0, 2]);
+        assert_eq!(report.problems, ["humph: no guess after crate aaaaa"]);
+
+        let moves = tree.play("cigar", ScoringRules::Wordle).unwrap();
+        assert_eq!(moves[1], ("cigar", "ccccc".parse().unwrap()));
+        assert_eq!(tree.play("humph", ScoringRules::Wordle), None);
+    }
+}
@@ -0,0 +1,336 @@
+use std::fs::File;
+use std::io::{stderr, stdout, BufReader, BufWriter, Write};
+
+use argparse::{ArgumentParser, Parse, Store, StoreOption, StoreTrue};
+
+use wordle_solver::loader::load_list_from_file;
+use wordle_solver::prove::Prover;
+use wordle_solver::score::ScoringRules;
+use wordle_solver::solver::{Solver, Strategy};
+use wordle_solver::tree::{evaluate, DecisionTree};
+
+fn exit_on_error<T>(path: &str, result: std::io::Result<T>) -> T {
+    result.unwrap_or_else(|e| {
+        eprintln!("{}: {}", path, e);
+        std::process::exit(1);
+    })
+}
+
+/// `wordle-solver eval-tree`: check a decision tree against the word lists, and report how many
+/// guesses it takes.
+pub fn eval_command(args: Vec<String>) {
+    let mut guessable_path = String::new();
+    let mut solutions_path = String::new();
+    let mut tree_path = String::new();
+    let mut hard_mode = false;
+    let mut max_guesses = 6;
+    let mut scoring_rules = ScoringRules::Wordle;
+
+    {
+        let mut parser = ArgumentParser::new();
+        parser.set_description(concat!(
+            "Check that a decision tree solves every answer, and report its expected and ",
+            "worst-case number of guesses. The tree has a line per answer, listing each guess ",
+            "and its score, e.g. 'salet BBBYB trice GGGGG'."
+        ));
+        parser.refer(&mut guessable_path).required().add_argument(
+            "guessable",
+            Store,
+            "The guessable list",
+        );
+        parser.refer(&mut solutions_path).required().add_argument(
+            "solutions",
+            Store,
+            "The solutions list",
+        );
+        parser
+            .refer(&mut tree_path)
+            .required()
+            .add_argument("tree", Store, "The decision tree");
+        parser.refer(&mut hard_mode).add_option(
+            &["--hard-mode"],
+            StoreTrue,
+            "Check that every guess is allowed in hard mode",
+        );
+        parser.refer(&mut max_guesses).add_option(
+            &["--max-guesses"],
+            Store,
+            "Answers taking more guesses than this are failures (default 6)",
+        );
+        parser.refer(&mut scoring_rules).add_option(
+            &["--scoring-rules"],
+            Parse,
+            "How guesses are scored: wordle (default) or all-present",
+        );
+        if let Err(code) = parser.parse(args, &mut stdout(), &mut stderr()) {
+            std::process::exit(code);
+        }
+    }
+
+    let [guessable, solutions] = [&guessable_path, &solutions_path]
+        .map(|path| exit_on_error(path, load_list_from_file(path.as_ref())));
+    let file = exit_on_error(&tree_path, File::open(&tree_path));
+    let tree = exit_on_error(&tree_path, DecisionTree::read(BufReader::new(file)));
+
+    let report = evaluate(&tree, &guessable, &solutions, scoring_rules, hard_mode);
+    for problem in report.problems.iter() {
+        eprintln!("{}", problem);
+    }
+    let failures = report.failures(max_guesses);
+
+    println!("Answers:          {}", solutions.len());
+    if let Some(expected) = report.expected_guesses() {
+        println!("Expected guesses: {:.4}", expected);
+    }
+    if let Some(worst) = report.worst_case() {
+        println!("Worst case:       {}", worst);
+    }
+    println!(
+        "Failures (unsolved or more than {} guesses): {}",
+        max_guesses,
+        failures.len()
+    );
+    if !failures.is_empty() {
+        let words: Vec<&str> = failures.iter().map(|i| solutions[*i].as_str()).collect();
+        println!("  {}", words.join(" "));
+    }
+
+    if !report.problems.is_empty() || !failures.is_empty() {
+        std::process::exit(1);
+    }
+}
+
+/// How `export-tree` writes the tree.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum TreeFormat {
+    /// A Graphviz graph (see `DecisionTree::write_dot`).
+    Dot,
+
+    /// The text format other solvers use, which `eval-tree` reads (see
+    /// `DecisionTree::text_lines`).
+    Text,
+}
+
+impl argparse::FromCommandLine for TreeFormat {
+    fn from_argument(s: &str) -> Result<Self, String> {
+        match s {
+            "dot" => Ok(Self::Dot),
+            "text" => Ok(Self::Text),
+            _ => Err("tree formats are 'dot' and 'text'".to_string()),
+        }
+    }
+}
+
+/// `wordle-solver export-tree`: write out the decision tree that the solver follows.
+pub fn export_command(args: Vec<String>) {
+    let mut guessable_path = String::new();
+    let mut solutions_path = String::new();
+    let mut strategy = Strategy::GroupSize;
+    let mut hard_mode = false;
+    let mut scoring_rules = ScoringRules::Wordle;
+    let mut max_depth: Option<usize> = None;
+    let mut max_nodes: Option<usize> = Some(1000);
+    let mut output_path: Option<String> = None;
+    let mut format = TreeFormat::Dot;
+    let mut full_lines = false;
+
+    {
+        let mut parser = ArgumentParser::new();
+        parser.set_description(concat!(
+            "Expand the solver's choices into a decision tree over every answer, and write it as ",
+            "a Graphviz graph, with guesses on the nodes and scores on the edges, or in the text ",
+            "format other solvers use"
+        ));
+        parser.refer(&mut guessable_path).required().add_argument(
+            "guessable",
+            Store,
+            "The guessable list",
+        );
+        parser.refer(&mut solutions_path).required().add_argument(
+            "solutions",
+            Store,
+            "The solutions list",
+        );
+        parser.refer(&mut strategy).add_option(
+            &["--strategy"],
+            Parse,
+            "Which solving strategy to use: groupcount or groupsize (default)",
+        );
+        parser.refer(&mut hard_mode).add_option(
+            &["--hard-mode"],
+            StoreTrue,
+            "Only guess words that are possible solutions",
+        );
+        parser.refer(&mut scoring_rules).add_option(
+            &["--scoring-rules"],
+            Parse,
+            "How guesses are scored: wordle (default) or all-present",
+        );
+        parser.refer(&mut format).add_option(
+            &["--format"],
+            Parse,
+            "dot (default): a Graphviz graph; text: a line per answer, as eval-tree reads",
+        );
+        parser.refer(&mut full_lines).add_option(
+            &["--full-lines"],
+            StoreTrue,
+            "With --format text, don't replace guesses shared with the line before by spaces",
+        );
+        parser.refer(&mut max_depth).add_option(
+            &["--max-depth"],
+            StoreOption,
+            "With --format dot, only draw this many guesses deep; deeper subtrees are summarized",
+        );
+        parser.refer(&mut max_nodes).add_option(
+            &["--max-nodes"],
+            StoreOption,
+            "With --format dot, only draw this many nodes (default 1000); the rest are summarized",
+        );
+        parser.refer(&mut output_path).add_option(
+            &["--output", "-o"],
+            StoreOption,
+            "Write to this file instead of stdout",
+        );
+        if let Err(code) = parser.parse(args, &mut stdout(), &mut stderr()) {
+            std::process::exit(code);
+        }
+    }
+
+    let [guessable, solutions] = [&guessable_path, &solutions_path]
+        .map(|path| exit_on_error(path, load_list_from_file(path.as_ref())));
+    let mut solver = Solver::new(&guessable, &solutions, hard_mode, false, strategy);
+    solver.set_scoring_rules(scoring_rules);
+    let tree = DecisionTree::build(&solver).unwrap_or_else(|e| {
+        eprintln!("Can't build a tree: {}", e);
+        std::process::exit(1);
+    });
+
+    let (name, mut out): (&str, Box<dyn Write>) = match output_path {
+        Some(ref path) => {
+            let file = exit_on_error(path, File::create(path));
+            (path, Box::new(BufWriter::new(file)))
+        }
+        None => ("stdout", Box::new(BufWriter::new(stdout()))),
+    };
+    let result = match format {
+        TreeFormat::Dot => tree.write_dot(&mut out, max_depth, max_nodes),
+        TreeFormat::Text => tree
+            .text_lines(!full_lines)
+            .iter()
+            .try_for_each(|line| writeln!(out, "{}", line)),
+    };
+    let result = result.and_then(|_| out.flush());
+    exit_on_error(name, result);
+}
+
+/// The most guesses `prove` looks for a tree within, when no limit is given.
+const MAX_PROVE_GUESSES: usize = 10;
+
+/// `wordle-solver prove`: search for a tree that solves every answer within some number of
+/// guesses.
+pub fn prove_command(args: Vec<String>) {
+    let mut guessable_path = String::new();
+    let mut solutions_path = String::new();
+    let mut max_guesses: Option<usize> = None;
+    let mut candidates = 20;
+    let mut scoring_rules = ScoringRules::Wordle;
+    let mut output_path: Option<String> = None;
+
+    {
+        let mut parser = ArgumentParser::new();
+        parser.set_description(concat!(
+            "Search for a decision tree that solves every answer within a number of guesses, ",
+            "to prove that it can be done. Without --max-guesses, finds the fewest guesses that ",
+            "it can."
+        ));
+        parser.refer(&mut guessable_path).required().add_argument(
+            "guessable",
+            Store,
+            "The guessable list",
+        );
+        parser.refer(&mut solutions_path).required().add_argument(
+            "solutions",
+            Store,
+            "The solutions list",
+        );
+        parser.refer(&mut max_guesses).add_option(
+            &["--max-guesses"],
+            StoreOption,
+            "Only look for a tree that solves every answer within this many guesses",
+        );
+        parser.refer(&mut candidates).add_option(
+            &["--candidates"],
+            Store,
+            concat!(
+                "How many guesses to try at each step, best first (default 20); 0 for all, so ",
+                "that failing to find a tree proves there isn't one"
+            ),
+        );
+        parser.refer(&mut scoring_rules).add_option(
+            &["--scoring-rules"],
+            Parse,
+            "How guesses are scored: wordle (default) or all-present",
+        );
+        parser.refer(&mut output_path).add_option(
+            &["--output", "-o"],
+            StoreOption,
+            "Write the tree to this file, in the format eval-tree reads, to verify the result",
+        );
+        if let Err(code) = parser.parse(args, &mut stdout(), &mut stderr()) {
+            std::process::exit(code);
+        }
+    }
+
+    let [guessable, solutions] = [&guessable_path, &solutions_path]
+        .map(|path| exit_on_error(path, load_list_from_file(path.as_ref())));
+    let mut prover = Prover::new(&guessable, &solutions);
+    prover.set_scoring_rules(scoring_rules);
+    prover.set_candidate_limit(Some(candidates).filter(|n| *n > 0));
+    let possibilities: Vec<&str> = solutions.iter().map(String::as_str).collect();
+
+    let limits = match max_guesses {
+        Some(max) => max..=max,
+        None => 1..=MAX_PROVE_GUESSES,
+    };
+    let mut failed_within = None;
+    let mut found = None;
+    for limit in limits {
+        match prover.solve(&possibilities, limit) {
+            Some(tree) => {
+                found = Some((limit, tree));
+                break;
+            }
+            None => failed_within = Some(limit),
+        }
+    }
+
+    if let Some(limit) = failed_within {
+        let how = if prover.is_exhaustive() {
+            "after trying every guess"
+        } else {
+            "among the best guesses at each step; try more --candidates"
+        };
+        println!(
+            "No tree solves every answer within {} guesses ({})",
+            limit, how
+        );
+    }
+    let Some((limit, tree)) = found else {
+        std::process::exit(1);
+    };
+    println!("Every answer can be solved within {} guesses", limit);
+
+    if let Some(path) = output_path {
+        let mut file = BufWriter::new(exit_on_error(&path, File::create(&path)));
+        let result = tree
+            .text_lines(true)
+            .iter()
+            .try_for_each(|line| writeln!(file, "{}", line))
+            .and_then(|_| file.flush());
+        exit_on_error(&path, result);
+        println!(
+            "Tree written to {}; check it with wordle-solver eval-tree",
+            path
+        );
+    }
+}
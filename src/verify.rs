@@ -0,0 +1,370 @@
+use std::fmt::Display;
+use std::io::{stderr, stdout};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use argparse::{ArgumentParser, Parse, Store, StoreOption};
+
+use wordle_solver::default_thread_count;
+use wordle_solver::loader::load_list_from_file;
+use wordle_solver::score::{
+    compute_score, compute_scores, is_consistent, reference_score, DetailScore, ScoringRules,
+};
+use wordle_solver::solver::{Solver, Strategy};
+
+/// Games taking more guesses than this are assumed to be stuck.
+const MAX_GUESSES: usize = 50;
+
+/// Something that went wrong in a game. See `check_game`.
+#[derive(Debug, PartialEq)]
+enum Violation {
+    /// The answer was no longer a possibility after this many guesses.
+    AnswerEliminated(usize),
+
+    /// In hard mode, this guess contradicted an earlier score.
+    IllegalGuess(usize),
+
+    /// The game wasn't won within `MAX_GUESSES` guesses.
+    Stuck,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::AnswerEliminated(n) => {
+                write!(f, "answer eliminated after guess {}", n)
+            }
+            Violation::IllegalGuess(n) => write!(f, "guess {} isn't allowed in hard mode", n),
+            Violation::Stuck => write!(f, "not solved within {} guesses", MAX_GUESSES),
+        }
+    }
+}
+
+/// Play a game, checking after every guess that the answer is still a possibility and, in hard
+/// mode, that the guess was allowed. Returns the moves made, and the first violation if any.
+fn check_game<'a>(
+    mut solver: Solver<'a>,
+    first_guess: &'a str,
+    answer: &str,
+    hard_mode: bool,
+) -> (Vec<(&'a str, DetailScore)>, Option<Violation>) {
+    let rules = solver.scoring_rules();
+    let mut guess = first_guess;
+
+    for n in 1..=MAX_GUESSES {
+        if hard_mode && !rules.is_hard_mode_legal(solver.history(), guess) {
+            return (solver.history().to_vec(), Some(Violation::IllegalGuess(n)));
+        }
+        let score = rules.compute_score(guess, answer);
+        // The solver can't take a score that leaves it no possibilities, which means it lost the
+        // answer.
+        let possibilities = solver.get_possibilities();
+        if !possibilities
+            .iter()
+            .any(|word| rules.is_consistent(guess, score, word))
+        {
+            let mut moves = solver.history().to_vec();
+            moves.push((guess, score));
+            return (moves, Some(Violation::AnswerEliminated(n)));
+        }
+        solver.respond_to_score(guess, score);
+        if score.is_win() {
+            return (solver.history().to_vec(), None);
+        }
+        if !solver.get_possibilities().contains(&answer) {
+            let violation = Violation::AnswerEliminated(n);
+            return (solver.history().to_vec(), Some(violation));
+        }
+        guess = solver.next_guess();
+    }
+    (solver.history().to_vec(), Some(Violation::Stuck))
+}
+
+/// `wordle-solver verify`: play every answer with every configuration, and check that the solver
+/// never loses track of the answer.
+pub fn command(args: Vec<String>) {
+    let mut guessable_path = String::new();
+    let mut solutions_path = String::new();
+    let mut strategy: Option<Strategy> = None;
+    let mut scoring_rules = ScoringRules::Wordle;
+    let mut thread_count = default_thread_count();
+
+    {
+        let mut parser = ArgumentParser::new();
+        parser.set_description(concat!(
+            "Play every answer with every strategy, in normal and hard mode, and check that the ",
+            "answer stays among the possibilities after every guess, and that every hard mode ",
+            "guess is allowed"
+        ));
+        parser.refer(&mut guessable_path).required().add_argument(
+            "guessable",
+            Store,
+            "The guessable list",
+        );
+        parser.refer(&mut solutions_path).required().add_argument(
+            "solutions",
+            Store,
+            "The solutions list",
+        );
+        parser.refer(&mut strategy).add_option(
+            &["--strategy"],
+            StoreOption,
+            "Only check this strategy: groupcount or groupsize (default: both)",
+        );
+        parser.refer(&mut scoring_rules).add_option(
+            &["--scoring-rules"],
+            Parse,
+            "How guesses are scored: wordle (default) or all-present",
+        );
+        parser.refer(&mut thread_count).add_option(
+            &["--thread-count"],
+            Store,
+            "Number of threads to use (default: one per CPU)",
+        );
+        if let Err(code) = parser.parse(args, &mut stdout(), &mut stderr()) {
+            std::process::exit(code);
+        }
+    }
+
+    let [guessable, solutions] = [guessable_path, solutions_path].map(|path| {
+        load_list_from_file(path.as_ref()).unwrap_or_else(|e| {
+            eprintln!("{}: {}", path, e);
+            std::process::exit(1);
+        })
+    });
+    let strategies = match strategy {
+        Some(strategy) => vec![strategy],
+        None => vec![Strategy::GroupCount, Strategy::GroupSize],
+    };
+
+    let mut violation_count = 0;
+    for strategy in strategies {
+        for hard_mode in [false, true] {
+            let new_solver = || {
+                let mut solver = Solver::new(&guessable, &solutions, hard_mode, false, strategy);
+                solver.set_scoring_rules(scoring_rules);
+                solver
+            };
+            let first_guess = new_solver().next_guess();
+
+            let next_index = AtomicUsize::new(0);
+            let mut violations: Vec<(usize, String)> = std::thread::scope(|scope| {
+                let threads: Vec<_> = (0..thread_count.max(1))
+                    .map(|_| {
+                        scope.spawn(|| {
+                            let mut violations = Vec::new();
+                            loop {
+                                let index = next_index.fetch_add(1, Ordering::Relaxed);
+                                let Some(answer) = solutions.get(index) else {
+                                    break;
+                                };
+                                let (moves, violation) =
+                                    check_game(new_solver(), first_guess, answer, hard_mode);
+                                if let Some(violation) = violation {
+                                    let moves: Vec<String> = moves
+                                        .iter()
+                                        .map(|(guess, score)| format!("{} {}", guess, score))
+                                        .collect();
+                                    let line = format!(
+                                        "  {}: {} ({})",
+                                        answer,
+                                        violation,
+                                        moves.join(", ")
+                                    );
+                                    violations.push((index, line));
+                                }
+                            }
+                            violations
+                        })
+                    })
+                    .collect();
+                threads
+                    .into_iter()
+                    .flat_map(|thread| thread.join().unwrap())
+                    .collect()
+            });
+            violations.sort();
+
+            println!(
+                "{}{}: {} answers, {} violations",
+                strategy.to_string().to_uppercase(),
+                if hard_mode { " (hard mode)" } else { "" },
+                solutions.len(),
+                violations.len()
+            );
+            for (_, line) in violations.iter() {
+                println!("{}", line);
+            }
+            violation_count += violations.len();
+        }
+    }
+
+    if violation_count > 0 {
+        std::process::exit(1);
+    }
+}
+
+/// How many mismatches `verify-scores` lists before summarizing the rest.
+const MAX_LISTED_MISMATCHES: usize = 20;
+
+/// Compare `compute_score`, `compute_scores` and `is_consistent` against `reference_score` for
+/// one guess against every solution. Returns a line per disagreement.
+fn check_scores(guess: &str, solutions: &[&str]) -> Vec<String> {
+    let mut mismatches = Vec::new();
+    for (solution, batch_score) in solutions.iter().zip(compute_scores(guess, solutions)) {
+        let expected = reference_score(guess, solution);
+        let score = compute_score(guess, solution);
+        if score != expected {
+            mismatches.push(format!(
+                "compute_score({}, {}) = {}, expected {}",
+                guess, solution, score, expected
+            ));
+        }
+        if batch_score != expected {
+            mismatches.push(format!(
+                "compute_scores({}, [{}]) = {}, expected {}",
+                guess, solution, batch_score, expected
+            ));
+        }
+        if !is_consistent(guess, expected, solution) {
+            mismatches.push(format!(
+                "is_consistent({}, {}, {}) is false",
+                guess, expected, solution
+            ));
+        }
+    }
+    mismatches
+}
+
+/// `wordle-solver verify-scores`: check the optimized scoring code against a slow reference
+/// implementation for every guess and solution.
+pub fn scores_command(args: Vec<String>) {
+    let mut guessable_path = String::new();
+    let mut solutions_path = String::new();
+    let mut thread_count = default_thread_count();
+
+    {
+        let mut parser = ArgumentParser::new();
+        parser.set_description(concat!(
+            "Score every guess against every solution with both the optimized scoring code and ",
+            "a slow reference implementation, and report any differences"
+        ));
+        parser.refer(&mut guessable_path).required().add_argument(
+            "guessable",
+            Store,
+            "The guessable list",
+        );
+        parser.refer(&mut solutions_path).required().add_argument(
+            "solutions",
+            Store,
+            "The solutions list",
+        );
+        parser.refer(&mut thread_count).add_option(
+            &["--thread-count"],
+            Store,
+            "Number of threads to use (default: one per CPU)",
+        );
+        if let Err(code) = parser.parse(args, &mut stdout(), &mut stderr()) {
+            std::process::exit(code);
+        }
+    }
+
+    let [guessable, solutions] = [guessable_path, solutions_path].map(|path| {
+        load_list_from_file(path.as_ref()).unwrap_or_else(|e| {
+            eprintln!("{}: {}", path, e);
+            std::process::exit(1);
+        })
+    });
+    let guesses: Vec<&str> = solutions
+        .iter()
+        .chain(guessable.iter())
+        .map(String::as_str)
+        .collect();
+    let solutions: Vec<&str> = solutions.iter().map(String::as_str).collect();
+
+    let next_index = AtomicUsize::new(0);
+    let mut mismatches: Vec<(usize, Vec<String>)> = std::thread::scope(|scope| {
+        let threads: Vec<_> = (0..thread_count.max(1))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut mismatches = Vec::new();
+                    loop {
+                        let index = next_index.fetch_add(1, Ordering::Relaxed);
+                        let Some(guess) = guesses.get(index) else {
+                            break;
+                        };
+                        let found = check_scores(guess, &solutions);
+                        if !found.is_empty() {
+                            mismatches.push((index, found));
+                        }
+                    }
+                    mismatches
+                })
+            })
+            .collect();
+        threads
+            .into_iter()
+            .flat_map(|thread| thread.join().unwrap())
+            .collect()
+    });
+    mismatches.sort();
+    let mismatches: Vec<String> = mismatches.into_iter().flat_map(|(_, m)| m).collect();
+
+    println!(
+        "{} guesses x {} solutions: {} mismatches",
+        guesses.len(),
+        solutions.len(),
+        mismatches.len()
+    );
+    for line in mismatches.iter().take(MAX_LISTED_MISMATCHES) {
+        println!("  {}", line);
+    }
+    if mismatches.len() > MAX_LISTED_MISMATCHES {
+        println!(
+            "  ... and {} more",
+            mismatches.len() - MAX_LISTED_MISMATCHES
+        );
+    }
+
+    if !mismatches.is_empty() {
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_game() {
+        let solutions = ["cigar", "rebut", "sissy", "humph", "awake", "blush"].map(String::from);
+        let guessable = ["eerie"].map(String::from);
+        let solver = Solver::new(&guessable, &solutions, true, false, Strategy::GroupSize);
+        let (moves, violation) = check_game(solver.clone(), "humph", "blush", true);
+        assert_eq!(violation, None);
+        assert_eq!(moves.last().unwrap().0, "blush");
+
+        // "eerie" doesn't have the "u" and "h" that "humph" found.
+        let mut after_humph = solver.clone();
+        after_humph.respond_to_score("humph", compute_score("humph", "blush"));
+        let (_, violation) = check_game(after_humph, "eerie", "blush", true);
+        assert_eq!(violation, Some(Violation::IllegalGuess(1)));
+
+        // A wrong score eliminates the answer. Scoring "cigar" against it leaves no possibilities,
+        // which is reported rather than left to the solver, which would panic.
+        let mut wrong = solver;
+        wrong.respond_to_score("humph", compute_score("humph", "sissy"));
+        let (moves, violation) = check_game(wrong, "cigar", "blush", false);
+        assert_eq!(violation, Some(Violation::AnswerEliminated(1)));
+        assert_eq!(moves.last().unwrap().0, "cigar");
+    }
+
+    #[test]
+    fn test_check_scores() {
+        let solutions = [
+            "cigar", "rebut", "sissy", "humph", "awake", "blush", "señor",
+        ];
+        for guess in ["sissy", "eerie", "niños"] {
+            assert_eq!(check_scores(guess, &solutions), Vec::<String>::new());
+        }
+    }
+}